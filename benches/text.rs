@@ -0,0 +1,155 @@
+use std::{hint::black_box, path::PathBuf};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use notepad_minus_minus::models::text::{Caret, TextFile};
+
+const SIZES: [usize; 3] = [10_000, 100_000, 1_000_000];
+
+fn generate(lines: usize) -> TextFile {
+    let content = (0..lines)
+        .map(|i| format!("line {i}: the quick brown fox jumps over the lazy dog\n"))
+        .collect::<String>();
+
+    TextFile::from_content(PathBuf::from("bench.txt"), &content)
+}
+
+fn caret_conversions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("caret_conversions");
+
+    for lines in SIZES {
+        let mut text = generate(lines);
+        let middle = text.rope.len_chars() / 2;
+        text.char_idx = middle;
+
+        group.bench_with_input(BenchmarkId::new("get_caret", lines), &text, |b, text| {
+            b.iter(|| black_box(text.get_caret()))
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("get_caret_from_idx", lines),
+            &text,
+            |b, text| b.iter(|| black_box(text.get_caret_from_idx(black_box(middle)))),
+        );
+
+        let caret = Caret::from(lines / 2, 10);
+        group.bench_with_input(BenchmarkId::new("get_char_idx", lines), &text, |b, text| {
+            b.iter(|| black_box(text.get_char_idx(black_box(caret))))
+        });
+    }
+
+    group.finish();
+}
+
+fn caret_movement(c: &mut Criterion) {
+    let mut group = c.benchmark_group("caret_movement");
+
+    for lines in SIZES {
+        let mut text = generate(lines);
+        text.set_caret_position(lines / 2, 10, false);
+
+        group.bench_function(BenchmarkId::new("down_up", lines), |b| {
+            b.iter(|| {
+                text.caret_move_down();
+                text.caret_move_up();
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("line_end_start", lines), |b| {
+            b.iter(|| {
+                text.caret_move_line_end(false);
+                text.caret_move_line_start(false);
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("right_left", lines), |b| {
+            b.iter(|| {
+                text.caret_move_right(false);
+                text.caret_move_left(false);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn editing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("editing");
+
+    for lines in SIZES {
+        group.bench_function(BenchmarkId::new("insert_char", lines), |b| {
+            let mut text = generate(lines);
+            text.set_caret_position(lines / 2, 10, false);
+
+            b.iter(|| text.insert_char(black_box('x')))
+        });
+    }
+
+    group.finish();
+}
+
+fn search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("search");
+    group.sample_size(10);
+
+    for lines in SIZES {
+        let text = generate(lines);
+
+        group.bench_with_input(BenchmarkId::new("find_all", lines), &text, |b, text| {
+            b.iter(|| black_box(text.find_all(black_box("lazy"), false)))
+        });
+
+        group.bench_function(BenchmarkId::new("insert_and_find_all", lines), |b| {
+            let mut text = generate(lines);
+            text.set_caret_position(lines / 2, 10, false);
+            text.find_all("lazy", false);
+
+            b.iter(|| {
+                text.insert_char(black_box('x'));
+                black_box(text.find_all("lazy", false))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn wrapping(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wrapping");
+
+    for lines in SIZES {
+        let mut text = generate(lines);
+        text.wrap_width = Some(20);
+
+        // the rows of a screen of lines, as every render asks for them
+        group.bench_function(BenchmarkId::new("visible_rows", lines), |b| {
+            b.iter(|| {
+                (lines / 2..lines / 2 + 50)
+                    .map(|ln| text.wrapped_line(ln).rows())
+                    .sum::<usize>()
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("insert_and_visible_rows", lines), |b| {
+            text.set_caret_position(lines / 2, 10, false);
+
+            b.iter(|| {
+                text.insert_char(black_box('x'));
+                (lines / 2..lines / 2 + 50)
+                    .map(|ln| text.wrapped_line(ln).rows())
+                    .sum::<usize>()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    caret_conversions,
+    caret_movement,
+    editing,
+    search,
+    wrapping
+);
+criterion_main!(benches);
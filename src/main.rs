@@ -1,25 +1,32 @@
-pub mod models;
-pub mod views;
-
 use dioxus::desktop::window;
-use models::file_system::FileSystem;
-use models::panels::ShownPanels;
-use models::tabs::Tabs;
+use notepad_minus_minus::models::clipboard::ClipboardRing;
+use notepad_minus_minus::models::file_system::FileSystem;
+use notepad_minus_minus::models::macros::Macros;
+use notepad_minus_minus::models::panels::ShownPanels;
+use notepad_minus_minus::models::search::Search;
+use notepad_minus_minus::models::tabs::{Split, Tabs};
+use notepad_minus_minus::models::theme::Themes;
+use notepad_minus_minus::views::dialogs::fs_operations::OperationDialogHandler;
+use notepad_minus_minus::views::editor::Editor;
+use notepad_minus_minus::views::file_explorer::context_menu::{
+    RightClickMenu, RightClickMenuHandler,
+};
+use notepad_minus_minus::views::file_explorer::file_explorer::FileExplorer;
+use notepad_minus_minus::views::sessionexplorer::SessionsExplorer;
+use notepad_minus_minus::views::side_panel::SidePanel;
+use notepad_minus_minus::views::tabs::EditorTabs;
+use notepad_minus_minus::views::{
+    edit_history::EditHistory, find_in_files::FindInFiles, find_replace::FindReplace,
+};
 use std::rc::Rc;
 use tracing::info;
-use views::dialogs::fs_operations::OperationDialogHandler;
-use views::editor::Editor;
-use views::file_explorer::context_menu::{RightClickMenu, RightClickMenuHandler};
-use views::file_explorer::file_explorer::FileExplorer;
-use views::sessionexplorer::SessionsExplorer;
-use views::side_panel::SidePanel;
-use views::tabs::EditorTabs;
-use views::{edit_history::EditHistory, find_replace::FindReplace};
 
-use crate::views::dialogs::error::{ErrorDialog, ErrorDialogHandler};
+use notepad_minus_minus::views::dialogs::error::{ErrorDialog, ErrorDialogHandler};
 
 use dioxus::prelude::*;
-use views::terminal::Terminal;
+use notepad_minus_minus::views::macros::MacrosPanel;
+use notepad_minus_minus::views::terminal::Terminal;
+use notepad_minus_minus::views::theme_switcher::ThemeSwitcher;
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
 const MAIN_CSS: Asset = asset!("/assets/main.css");
@@ -37,6 +44,10 @@ pub fn Layout() -> Element {
         use_context_provider(|| Signal::new(RightClickMenuHandler::new()));
     let _ = use_context_provider(|| Signal::new(FileSystem::new()));
     let _ = use_context_provider(OperationDialogHandler::new);
+    let _ = use_context_provider(|| Signal::new(Search::new()));
+    let themes = use_context_provider(|| Signal::new(Themes::load()));
+    let _ = use_context_provider(|| Signal::new(Macros::load()));
+    let _ = use_context_provider(|| Signal::new(ClipboardRing::load()));
 
     let tabs = use_signal(Tabs::new);
 
@@ -85,9 +96,22 @@ pub fn Layout() -> Element {
         document::Link { rel: "icon", href: FAVICON }
         document::Link { rel: "stylesheet", href: MAIN_CSS }
         document::Link { rel: "stylesheet", href: BOOTSTRAP_CSS }
+        style { {themes.read().active().to_css()} }
 
         div {
             style: "display: flex; flex-direction: row; width: 100vw ; height: 100vh;",
+            onkeydown: move |e| {
+                let ctrl = e.modifiers().contains(Modifiers::CONTROL);
+                let shift = e.modifiers().contains(Modifiers::SHIFT);
+
+                match (e.key(), ctrl, shift) {
+                    (Key::Character(t), true, true) if t.eq_ignore_ascii_case("t") => {
+                        let mut themes_panel = shown_panels.themes;
+                        themes_panel.toggle();
+                    }
+                    (_, _, _) => {}
+                }
+            },
             SidePanel {shown_panels}
             div {
                 style: "display: flex; flex-direction: column; flex: 1; max-height: 100%; overflow: hidden",
@@ -104,9 +128,12 @@ pub fn Layout() -> Element {
                         style: "display: flex; flex-direction: row; overflow: hidden;",
                         display: if
                             !*shown_panels.search.read() &&
+                            !*shown_panels.find_in_files.read() &&
                             !*shown_panels.file_tree.read() &&
                             !*shown_panels.sessions.read() &&
-                            !*shown_panels.history.read() {"none"} else {"flex"},
+                            !*shown_panels.history.read() &&
+                            !*shown_panels.themes.read() &&
+                            !*shown_panels.macros.read() {"none"} else {"flex"},
                         LeftPanel {
                             tabs,
                             width: left_panel_width,
@@ -147,7 +174,7 @@ pub fn Layout() -> Element {
 pub fn Divider() -> Element {
     rsx! {
         div {
-            style: "display: flex; min-height: 3px; background-color: rgb(139, 139, 139); width: 100%;",
+            style: "display: flex; min-height: 3px; background-color: var(--chrome-separator); width: 100%;",
         }
     }
 }
@@ -171,27 +198,68 @@ pub fn LeftPanel(tabs: Signal<Tabs>, width: Signal<i32>, shown_panels: ShownPane
                 Divider {}
             }
             div {
-                style: "display: flex; flex-direction: column; flex: 1; background-color: yellow;",
+                style: "display: flex; flex-direction: column; flex: 1; background-color: var(--chrome-panel);",
                 display: if !*shown_panels.search.read() {"none"} else {"flex"},
                 FindReplace { tabs }
                 Divider {}
             }
+            div {
+                style: "display: flex; flex-direction: column; flex: 1; overflow: hidden; min-height: 250px;",
+                display: if !*shown_panels.find_in_files.read() {"none"} else {"flex"},
+                FindInFiles { tabs }
+                Divider {}
+            }
             div {
                 style: "display: flex; flex-direction: column; flex: 1; max-height: 100%; overflow: hidden; min-height: 250px;",
                 display: if !*shown_panels.history.read() {"none"} else {"flex"},
                 EditHistory {tabs}
             }
+            div {
+                style: "display: flex; flex-direction: column; flex: 1; overflow: hidden; min-height: 250px;",
+                display: if !*shown_panels.themes.read() {"none"} else {"flex"},
+                ThemeSwitcher {}
+            }
+            div {
+                style: "display: flex; flex-direction: column; flex: 1; overflow: hidden; min-height: 250px;",
+                display: if !*shown_panels.macros.read() {"none"} else {"flex"},
+                MacrosPanel {tabs}
+            }
         }
     }
 }
 
 #[component]
 pub fn RightPanel(tabs: Signal<Tabs>) -> Element {
+    let (direction, border) = match tabs.read().split {
+        Split::Vertical => ("row", "border-left"),
+        Split::Horizontal => ("column", "border-top"),
+    };
+    let panes = tabs
+        .read()
+        .panes
+        .iter()
+        .enumerate()
+        .map(|(i, pane)| match i {
+            0 => (pane.id, String::new()),
+            _ => (
+                pane.id,
+                format!("{border}: 2px solid var(--chrome-separator);"),
+            ),
+        })
+        .collect::<Vec<_>>();
+
     rsx! {
         div {
-            style: "display: flex; flex-direction: column; background-color: #ddd; flex: 1;overflow: hidden;",
-            EditorTabs {tabs}
-            Editor {tabs}
+            style: "display: flex; flex-direction: {direction}; flex: 1; overflow: hidden;",
+            for (pane, separator) in panes {
+                div {
+                    key: "{pane}",
+                    style: "display: flex; flex-direction: column; background-color: var(--editor-empty-background); flex: 1; overflow: hidden;
+                            min-width: 0; min-height: 0; {separator}",
+                    EditorTabs {tabs, pane}
+                    Editor {tabs, pane}
+                }
+            }
         }
     }
 }
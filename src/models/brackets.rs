@@ -0,0 +1,82 @@
+use ropey::Rope;
+
+use super::syntax::languages::Language;
+
+// matching stops looking after this many chars so huge files stay responsive
+const MAX_SCAN: usize = 100_000;
+
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+pub fn closing_bracket(open: char) -> Option<char> {
+    BRACKETS
+        .iter()
+        .find(|(o, _)| *o == open)
+        .map(|(_, close)| *close)
+}
+
+// brackets always pair up, quotes only where the language has strings delimited by them
+pub fn auto_pairs(language: Option<Language>) -> Vec<(char, char)> {
+    let mut pairs = BRACKETS.to_vec();
+
+    let quotes = match language {
+        Some(language) => language
+            .grammar()
+            .strings
+            .iter()
+            .filter(|rule| rule.open == rule.close && !rule.char_literal)
+            .filter_map(|rule| {
+                let mut chars = rule.open.chars();
+                chars.next().filter(|_| chars.next().is_none())
+            })
+            .collect(),
+        None => vec!['"'],
+    };
+
+    for quote in quotes {
+        if !pairs.contains(&(quote, quote)) {
+            pairs.push((quote, quote));
+        }
+    }
+
+    pairs
+}
+
+// the bracket at `idx` and the one it pairs with
+pub fn matching_bracket(rope: &Rope, idx: usize) -> Option<usize> {
+    let c = rope.get_char(idx)?;
+
+    if let Some(close) = closing_bracket(c) {
+        let mut depth = 0;
+
+        for (i, other) in rope.chars_at(idx).enumerate().take(MAX_SCAN) {
+            match other {
+                other if other == c => depth += 1,
+                other if other == close => depth -= 1,
+                _ => continue,
+            }
+
+            if depth == 0 {
+                return Some(idx + i);
+            }
+        }
+
+        return None;
+    }
+
+    let (open, _) = BRACKETS.iter().find(|(_, close)| *close == c)?;
+    let mut depth = 0;
+
+    for (i, other) in rope.chars_at(idx + 1).reversed().enumerate().take(MAX_SCAN) {
+        match other {
+            other if other == c => depth += 1,
+            other if other == *open => depth -= 1,
+            _ => continue,
+        }
+
+        if depth == 0 {
+            return Some(idx - i);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,132 @@
+use std::{fs, io};
+
+use arboard::Clipboard;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{permissions::write_private, theme::config_dir};
+
+const CLIPBOARD_FILE: &str = "clipboard.json";
+const CAPACITY: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipEntry {
+    pub text: String,
+    // whole lines, like the output of cut_line, are pasted at the start of the caret line
+    pub linewise: bool,
+}
+
+impl ClipEntry {
+    // a single line preview for the picker
+    pub fn preview(&self) -> String {
+        let text = self.text.trim();
+        let first = text.lines().next().unwrap_or_default();
+        let more = text.lines().count().saturating_sub(1);
+
+        match more {
+            0 => first.to_string(),
+            more => format!("{first} (+{more} lines)"),
+        }
+    }
+}
+
+// the last copies and cuts, newest first, the newest one is also on the system clipboard
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ClipboardRing {
+    pub entries: Vec<ClipEntry>,
+    pub error: Option<String>,
+    // copies can be passwords, they are only written to disk once this is turned on
+    pub persistent: bool,
+}
+
+impl ClipboardRing {
+    pub fn load() -> Self {
+        let mut ring = Self::default();
+
+        // the file only exists while the history is kept
+        let Some(content) =
+            config_dir().and_then(|dir| fs::read_to_string(dir.join(CLIPBOARD_FILE)).ok())
+        else {
+            return ring;
+        };
+        ring.persistent = true;
+
+        match serde_json::from_str(&content) {
+            Ok(entries) => ring.entries = entries,
+            Err(e) => ring.error = Some(format!("{CLIPBOARD_FILE}: {e}")),
+        }
+
+        ring
+    }
+
+    pub fn copy(&mut self, text: String, linewise: bool) {
+        if text.is_empty() {
+            return;
+        }
+
+        match Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text.clone())) {
+            Ok(()) => info!("copied {} chars to clipboard", text.chars().count()),
+            Err(e) => info!("failed to copy to clipboard: {}", e),
+        }
+
+        self.push(ClipEntry { text, linewise });
+    }
+
+    // copying an entry again moves it to the front instead of keeping it twice
+    pub fn push(&mut self, entry: ClipEntry) {
+        self.entries.retain(|e| e.text != entry.text);
+        self.entries.insert(0, entry);
+        self.entries.truncate(CAPACITY);
+
+        self.error = self.persist().err().map(|e| e.to_string());
+    }
+
+    // what Ctrl+V pastes, text copied in another application is never linewise
+    pub fn current(&self) -> Option<ClipEntry> {
+        let system = Clipboard::new().and_then(|mut clipboard| clipboard.get_text());
+
+        match system {
+            Ok(text) => Some(
+                self.entries
+                    .iter()
+                    .find(|e| e.text == text)
+                    .cloned()
+                    .unwrap_or(ClipEntry {
+                        text,
+                        linewise: false,
+                    }),
+            ),
+            Err(_) => self.entries.first().cloned(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.error = self.persist().err().map(|e| e.to_string());
+    }
+
+    pub fn set_persistent(&mut self, persistent: bool) {
+        self.persistent = persistent;
+
+        let result = match persistent {
+            true => self.persist(),
+            false => match config_dir().map(|dir| fs::remove_file(dir.join(CLIPBOARD_FILE))) {
+                Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        };
+
+        self.error = result.err().map(|e| e.to_string());
+    }
+
+    fn persist(&self) -> io::Result<()> {
+        let Some(dir) = config_dir().filter(|_| self.persistent) else {
+            return Ok(());
+        };
+
+        let content = serde_json::to_string_pretty(&self.entries)?;
+
+        fs::create_dir_all(&dir)?;
+        write_private(&dir.join(CLIPBOARD_FILE), content.as_bytes())
+    }
+}
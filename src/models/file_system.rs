@@ -1,4 +1,5 @@
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
@@ -23,6 +24,7 @@ impl PartialEq for FileSystemItem {
 pub struct FileSystem {
     root: Option<Directory>,
     focused_fs_item: Option<PathBuf>,
+    dragged_fs_item: Option<PathBuf>,
 }
 
 impl Default for FileSystem {
@@ -36,6 +38,7 @@ impl FileSystem {
         FileSystem {
             root: None,
             focused_fs_item: None,
+            dragged_fs_item: None,
         }
     }
 
@@ -44,6 +47,7 @@ impl FileSystem {
         FileSystem {
             root: Some(root_directory),
             focused_fs_item: None,
+            dragged_fs_item: None,
         }
     }
 
@@ -73,6 +77,55 @@ impl FileSystem {
         self.focused_fs_item = None;
     }
 
+    pub fn start_drag(&mut self, path: &Path) {
+        self.dragged_fs_item = Some(path.to_path_buf());
+    }
+
+    pub fn take_dragged(&mut self) -> Option<PathBuf> {
+        self.dragged_fs_item.take()
+    }
+
+    // returns the new path of the item, which is unchanged when it already lives in `directory`
+    pub fn move_item(&mut self, path: &Path, directory: &Path) -> io::Result<PathBuf> {
+        let Some(name) = path.file_name() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot move the root of the file system",
+            ));
+        };
+
+        if directory.starts_with(path) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Cannot move {} into itself", path.display()),
+            ));
+        }
+
+        let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        if parent == directory {
+            return Ok(path.to_path_buf());
+        }
+
+        let new_path = directory.join(name);
+        if new_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", new_path.display()),
+            ));
+        }
+
+        fs::rename(path, &new_path)?;
+
+        self.reload_directory(&parent);
+        self.reload_directory(&directory.to_path_buf());
+
+        if self.focused_fs_item.as_deref() == Some(path) {
+            self.focused_fs_item = Some(new_path.clone());
+        }
+
+        Ok(new_path)
+    }
+
     pub fn reload(&mut self) {
         if let Some(root) = &mut self.root {
             root.reload_children();
@@ -0,0 +1,362 @@
+use std::{
+    cell::{Ref, RefCell},
+    cmp::{max, min},
+    collections::{BTreeMap, BTreeSet},
+};
+
+use itertools::Itertools;
+use ropey::{Rope, RopeSlice};
+
+const TAB_WIDTH: usize = 4;
+const COMMENT_PREFIXES: [&str; 6] = ["//", "#", "--", "/*", "<!--", ";"];
+
+const CHECKPOINT_LINES: usize = 256;
+
+// regions are keyed by the line that stays visible and map to the last line they hide
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Folding {
+    regions: RefCell<Regions>,
+    folded: BTreeSet<usize>,
+}
+
+// indentation gives the default regions, brackets and region markers are more precise and win
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Indented,
+    Bracketed,
+    Marked,
+}
+
+// what the scan still has open before it reads line `ln`
+#[derive(Debug, Clone, PartialEq, Default)]
+struct Scan {
+    ln: usize,
+    open_indents: Vec<(usize, usize)>,
+    last_text_line: usize,
+    open_brackets: Vec<(char, usize)>,
+    open_markers: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Change {
+    ln: usize,
+    kind: Kind,
+    start: usize,
+    previous: Option<usize>,
+}
+
+// the regions are found from the top and only as far as they are asked for, like the lines of
+// the highlighter, an edit takes back what was found from the checkpoint above it on
+#[derive(Debug, Clone, PartialEq, Default)]
+struct Regions {
+    scan: Scan,
+    finished: bool,
+    // the scan before every `CHECKPOINT_LINES` lines
+    checkpoints: Vec<Scan>,
+    ends: [BTreeMap<usize, usize>; 3],
+    // every change to `ends` with the line it was found on
+    changes: Vec<Change>,
+}
+
+impl Scan {
+    fn is_open(&self, start: usize) -> bool {
+        self.open_indents.iter().any(|(_, s)| *s == start)
+            || self.open_brackets.iter().any(|(_, s)| *s == start)
+            || self.open_markers.contains(&start)
+    }
+
+    // the stacks hold their start lines in order, so the first ones are the oldest
+    fn oldest_open(&self) -> Option<usize> {
+        [
+            self.open_indents.first().map(|(_, s)| *s),
+            self.open_brackets.first().map(|(_, s)| *s),
+            self.open_markers.first().copied(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+}
+
+impl Regions {
+    fn end(&self, start: usize) -> Option<usize> {
+        [Kind::Marked, Kind::Bracketed, Kind::Indented]
+            .iter()
+            .find_map(|&kind| self.ends[kind as usize].get(&start).copied())
+    }
+
+    // region starts up to `ln`, from the last one up
+    fn starts_until(&self, ln: usize) -> impl Iterator<Item = usize> + '_ {
+        self.ends
+            .iter()
+            .map(|ends| ends.range(..=ln).rev().map(|(start, _)| *start))
+            .kmerge_by(|a, b| a > b)
+            .dedup()
+    }
+
+    fn set(&mut self, kind: Kind, start: usize, end: usize) {
+        let previous = self.ends[kind as usize].insert(start, end);
+
+        self.changes.push(Change {
+            ln: self.scan.ln,
+            kind,
+            start,
+            previous,
+        });
+    }
+
+    // scans on until `done` or the end of the file
+    fn scan_until(&mut self, rope: &Rope, done: impl Fn(&Scan) -> bool) {
+        let lines = max(rope.len_lines(), 2) - 1;
+
+        while !self.finished && !done(&self.scan) {
+            if self.scan.ln == lines {
+                self.finish();
+                break;
+            }
+
+            if self.scan.ln == self.checkpoints.len() * CHECKPOINT_LINES {
+                self.checkpoints.push(self.scan.clone());
+            }
+
+            self.scan_line(rope.line(self.scan.ln));
+            self.scan.ln += 1;
+        }
+    }
+
+    fn scan_line(&mut self, line: RopeSlice) {
+        let ln = self.scan.ln;
+
+        if let Some(indent) = indent_of(line) {
+            while let Some(&(open_indent, start)) = self.scan.open_indents.last() {
+                if open_indent < indent {
+                    break;
+                }
+
+                self.scan.open_indents.pop();
+                if self.scan.last_text_line > start {
+                    self.set(Kind::Indented, start, self.scan.last_text_line);
+                }
+            }
+
+            self.scan.open_indents.push((indent, ln));
+            self.scan.last_text_line = ln;
+        }
+
+        for c in line.chars() {
+            match c {
+                '{' => self.scan.open_brackets.push(('}', ln)),
+                '[' => self.scan.open_brackets.push((']', ln)),
+                '(' => self.scan.open_brackets.push((')', ln)),
+                '}' | ']' | ')' => {
+                    let Some(i) = self
+                        .scan
+                        .open_brackets
+                        .iter()
+                        .rposition(|(close, _)| *close == c)
+                    else {
+                        continue;
+                    };
+
+                    // the closing line stays visible, a later one closing on the same line wins
+                    let start = self.scan.open_brackets[i].1;
+                    self.scan.open_brackets.truncate(i);
+
+                    if ln > start + 1 {
+                        self.set(Kind::Bracketed, start, ln - 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        match marker(line) {
+            Some(true) => self.scan.open_markers.push(ln),
+            Some(false) => {
+                if let Some(start) = self.scan.open_markers.pop() {
+                    self.set(Kind::Marked, start, ln);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn finish(&mut self) {
+        for (_, start) in std::mem::take(&mut self.scan.open_indents) {
+            if self.scan.last_text_line > start {
+                self.set(Kind::Indented, start, self.scan.last_text_line);
+            }
+        }
+
+        self.finished = true;
+    }
+
+    // goes back to the checkpoint above `first_line`, what was found below it may have changed
+    fn edit(&mut self, first_line: usize) {
+        // nothing was read from there yet
+        if !self.finished && first_line >= self.scan.ln {
+            return;
+        }
+
+        let Some(checkpoint) = self
+            .checkpoints
+            .len()
+            .checked_sub(1)
+            .map(|last| min(first_line / CHECKPOINT_LINES, last))
+        else {
+            return;
+        };
+
+        self.checkpoints.truncate(checkpoint + 1);
+        self.scan = self.checkpoints[checkpoint].clone();
+        self.finished = false;
+
+        while let Some(change) = self.changes.pop_if(|change| change.ln >= self.scan.ln) {
+            let ends = &mut self.ends[change.kind as usize];
+
+            match change.previous {
+                Some(end) => ends.insert(change.start, end),
+                None => ends.remove(&change.start),
+            };
+        }
+    }
+}
+
+impl Folding {
+    // the regions known once the scan is `done`
+    fn regions(&self, rope: &Rope, done: impl Fn(&Scan) -> bool) -> Ref<'_, Regions> {
+        self.regions.borrow_mut().scan_until(rope, done);
+        self.regions.borrow()
+    }
+
+    pub fn region_end(&self, rope: &Rope, ln: usize) -> Option<usize> {
+        self.regions(rope, |scan| scan.ln > ln && !scan.is_open(ln))
+            .end(ln)
+    }
+
+    // the innermost region around a line
+    pub fn region_at(&self, rope: &Rope, ln: usize) -> Option<usize> {
+        let regions = self.regions(rope, |scan| {
+            scan.ln > ln && scan.oldest_open().is_none_or(|start| start > ln)
+        });
+
+        let start = regions
+            .starts_until(ln)
+            .find(|start| regions.end(*start).is_some_and(|end| end >= ln));
+        start
+    }
+
+    pub fn is_folded(&self, rope: &Rope, ln: usize) -> bool {
+        self.folded.contains(&ln) && self.region_end(rope, ln).is_some()
+    }
+
+    pub fn fold(&mut self, rope: &Rope, ln: usize) {
+        if self.region_end(rope, ln).is_some() {
+            self.folded.insert(ln);
+        }
+    }
+
+    pub fn unfold(&mut self, ln: usize) {
+        self.folded.remove(&ln);
+    }
+
+    pub fn toggle(&mut self, rope: &Rope, ln: usize) {
+        match self.folded.contains(&ln) {
+            true => self.unfold(ln),
+            false => self.fold(rope, ln),
+        }
+    }
+
+    pub fn fold_all(&mut self, rope: &Rope) {
+        let folded = self
+            .regions(rope, |_| false)
+            .starts_until(usize::MAX)
+            .collect();
+        self.folded = folded;
+    }
+
+    pub fn unfold_all(&mut self) {
+        self.folded.clear();
+    }
+
+    // the outermost folded region hiding a line
+    pub fn hidden_by(&self, rope: &Rope, ln: usize) -> Option<usize> {
+        self.folded
+            .range(..ln)
+            .find(|start| self.region_end(rope, **start).is_some_and(|end| end >= ln))
+            .copied()
+    }
+
+    pub fn visible_line(&self, rope: &Rope, ln: usize) -> usize {
+        self.hidden_by(rope, ln).unwrap_or(ln)
+    }
+
+    pub fn next_visible(&self, rope: &Rope, ln: usize) -> usize {
+        let ln = self.visible_line(rope, ln);
+
+        match self.folded.contains(&ln) {
+            true => self.region_end(rope, ln).unwrap_or(ln) + 1,
+            false => ln + 1,
+        }
+    }
+
+    pub fn reveal(&mut self, rope: &Rope, ln: usize) {
+        while let Some(start) = self.hidden_by(rope, ln) {
+            self.folded.remove(&start);
+        }
+    }
+
+    // the lines from `first_line` on were edited, folds follow the lines they start on,
+    // `line_moved` gives the new line or drops the fold
+    pub fn edit(&mut self, first_line: usize, line_moved: impl Fn(usize) -> Option<usize>) {
+        self.regions.get_mut().edit(first_line);
+
+        self.folded = self
+            .folded
+            .iter()
+            .filter_map(|&ln| line_moved(ln))
+            .collect();
+    }
+}
+
+// blank lines have no indentation of their own
+fn indent_of(line: RopeSlice) -> Option<usize> {
+    let mut indent = 0;
+
+    for c in line.chars() {
+        match c {
+            ' ' => indent += 1,
+            '\t' => indent += TAB_WIDTH,
+            '\n' | '\r' => return None,
+            _ => return Some(indent),
+        }
+    }
+
+    None
+}
+
+// `// region`, `#region`, `// #endregion`, `<!-- region -->`, ...
+fn marker(line: RopeSlice) -> Option<bool> {
+    let first = line.chars().find(|c| !c.is_whitespace())?;
+    if !"/#-<;".contains(first) {
+        return None;
+    }
+
+    let line = line.to_string();
+    let rest = COMMENT_PREFIXES
+        .iter()
+        .find_map(|prefix| line.trim_start().strip_prefix(prefix))?
+        .trim_start();
+    let rest = rest.strip_prefix('#').unwrap_or(rest);
+
+    let is_word = |word: &str| {
+        rest.strip_prefix(word)
+            .is_some_and(|after| !after.starts_with(|c: char| c.is_alphanumeric()))
+    };
+
+    match (is_word("region"), is_word("endregion")) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
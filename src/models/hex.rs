@@ -0,0 +1,245 @@
+use std::{
+    cmp::min,
+    fs::{self, File},
+    io::{self, Read},
+    path::Path,
+};
+
+use super::{transform::hex_byte, workspace_search::write_atomically};
+
+pub const BYTES_PER_ROW: usize = 16;
+// only the start of a file is looked at to tell text from binary
+const SNIFF_SIZE: usize = 8192;
+
+pub fn is_binary(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+
+    let mut head = Vec::new();
+    match file.take(SNIFF_SIZE as u64).read_to_end(&mut head) {
+        Ok(_) => looks_binary(&head),
+        Err(_) => false,
+    }
+}
+
+// text has no NUL bytes and decodes as utf-8, except for a char cut off at the end
+pub fn looks_binary(head: &[u8]) -> bool {
+    if head.contains(&0) {
+        return true;
+    }
+
+    match std::str::from_utf8(head) {
+        Ok(_) => false,
+        Err(e) => e.error_len().is_some(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pane {
+    Hex,
+    Ascii,
+}
+
+// a byte pattern to search for, `None` matches any byte
+pub type Pattern = Vec<Option<u8>>;
+
+// hex bytes like `4d 5a ?? 00` or `4D5A`, or ascii text in double quotes
+pub fn parse_pattern(pattern: &str) -> Result<Pattern, String> {
+    let pattern = pattern.trim();
+
+    if let Some(text) = pattern
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        return Ok(text.bytes().map(Some).collect());
+    }
+
+    let mut bytes = Vec::new();
+
+    for token in pattern.split_whitespace() {
+        if token.len() % 2 != 0 {
+            return Err(format!("`{token}` has an odd number of digits"));
+        }
+
+        for i in (0..token.len()).step_by(2) {
+            let pair = token.get(i..i + 2).ok_or(format!("`{token}` is not hex"))?;
+
+            bytes.push(match pair {
+                "??" => None,
+                pair => Some(hex_byte(pair).ok_or(format!("`{pair}` is not hex"))?),
+            });
+        }
+    }
+
+    Ok(bytes)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HexFile {
+    pub bytes: Vec<u8>,
+    // may be one past the last byte, typing there appends
+    pub cursor: usize,
+    // the next hex digit goes into the low half of the byte under the cursor
+    pub low_nibble: bool,
+    pub pane: Pane,
+    pub scroll_row: usize,
+    pub dirty: bool,
+    pub read_only: bool,
+}
+
+impl HexFile {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Ok(Self::from_bytes(fs::read(path)?))
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            cursor: 0,
+            low_nibble: false,
+            pane: Pane::Hex,
+            scroll_row: 0,
+            dirty: false,
+            read_only: false,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.bytes.len() / BYTES_PER_ROW + 1
+    }
+
+    pub fn row(&self, row: usize) -> &[u8] {
+        let start = min(row * BYTES_PER_ROW, self.bytes.len());
+        let end = min(start + BYTES_PER_ROW, self.bytes.len());
+
+        &self.bytes[start..end]
+    }
+
+    pub fn move_by(&mut self, delta: isize) {
+        self.cursor = self
+            .cursor
+            .saturating_add_signed(delta)
+            .min(self.bytes.len());
+        self.low_nibble = false;
+    }
+
+    pub fn move_to(&mut self, offset: usize) {
+        self.cursor = min(offset, self.bytes.len());
+        self.low_nibble = false;
+    }
+
+    pub fn toggle_pane(&mut self) {
+        self.pane = match self.pane {
+            Pane::Hex => Pane::Ascii,
+            Pane::Ascii => Pane::Hex,
+        };
+        self.low_nibble = false;
+    }
+
+    // overwrites half of the byte under the cursor, false when `c` is not a hex digit
+    pub fn type_hex(&mut self, c: char) -> bool {
+        let Some(digit) = c.to_digit(16).map(|digit| digit as u8) else {
+            return false;
+        };
+
+        let low_nibble = self.low_nibble;
+        let byte = self.byte_at_cursor();
+        *byte = match low_nibble {
+            false => digit << 4 | *byte & 0x0f,
+            true => *byte & 0xf0 | digit,
+        };
+
+        self.dirty = true;
+        match self.low_nibble {
+            false => self.low_nibble = true,
+            true => self.move_by(1),
+        }
+
+        true
+    }
+
+    // overwrites the byte under the cursor, only ascii fits in a single byte
+    pub fn type_ascii(&mut self, c: char) -> bool {
+        if !c.is_ascii() {
+            return false;
+        }
+
+        *self.byte_at_cursor() = c as u8;
+        self.dirty = true;
+        self.move_by(1);
+
+        true
+    }
+
+    pub fn insert_byte(&mut self) {
+        self.bytes.insert(self.cursor, 0);
+        self.low_nibble = false;
+        self.dirty = true;
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor < self.bytes.len() {
+            self.bytes.remove(self.cursor);
+            self.low_nibble = false;
+            self.dirty = true;
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.move_by(-1);
+            self.delete();
+        }
+    }
+
+    fn byte_at_cursor(&mut self) -> &mut u8 {
+        if self.cursor == self.bytes.len() {
+            self.bytes.push(0);
+        }
+
+        &mut self.bytes[self.cursor]
+    }
+
+    // the next match after the cursor, or before it, wrapping around the ends of the file
+    pub fn find(&self, pattern: &[Option<u8>], reverse: bool) -> Option<usize> {
+        if pattern.is_empty() || pattern.len() > self.bytes.len() {
+            return None;
+        }
+
+        let last = self.bytes.len() - pattern.len();
+        let matches = |offset: &usize| {
+            self.bytes[*offset..*offset + pattern.len()]
+                .iter()
+                .zip(pattern)
+                .all(|(byte, expected)| expected.is_none_or(|expected| *byte == expected))
+        };
+
+        match reverse {
+            false => (self.cursor + 1..=last)
+                .chain(0..=min(self.cursor, last))
+                .find(matches),
+            true => (0..min(self.cursor, last + 1))
+                .rev()
+                .chain((self.cursor..=last).rev())
+                .find(matches),
+        }
+    }
+
+    pub fn scroll_to_cursor(&mut self, visible_rows: usize) {
+        let row = self.cursor / BYTES_PER_ROW;
+
+        if row < self.scroll_row {
+            self.scroll_row = row;
+        } else if visible_rows > 0 && row >= self.scroll_row + visible_rows {
+            self.scroll_row = row + 1 - visible_rows;
+        }
+    }
+
+    pub fn save(&mut self, path: &Path) -> io::Result<()> {
+        write_atomically(path, &self.bytes)?;
+        self.dirty = false;
+
+        Ok(())
+    }
+}
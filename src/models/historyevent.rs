@@ -10,6 +10,8 @@ pub enum HistoryEvent {
     RemoveString(String, usize),
 
     MoveLine(usize, bool),
+
+    Compound(String, Vec<HistoryEvent>),
 }
 
 impl Display for HistoryEvent {
@@ -34,6 +36,7 @@ impl Display for HistoryEvent {
             Self::AddString(s, _) => write!(f, "Add \"{}\"", ws(s)),
             Self::RemoveString(s, _) => write!(f, "Del \"{}\"", ws(s)),
             Self::MoveLine(i, b) => write!(f, "Move {} line {}", if *b { "up" } else { "down" }, i),
+            Self::Compound(name, events) => write!(f, "{} ({} edits)", name, events.len()),
         }
     }
 }
@@ -0,0 +1,94 @@
+use std::{collections::HashMap, fmt};
+
+// detection only looks at the top of big files
+const DETECT_LINES: usize = 1000;
+const SPACES: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Indentation {
+    Spaces(usize),
+    Tabs,
+}
+
+impl Default for Indentation {
+    fn default() -> Self {
+        Indentation::Spaces(SPACES)
+    }
+}
+
+impl Indentation {
+    // tabs win when more lines start with them, otherwise the most common step between
+    // the indentation of neighbouring lines is the width
+    pub fn detect(content: &str) -> Option<Self> {
+        let mut tabs = 0;
+        let mut spaces = 0;
+        let mut steps: HashMap<usize, usize> = HashMap::new();
+        let mut previous = 0;
+
+        for line in content.lines().take(DETECT_LINES) {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if line.starts_with('\t') {
+                tabs += 1;
+                continue;
+            }
+
+            let indent = line.chars().take_while(|c| *c == ' ').count();
+            if indent > 0 {
+                spaces += 1;
+            }
+
+            let step = indent.abs_diff(previous);
+            if (2..=8).contains(&step) {
+                *steps.entry(step).or_default() += 1;
+            }
+            previous = indent;
+        }
+
+        if tabs == 0 && spaces == 0 {
+            return None;
+        }
+
+        if tabs > spaces {
+            return Some(Indentation::Tabs);
+        }
+
+        steps
+            .into_iter()
+            .max_by_key(|(step, count)| (*count, *step))
+            .map(|(step, _)| Indentation::Spaces(step))
+    }
+
+    pub fn unit(self) -> String {
+        match self {
+            Indentation::Spaces(width) => " ".repeat(width),
+            Indentation::Tabs => "\t".to_string(),
+        }
+    }
+
+    // columns of one level, also the width a tab is counted as
+    pub fn width(self) -> usize {
+        match self {
+            Indentation::Spaces(width) => width,
+            Indentation::Tabs => SPACES,
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            Indentation::Spaces(_) => Indentation::Tabs,
+            Indentation::Tabs => Indentation::Spaces(SPACES),
+        }
+    }
+}
+
+impl fmt::Display for Indentation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Indentation::Spaces(width) => write!(f, "Spaces: {width}"),
+            Indentation::Tabs => write!(f, "Tabs"),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use ropey::{Rope, RopeBuilder};
+
+// files above this open read-only, without highlighting and only after a warning
+pub const LARGE_FILE_SIZE: u64 = 16 * 1024 * 1024;
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Load {
+    Loaded,
+    // waits for the user to confirm opening a large file
+    Confirm { size: u64 },
+    Loading { loaded: u64, size: u64 },
+    Failed(String),
+}
+
+pub enum LoadEvent {
+    Progress(u64),
+    Done(Rope),
+    Failed(String),
+}
+
+pub fn is_large(path: &Path) -> Option<u64> {
+    path.metadata()
+        .ok()
+        .map(|metadata| metadata.len())
+        .filter(|size| *size > LARGE_FILE_SIZE)
+}
+
+// reads the file chunk by chunk straight into a rope, without holding the whole content in a
+// string, `progress` gets the number of bytes read so far after every chunk
+pub fn load_rope(path: &Path, mut progress: impl FnMut(u64)) -> io::Result<Rope> {
+    let mut file = File::open(path)?;
+    let mut builder = RopeBuilder::new();
+    let mut chunk = vec![0; CHUNK_SIZE];
+    // bytes of a char, or a `\r\n`, split between two chunks wait for the next one
+    let mut pending = Vec::new();
+    let mut loaded = 0;
+    let mut last = None;
+
+    loop {
+        let read = file.read(&mut chunk)?;
+        if read == 0 {
+            break;
+        }
+
+        loaded += read as u64;
+        pending.extend_from_slice(&chunk[..read]);
+
+        let text = decode(&mut pending);
+        last = text.chars().last().or(last);
+        builder.append(&text);
+
+        progress(loaded);
+    }
+
+    if !pending.is_empty() {
+        let text = String::from_utf8_lossy(&pending).to_string();
+        last = text.chars().last().or(last);
+        builder.append(&text);
+    }
+
+    if last != Some('\n') {
+        builder.append("\n");
+    }
+
+    Ok(builder.finish())
+}
+
+// takes the complete chars off the front of `bytes`, invalid bytes become replacement chars
+fn decode(bytes: &mut Vec<u8>) -> String {
+    let mut text = String::new();
+    let mut start = 0;
+
+    loop {
+        match std::str::from_utf8(&bytes[start..]) {
+            Ok(valid) => {
+                text.push_str(valid);
+                start = bytes.len();
+                break;
+            }
+            Err(e) => {
+                let valid_end = start + e.valid_up_to();
+                text.push_str(std::str::from_utf8(&bytes[start..valid_end]).unwrap_or_default());
+
+                match e.error_len() {
+                    Some(len) => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        start = valid_end + len;
+                    }
+                    // the rest of the char is in the next chunk
+                    None => {
+                        start = valid_end;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    // a `\r` at the end may still be followed by a `\n`
+    if text.ends_with('\r') {
+        text.pop();
+        start -= 1;
+    }
+
+    bytes.drain(..start);
+    text.replace("\r\n", "\n")
+}
+
+pub struct FileSize(pub u64);
+
+impl fmt::Display for FileSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+        let mut size = self.0 as f64;
+        let mut unit = 0;
+
+        while size >= 1024.0 && unit + 1 < UNITS.len() {
+            size /= 1024.0;
+            unit += 1;
+        }
+
+        match unit {
+            0 => write!(f, "{} {}", self.0, UNITS[0]),
+            _ => write!(f, "{size:.1} {}", UNITS[unit]),
+        }
+    }
+}
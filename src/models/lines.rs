@@ -0,0 +1,140 @@
+use std::{
+    cmp::Ordering,
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+};
+
+use itertools::Itertools;
+
+// commands that rewrite a block of whole lines
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineCommand {
+    SortAscending,
+    SortDescending,
+    SortNatural,
+    SortCaseInsensitive,
+    Unique,
+    Reverse,
+    Shuffle,
+    Join,
+    TrimTrailingWhitespace,
+}
+
+impl LineCommand {
+    pub const ALL: [LineCommand; 9] = [
+        LineCommand::SortAscending,
+        LineCommand::SortDescending,
+        LineCommand::SortNatural,
+        LineCommand::SortCaseInsensitive,
+        LineCommand::Unique,
+        LineCommand::Reverse,
+        LineCommand::Shuffle,
+        LineCommand::Join,
+        LineCommand::TrimTrailingWhitespace,
+    ];
+
+    pub fn apply(self, mut lines: Vec<String>) -> Vec<String> {
+        match self {
+            LineCommand::SortAscending => lines.sort(),
+            LineCommand::SortDescending => lines.sort_by(|a, b| b.cmp(a)),
+            LineCommand::SortNatural => lines.sort_by(|a, b| natural_cmp(a, b)),
+            LineCommand::SortCaseInsensitive => lines.sort_by_key(|line| line.to_lowercase()),
+            LineCommand::Unique => lines = lines.into_iter().unique().collect(),
+            LineCommand::Reverse => lines.reverse(),
+            LineCommand::Shuffle => shuffle(&mut lines),
+            LineCommand::Join => lines = vec![join(&lines)],
+            LineCommand::TrimTrailingWhitespace => {
+                for line in lines.iter_mut() {
+                    line.truncate(line.trim_end().len());
+                }
+            }
+        }
+
+        lines
+    }
+}
+
+impl fmt::Display for LineCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LineCommand::SortAscending => write!(f, "Sort ascending"),
+            LineCommand::SortDescending => write!(f, "Sort descending"),
+            LineCommand::SortNatural => write!(f, "Sort naturally"),
+            LineCommand::SortCaseInsensitive => write!(f, "Sort ignoring case"),
+            LineCommand::Unique => write!(f, "Remove duplicates"),
+            LineCommand::Reverse => write!(f, "Reverse"),
+            LineCommand::Shuffle => write!(f, "Shuffle"),
+            LineCommand::Join => write!(f, "Join"),
+            LineCommand::TrimTrailingWhitespace => write!(f, "Trim trailing whitespace"),
+        }
+    }
+}
+
+// numbers compare by value, so `file2` goes before `file10`
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let chunks = |s: &str| {
+        s.chars()
+            .chunk_by(|c| c.is_ascii_digit())
+            .into_iter()
+            .map(|(_, chunk)| chunk.collect::<String>())
+            .collect::<Vec<_>>()
+    };
+
+    let (a_chunks, b_chunks) = (chunks(a), chunks(b));
+
+    for (x, y) in a_chunks.iter().zip(&b_chunks) {
+        let is_number = |s: &String| s.starts_with(|c: char| c.is_ascii_digit());
+
+        let ordering = match (is_number(x), is_number(y)) {
+            (true, true) => {
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            _ => x.cmp(y),
+        };
+
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+
+    a_chunks.len().cmp(&b_chunks.len()).then_with(|| a.cmp(b))
+}
+
+// lines are joined by a single space without their indentation, like `J` in vim
+fn join(lines: &[String]) -> String {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => line.trim_end(),
+            _ => line.trim(),
+        })
+        .filter(|line| !line.is_empty())
+        .join(" ")
+}
+
+// fisher-yates with a xorshift seeded from the randomly keyed std hasher
+fn shuffle(lines: &mut [String]) {
+    let mut state = RandomState::new().build_hasher().finish() | 1;
+
+    for i in (1..lines.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+
+        lines.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+}
+
+pub fn split(lines: Vec<String>, delimiter: &str) -> Vec<String> {
+    if delimiter.is_empty() {
+        return lines;
+    }
+
+    lines
+        .iter()
+        .flat_map(|line| line.split(delimiter).map(str::to_string))
+        .collect()
+}
@@ -0,0 +1,330 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{lines::LineCommand, text::TextFile, theme::config_dir};
+
+const MACROS_FILE: &str = "macros.json";
+// playing to the end of the file stops here even when every run keeps making progress
+const MAX_RUNS: usize = 10_000;
+
+// what a key press in the editor does, reified so it can be recorded and replayed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EditorCommand {
+    MoveLeft {
+        word: bool,
+        select: bool,
+    },
+    MoveRight {
+        word: bool,
+        select: bool,
+    },
+    MoveUp {
+        select: bool,
+    },
+    MoveDown {
+        select: bool,
+    },
+    LineStart {
+        document: bool,
+        select: bool,
+    },
+    LineEnd {
+        document: bool,
+        select: bool,
+    },
+    InsertChar(char),
+    InsertText(String),
+    // a linewise paste, the lines go in above the caret line
+    InsertLines(String),
+    Newline,
+    Indent,
+    Outdent,
+    Backspace {
+        word: bool,
+    },
+    Delete {
+        word: bool,
+    },
+    ClearSelection,
+    InsertLine {
+        below: bool,
+    },
+    DeleteLines,
+    // the selection, or the caret line without one
+    Cut,
+    JoinLines,
+    MoveLine {
+        down: bool,
+    },
+    DuplicateLine {
+        down: bool,
+    },
+    TransposeChars,
+    TransposeWords,
+    ToggleLineComment,
+    ToggleBlockComment,
+    JumpToBracket,
+    Increment(i64),
+    FindNext {
+        needle: String,
+        case_sensitive: bool,
+        reverse: bool,
+    },
+}
+
+impl EditorCommand {
+    // commands that change the text, they are refused in read-only files
+    pub fn is_edit(&self) -> bool {
+        !matches!(
+            self,
+            EditorCommand::MoveLeft { .. }
+                | EditorCommand::MoveRight { .. }
+                | EditorCommand::MoveUp { .. }
+                | EditorCommand::MoveDown { .. }
+                | EditorCommand::LineStart { .. }
+                | EditorCommand::LineEnd { .. }
+                | EditorCommand::ClearSelection
+                | EditorCommand::JumpToBracket
+                | EditorCommand::FindNext { .. }
+        )
+    }
+
+    // false when the command could not do anything, like a search without a match or a caret
+    // that could not move any further, playback stops there
+    pub fn run(&self, file: &mut TextFile) -> bool {
+        if file.read_only && self.is_edit() {
+            return false;
+        }
+
+        let old_idx = file.char_idx;
+
+        match *self {
+            EditorCommand::MoveLeft { word, .. } => file.caret_move_left(word),
+            EditorCommand::MoveRight { word, .. } => file.caret_move_right(word),
+            EditorCommand::MoveUp { .. } => file.caret_move_up(),
+            EditorCommand::MoveDown { .. } => file.caret_move_down(),
+            EditorCommand::LineStart { document, .. } => file.caret_move_line_start(document),
+            EditorCommand::LineEnd { document, .. } => file.caret_move_line_end(document),
+            EditorCommand::InsertChar(c) => file.insert_char(c),
+            EditorCommand::InsertText(ref text) => file.insert_string(text.clone()),
+            EditorCommand::InsertLines(ref text) => file.paste(text.clone(), true),
+            EditorCommand::Newline => {
+                file.clear_selection();
+                file.insert_newline();
+            }
+            EditorCommand::Indent => file.insert_tab(),
+            EditorCommand::Outdent => file.outdent(),
+            EditorCommand::Backspace { word } => file.backspace(word),
+            EditorCommand::Delete { word } => file.delete(word),
+            EditorCommand::ClearSelection => file.clear_selection(),
+            EditorCommand::InsertLine { below } => file.insert_line(below),
+            EditorCommand::DeleteLines => file.delete_lines(),
+            EditorCommand::Cut => {
+                file.cut();
+            }
+            EditorCommand::JoinLines => file.run_line_command(LineCommand::Join),
+            EditorCommand::MoveLine { down } => file.move_line(down),
+            EditorCommand::DuplicateLine { down } => file.duplicate_line(down),
+            EditorCommand::TransposeChars => file.transpose_chars(),
+            EditorCommand::TransposeWords => file.transpose_words(),
+            EditorCommand::ToggleLineComment => file.toggle_line_comment(),
+            EditorCommand::ToggleBlockComment => file.toggle_block_comment(),
+            EditorCommand::JumpToBracket => file.jump_to_bracket(),
+            EditorCommand::Increment(delta) => file.increment_number(delta),
+            EditorCommand::FindNext {
+                ref needle,
+                case_sensitive,
+                reverse,
+            } => return file.find_next(needle, case_sensitive, reverse),
+        }
+
+        match *self {
+            EditorCommand::MoveLeft { select, .. }
+            | EditorCommand::MoveRight { select, .. }
+            | EditorCommand::MoveUp { select }
+            | EditorCommand::MoveDown { select } => {
+                file.set_selection(select, old_idx);
+                file.char_idx != old_idx
+            }
+            // going to a line start where the caret already is still succeeds
+            EditorCommand::LineStart { select, .. } | EditorCommand::LineEnd { select, .. } => {
+                file.set_selection(select, old_idx);
+                true
+            }
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Macro {
+    pub name: String,
+    // played with Ctrl+Alt and this key
+    pub key: Option<char>,
+    pub commands: Vec<EditorCommand>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Playback {
+    Times(usize),
+    // until a command fails or a run stops getting closer to the end of the file
+    ToEnd,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Macros {
+    pub saved: Vec<Macro>,
+    // the last recording, it can be played right away and saved under a name later
+    pub last: Vec<EditorCommand>,
+    pub error: Option<String>,
+    recording: Option<Vec<EditorCommand>>,
+    // where the macros are saved, nothing is saved without one
+    path: Option<PathBuf>,
+    // a file that could not be read nor moved aside is not overwritten
+    unreadable: bool,
+}
+
+impl Macros {
+    pub fn load() -> Self {
+        match config_dir() {
+            Some(dir) => Self::load_from(&dir.join(MACROS_FILE)),
+            None => Self::default(),
+        }
+    }
+
+    // a file that does not parse is moved aside, so saving a macro does not overwrite it
+    pub fn load_from(path: &Path) -> Self {
+        let mut macros = Self {
+            path: Some(path.to_path_buf()),
+            ..Self::default()
+        };
+
+        let Ok(content) = fs::read_to_string(path) else {
+            return macros;
+        };
+
+        match serde_json::from_str(&content) {
+            Ok(saved) => macros.saved = saved,
+            Err(e) => {
+                let backup = path.with_extension("json.bak");
+
+                macros.error = Some(match fs::rename(path, &backup) {
+                    Ok(()) => format!("{MACROS_FILE}: {e}, it was moved to {}", backup.display()),
+                    Err(moved) => {
+                        macros.unreadable = true;
+                        format!(
+                            "{MACROS_FILE}: {e}, macros are not saved until it is fixed: {moved}"
+                        )
+                    }
+                });
+            }
+        }
+
+        macros
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn toggle_recording(&mut self) {
+        match self.recording.take() {
+            Some(commands) => self.last = commands,
+            None => self.recording = Some(Vec::new()),
+        }
+    }
+
+    pub fn record(&mut self, command: &EditorCommand) {
+        if let Some(commands) = self.recording.as_mut() {
+            commands.push(command.clone());
+        }
+    }
+
+    pub fn bound_to(&self, key: char) -> Option<&Macro> {
+        self.saved
+            .iter()
+            .find(|m| m.key.is_some_and(|k| k.eq_ignore_ascii_case(&key)))
+    }
+
+    // a macro with the same name is replaced, a key only plays one macro
+    pub fn save_last(&mut self, name: String, key: Option<char>) -> io::Result<()> {
+        self.saved.retain(|m| m.name != name);
+
+        for m in self.saved.iter_mut() {
+            if m.key.is_some() && m.key == key {
+                m.key = None;
+            }
+        }
+
+        self.saved.push(Macro {
+            name,
+            key,
+            commands: self.last.clone(),
+        });
+
+        self.persist()
+    }
+
+    pub fn remove(&mut self, name: &str) -> io::Result<()> {
+        self.saved.retain(|m| m.name != name);
+        self.persist()
+    }
+
+    fn persist(&self) -> io::Result<()> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+
+        if self.unreadable {
+            return Err(io::Error::other(format!(
+                "{} could not be read, fix or remove it first",
+                path.display()
+            )));
+        }
+
+        let content = serde_json::to_string_pretty(&self.saved)?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, content)
+    }
+}
+
+// the whole playback is a single undo step, returns how many runs went through completely
+pub fn play(file: &mut TextFile, commands: &[EditorCommand], playback: Playback) -> usize {
+    if commands.is_empty() {
+        return 0;
+    }
+
+    // the first replayed edit would otherwise be merged into the one typed before
+    file.break_history();
+    let history_start = file.history_idx;
+    let runs = match playback {
+        Playback::Times(times) => times,
+        Playback::ToEnd => MAX_RUNS,
+    };
+
+    let mut completed = 0;
+    for _ in 0..runs {
+        // measured from the end, so text the run inserts before the caret does not count
+        let remaining = file.rope.len_chars() - file.char_idx;
+
+        if !commands.iter().all(|command| command.run(file)) {
+            break;
+        }
+        completed += 1;
+
+        // a run that does not get the caret closer to the end would never get there
+        if playback == Playback::ToEnd && file.rope.len_chars() - file.char_idx >= remaining {
+            break;
+        }
+    }
+
+    file.group_history(history_start, "Macro");
+    completed
+}
@@ -1,8 +1,24 @@
+pub mod brackets;
+pub mod clipboard;
 pub mod file_system;
+pub mod folding;
+pub mod hex;
 pub mod historyevent;
+pub mod indentation;
+pub mod large_file;
+pub mod lines;
+pub mod macros;
 pub mod network;
 pub mod panels;
+pub mod permissions;
+pub mod search;
 pub mod sessions;
+pub mod syntax;
 pub mod tabs;
 pub mod terminal_state;
 pub mod text;
+pub mod theme;
+pub mod transform;
+pub mod unicode;
+pub mod workspace_search;
+pub mod wrap;
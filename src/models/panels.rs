@@ -7,17 +7,23 @@ use dioxus::{
 pub struct ShownPanels {
     pub terminal: Signal<bool>,
     pub search: Signal<bool>,
+    pub find_in_files: Signal<bool>,
     pub file_tree: Signal<bool>,
     pub sessions: Signal<bool>,
     pub history: Signal<bool>,
+    pub themes: Signal<bool>,
+    pub macros: Signal<bool>,
 }
 
 pub struct ReadOnlyShownPanels {
     pub terminal: ReadOnlySignal<bool>,
     pub search: ReadOnlySignal<bool>,
+    pub find_in_files: ReadOnlySignal<bool>,
     pub file_tree: ReadOnlySignal<bool>,
     pub sessions: ReadOnlySignal<bool>,
     pub history: ReadOnlySignal<bool>,
+    pub themes: ReadOnlySignal<bool>,
+    pub macros: ReadOnlySignal<bool>,
 }
 
 impl From<ShownPanels> for ReadOnlyShownPanels {
@@ -25,9 +31,12 @@ impl From<ShownPanels> for ReadOnlyShownPanels {
         Self {
             terminal: panels.terminal.into(),
             search: panels.search.into(),
+            find_in_files: panels.find_in_files.into(),
             file_tree: panels.file_tree.into(),
             sessions: panels.sessions.into(),
             history: panels.history.into(),
+            themes: panels.themes.into(),
+            macros: panels.macros.into(),
         }
     }
 }
@@ -43,9 +52,12 @@ impl ShownPanels {
         Self {
             terminal: use_signal(|| false),
             search: use_signal(|| false),
+            find_in_files: use_signal(|| false),
             file_tree: use_signal(|| true),
             sessions: use_signal(|| false),
             history: use_signal(|| false),
+            themes: use_signal(|| false),
+            macros: use_signal(|| false),
         }
     }
 }
@@ -0,0 +1,128 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    process::Command,
+};
+
+// a file that does not exist yet can be created, whether its directory allows that shows on save,
+// nothing is opened so a fifo does not block and no access time changes
+pub fn is_writable(path: &Path) -> bool {
+    match fs::metadata(path) {
+        Ok(metadata) => !metadata.permissions().readonly() && may_write(path),
+        Err(e) => e.kind() == io::ErrorKind::NotFound,
+    }
+}
+
+// the permission bits alone do not say whether they apply to us
+#[cfg(unix)]
+fn may_write(path: &Path) -> bool {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    CString::new(path.as_os_str().as_bytes())
+        .is_ok_and(|path| unsafe { libc::access(path.as_ptr(), libc::W_OK) } == 0)
+}
+
+#[cfg(not(unix))]
+fn may_write(_path: &Path) -> bool {
+    true
+}
+
+// the content goes to a temporary file first, the system's own password prompt then copies it
+// over the original as an administrator
+pub fn save_elevated(path: &Path, content: &[u8]) -> io::Result<()> {
+    // a fresh directory only we can enter, nobody can put a file or a link where ours goes
+    let dir = tempfile::Builder::new()
+        .prefix("notepad-minus-minus-")
+        .tempdir()?;
+    let temp = dir.path().join("content");
+    private_file(&temp)?.write_all(content)?;
+
+    let status = helper(&temp, path).and_then(|mut command| command.status());
+    dir.close()?;
+
+    match status? {
+        status if status.success() => Ok(()),
+        status => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("the elevated save was cancelled or failed ({status})"),
+        )),
+    }
+}
+
+// the content may be something only an administrator is meant to read, the file must not exist
+// yet and must not be a link
+#[cfg(unix)]
+fn private_file(path: &Path) -> io::Result<fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .custom_flags(libc::O_NOFOLLOW)
+        .mode(0o600)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn private_file(path: &Path) -> io::Result<fs::File> {
+    OpenOptions::new().write(true).create_new(true).open(path)
+}
+
+// a file only the user can read, an existing one loses any wider permissions it had
+#[cfg(unix)]
+pub fn write_private(path: &Path, content: &[u8]) -> io::Result<()> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .custom_flags(libc::O_NOFOLLOW)
+        .mode(0o600)
+        .open(path)?;
+
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(content)
+}
+
+#[cfg(not(unix))]
+pub fn write_private(path: &Path, content: &[u8]) -> io::Result<()> {
+    fs::write(path, content)
+}
+
+#[cfg(target_os = "linux")]
+fn helper(from: &Path, to: &Path) -> io::Result<Command> {
+    let mut command = Command::new("pkexec");
+    command.arg("cp").arg("--").arg(from).arg(to);
+
+    Ok(command)
+}
+
+#[cfg(target_os = "macos")]
+fn helper(from: &Path, to: &Path) -> io::Result<Command> {
+    let quoted = |path: &Path| {
+        let path = path
+            .to_string_lossy()
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"");
+        format!("quoted form of \"{path}\"")
+    };
+
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(format!(
+        "do shell script \"cp -- \" & {} & \" \" & {} with administrator privileges",
+        quoted(from),
+        quoted(to)
+    ));
+
+    Ok(command)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn helper(_from: &Path, _to: &Path) -> io::Result<Command> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "saving with elevated permissions is not supported on this system",
+    ))
+}
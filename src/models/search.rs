@@ -0,0 +1,333 @@
+use std::{
+    cell::RefCell,
+    cmp::{max, min},
+    collections::VecDeque,
+    ops::Range,
+};
+
+use ropey::Rope;
+
+use super::text::TextFile;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Search {
+    pub needle: String,
+    pub case_sensitive: bool,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Search {
+    pub fn new() -> Self {
+        Self {
+            needle: String::new(),
+            case_sensitive: false,
+        }
+    }
+
+    pub fn find_all(&self, text: &TextFile) -> Vec<(usize, usize)> {
+        text.find_all(&self.needle, self.case_sensitive)
+    }
+
+    pub fn find_in(&self, text: &TextFile, range: Range<usize>) -> Vec<(usize, usize)> {
+        text.find_in(&self.needle, self.case_sensitive, range)
+    }
+
+    pub fn first_in(&self, text: &TextFile, range: Range<usize>) -> Option<(usize, usize)> {
+        text.first_match_in(&self.needle, self.case_sensitive, range)
+    }
+
+    pub fn current_match(
+        matches: &[(usize, usize)],
+        selection: Option<(usize, usize)>,
+    ) -> Option<usize> {
+        let (start, end) = selection?;
+        let s = min(start, end);
+
+        matches.iter().position(|(m_start, _)| *m_start == s)
+    }
+}
+
+// the matches of the last searched needle, kept up to date by re-scanning around every edit
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MatchCache {
+    found: RefCell<Option<Found>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Found {
+    needle: String,
+    case_sensitive: bool,
+    matches: Vec<(usize, usize)>,
+}
+
+impl MatchCache {
+    pub fn find_all(&self, rope: &Rope, needle: &str, case_sensitive: bool) -> Vec<(usize, usize)> {
+        self.with_matches(rope, needle, case_sensitive, <[_]>::to_vec)
+    }
+
+    // the matches reaching into `range`
+    pub fn find_in(
+        &self,
+        rope: &Rope,
+        needle: &str,
+        case_sensitive: bool,
+        range: Range<usize>,
+    ) -> Vec<(usize, usize)> {
+        self.with_matches(rope, needle, case_sensitive, |matches| {
+            matches_in(matches, range).to_vec()
+        })
+    }
+
+    pub fn first_in(
+        &self,
+        rope: &Rope,
+        needle: &str,
+        case_sensitive: bool,
+        range: Range<usize>,
+    ) -> Option<(usize, usize)> {
+        self.with_matches(rope, needle, case_sensitive, |matches| {
+            matches_in(matches, range).first().copied()
+        })
+    }
+
+    fn with_matches<T>(
+        &self,
+        rope: &Rope,
+        needle: &str,
+        case_sensitive: bool,
+        f: impl FnOnce(&[(usize, usize)]) -> T,
+    ) -> T {
+        let mut found = self.found.borrow_mut();
+
+        match found.as_ref() {
+            Some(found) if found.needle == needle && found.case_sensitive == case_sensitive => {
+                f(&found.matches)
+            }
+            _ => {
+                let haystack = rope.chunks().flat_map(str::chars);
+
+                f(&found
+                    .insert(Found {
+                        needle: needle.to_string(),
+                        case_sensitive,
+                        matches: find_char_ranges(haystack, needle, case_sensitive),
+                    })
+                    .matches)
+            }
+        }
+    }
+
+    // `removed` chars at `start` were replaced by `inserted` ones, `rope` is the text after it
+    pub fn edit(&mut self, rope: &Rope, start: usize, removed: usize, inserted: usize) {
+        let Some(found) = self.found.get_mut() else {
+            return;
+        };
+
+        let matcher = Matcher::new(&found.needle, found.case_sensitive);
+        let (old_end, new_end) = (start + removed, start + inserted);
+
+        // the matches in front of the edit stay, the ones behind move along with the text
+        let kept = found.matches.partition_point(|&(_, end)| end <= start);
+        let moved = found.matches.partition_point(|&(s, _)| s < old_end);
+        let behind = found.matches[moved..]
+            .iter()
+            .map(|&(s, e)| (s - removed + inserted, e - removed + inserted))
+            .collect::<Vec<_>>();
+        // the old scan was busy with a match across the edit until here
+        let crossed = found.matches[kept..moved]
+            .last()
+            .map_or(0, |&(_, end)| max(end, old_end) - removed + inserted);
+        found.matches.truncate(kept);
+
+        // no match can start before this and reach into the edit
+        let from = max(
+            found.matches.last().map_or(0, |&(_, end)| end),
+            start.saturating_sub(matcher.needle.len()),
+        );
+        let mut scan = Matches::new(&matcher, rope.chars_at(from), from);
+        let mut rest = behind.len();
+
+        loop {
+            // from `resume` on, the new scan finds what the old one did
+            let resume = max(new_end, crossed).max(found.matches.last().map_or(0, |&(_, end)| end));
+            let next = behind.partition_point(|&(_, end)| end <= resume);
+            let overlapped = behind.get(next).is_some_and(|&(s, _)| s < resume);
+
+            if scan.checked() >= resume && !overlapped {
+                rest = next;
+                break;
+            }
+
+            match scan.step() {
+                Some(Some(m)) => found.matches.push(m),
+                Some(None) => {}
+                None => break,
+            }
+        }
+
+        found.matches.extend_from_slice(&behind[rest..]);
+    }
+}
+
+fn matches_in(matches: &[(usize, usize)], range: Range<usize>) -> &[(usize, usize)] {
+    let first = matches.partition_point(|&(_, end)| end <= range.start);
+    let last = matches.partition_point(|&(start, _)| start < range.end);
+
+    &matches[first..max(first, last)]
+}
+
+// compares folded chars, `İ` lowercases to two of them, so a match has to start and end on
+// whole chars of the haystack
+struct Matcher {
+    needle: Vec<char>,
+    // the longest proper prefix of the needle that also ends its first `i + 1` chars
+    fallback: Vec<usize>,
+    case_sensitive: bool,
+}
+
+impl Matcher {
+    fn new(needle: &str, case_sensitive: bool) -> Self {
+        let needle = match case_sensitive {
+            true => needle.chars().collect::<Vec<_>>(),
+            false => needle.chars().flat_map(char::to_lowercase).collect(),
+        };
+
+        let mut fallback = vec![0; needle.len()];
+        let mut len = 0;
+
+        for i in 1..needle.len() {
+            while len > 0 && needle[i] != needle[len] {
+                len = fallback[len - 1];
+            }
+            if needle[i] == needle[len] {
+                len += 1;
+            }
+            fallback[i] = len;
+        }
+
+        Self {
+            needle,
+            fallback,
+            case_sensitive,
+        }
+    }
+
+    fn fold(&self, c: char, folded: &mut Vec<char>) {
+        folded.clear();
+
+        match self.case_sensitive {
+            true => folded.push(c),
+            false => folded.extend(c.to_lowercase()),
+        }
+    }
+}
+
+// non-overlapping matches from left to right, reading the haystack only as far as needed
+struct Matches<'a, I> {
+    matcher: &'a Matcher,
+    haystack: I,
+    idx: usize,
+    matched: usize,
+    // for the folded chars that can still be part of a match, the index of the char they came
+    // from and whether they are its first
+    recent: VecDeque<(usize, bool)>,
+    folded: Vec<char>,
+}
+
+impl<'a, I: Iterator<Item = char>> Matches<'a, I> {
+    fn new(matcher: &'a Matcher, haystack: I, offset: usize) -> Self {
+        Self {
+            matcher,
+            haystack,
+            idx: offset,
+            matched: 0,
+            recent: VecDeque::new(),
+            folded: Vec::new(),
+        }
+    }
+
+    // every match starting before this has been found
+    fn checked(&self) -> usize {
+        match self.matched {
+            0 => self.idx,
+            matched => self.recent[self.recent.len() - matched].0,
+        }
+    }
+
+    // reads one char of the haystack, `None` once there are no more
+    fn step(&mut self) -> Option<Option<(usize, usize)>> {
+        let needle = &self.matcher.needle;
+        if needle.is_empty() {
+            return None;
+        }
+
+        let c = self.haystack.next()?;
+        let idx = self.idx;
+        self.idx += 1;
+
+        self.matcher.fold(c, &mut self.folded);
+
+        for (i, &f) in self.folded.iter().enumerate() {
+            self.recent.push_back((idx, i == 0));
+            if self.recent.len() > needle.len() {
+                self.recent.pop_front();
+            }
+
+            while self.matched > 0 && needle[self.matched] != f {
+                self.matched = self.matcher.fallback[self.matched - 1];
+            }
+            if needle[self.matched] == f {
+                self.matched += 1;
+            }
+
+            if self.matched == needle.len() {
+                let (start, whole) = self.recent[0];
+
+                if whole && i + 1 == self.folded.len() {
+                    self.matched = 0;
+                    self.recent.clear();
+                    return Some(Some((start, idx + 1)));
+                }
+
+                self.matched = self.matcher.fallback[self.matched - 1];
+            }
+        }
+
+        Some(None)
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for Matches<'_, I> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(m) = self.step()? {
+                return Some(m);
+            }
+        }
+    }
+}
+
+pub fn find_char_ranges(
+    haystack: impl Iterator<Item = char>,
+    needle: &str,
+    case_sensitive: bool,
+) -> Vec<(usize, usize)> {
+    let matcher = Matcher::new(needle, case_sensitive);
+    Matches::new(&matcher, haystack, 0).collect()
+}
+
+pub fn find_first(
+    haystack: impl Iterator<Item = char>,
+    needle: &str,
+    case_sensitive: bool,
+) -> Option<(usize, usize)> {
+    let matcher = Matcher::new(needle, case_sensitive);
+    Matches::new(&matcher, haystack, 0).next()
+}
@@ -130,6 +130,11 @@ impl Sessions {
         self.is_host = false;
     }
 
+    // guests only look at the host's files, they open read-only
+    pub fn is_guest(&self) -> bool {
+        self.current_session.is_some() && !self.is_host
+    }
+
     pub fn leave_session(&mut self) {
         self.current_session = None;
         self.is_host = false;
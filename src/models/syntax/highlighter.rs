@@ -0,0 +1,95 @@
+use std::{
+    cell::RefCell,
+    cmp::min,
+    ops::{Range, RangeInclusive},
+};
+
+use ropey::Rope;
+
+use super::{languages::Language, tokenize_line, LineState, Span};
+
+#[derive(Debug, Clone, PartialEq)]
+struct HighlightedLine {
+    spans: Vec<Span>,
+    end_state: LineState,
+}
+
+// lines are tokenized lazily from the top, the cache always holds a prefix of the file
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Highlighter {
+    pub language: Option<Language>,
+    lines: RefCell<Vec<HighlightedLine>>,
+}
+
+impl Highlighter {
+    pub fn new(language: Option<Language>) -> Self {
+        Self {
+            language,
+            lines: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn set_language(&mut self, language: Option<Language>) {
+        self.language = language;
+        self.lines.get_mut().clear();
+    }
+
+    pub fn spans(&self, rope: &Rope, lines: Range<usize>) -> Vec<Vec<Span>> {
+        let Some(language) = self.language else {
+            return vec![Vec::new(); lines.len()];
+        };
+
+        let mut cache = self.lines.borrow_mut();
+        let end = min(lines.end, rope.len_lines());
+
+        while cache.len() < end {
+            let ln = cache.len();
+            let state = cache.last().map(|l| l.end_state).unwrap_or_default();
+            cache.push(tokenize(language, rope, ln, state));
+        }
+
+        lines
+            .map(|ln| cache.get(ln).map(|l| l.spans.clone()).unwrap_or_default())
+            .collect()
+    }
+
+    // `old` lines of the previous text were replaced by `new` lines, both starting at the same line
+    pub fn edit(&mut self, rope: &Rope, old: RangeInclusive<usize>, new: RangeInclusive<usize>) {
+        let Some(language) = self.language else {
+            return;
+        };
+
+        let cache = self.lines.get_mut();
+        let (first, old_last) = (*old.start(), *old.end());
+
+        if first >= cache.len() {
+            return;
+        }
+
+        let old_end_state = cache.get(old_last).map(|l| l.end_state);
+        let tail = cache.split_off(min(old_last + 1, cache.len()));
+        cache.truncate(first);
+
+        for ln in first..=min(*new.end(), rope.len_lines() - 1) {
+            let state = cache.last().map(|l| l.end_state).unwrap_or_default();
+            cache.push(tokenize(language, rope, ln, state));
+        }
+
+        // lines below the edit only stay valid if they start in the same state as before
+        if cache.last().map(|l| l.end_state) == old_end_state {
+            cache.extend(tail);
+        }
+    }
+}
+
+fn tokenize(language: Language, rope: &Rope, ln: usize, state: LineState) -> HighlightedLine {
+    let line = rope
+        .line(ln)
+        .chars()
+        .filter(|c| *c != '\n' && *c != '\r')
+        .collect::<Vec<_>>();
+
+    let (spans, end_state) = tokenize_line(language.grammar(), &line, state);
+
+    HighlightedLine { spans, end_state }
+}
@@ -0,0 +1,254 @@
+use std::{fmt, path::Path};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Rust,
+    Toml,
+    Json,
+    Markdown,
+    Python,
+    Shell,
+    Yaml,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StringRule {
+    pub open: &'static str,
+    pub close: &'static str,
+    pub escape: bool,
+    pub multiline: bool,
+    pub char_literal: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grammar {
+    pub line_comments: &'static [&'static str],
+    pub block_comment: Option<(&'static str, &'static str)>,
+    pub strings: &'static [StringRule],
+    pub keywords: &'static [&'static str],
+    pub types: &'static [&'static str],
+    pub constants: &'static [&'static str],
+    pub extra_ident_chars: &'static str,
+    pub key_separator: Option<char>,
+    pub variable_prefix: Option<char>,
+    pub macro_suffix: Option<char>,
+    pub capitalized_types: bool,
+    pub section_headers: bool,
+    pub numbers: bool,
+    pub markup: bool,
+}
+
+const fn string(open: &'static str, close: &'static str, multiline: bool) -> StringRule {
+    StringRule {
+        open,
+        close,
+        escape: true,
+        multiline,
+        char_literal: false,
+    }
+}
+
+const fn raw_string(open: &'static str, close: &'static str, multiline: bool) -> StringRule {
+    StringRule {
+        open,
+        close,
+        escape: false,
+        multiline,
+        char_literal: false,
+    }
+}
+
+const PLAIN: Grammar = Grammar {
+    line_comments: &[],
+    block_comment: None,
+    strings: &[],
+    keywords: &[],
+    types: &[],
+    constants: &[],
+    extra_ident_chars: "",
+    key_separator: None,
+    variable_prefix: None,
+    macro_suffix: None,
+    capitalized_types: false,
+    section_headers: false,
+    numbers: true,
+    markup: false,
+};
+
+const RUST: Grammar = Grammar {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    strings: &[
+        raw_string("r#\"", "\"#", true),
+        string("\"", "\"", true),
+        StringRule {
+            open: "'",
+            close: "'",
+            escape: true,
+            multiline: false,
+            char_literal: true,
+        },
+    ],
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "type",
+        "unsafe", "use", "where", "while",
+    ],
+    types: &[
+        "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32",
+        "i64", "i128", "isize", "f32", "f64",
+    ],
+    constants: &["true", "false", "None", "Some", "Ok", "Err"],
+    macro_suffix: Some('!'),
+    capitalized_types: true,
+    ..PLAIN
+};
+
+const TOML: Grammar = Grammar {
+    line_comments: &["#"],
+    strings: &[
+        string("\"\"\"", "\"\"\"", true),
+        raw_string("'''", "'''", true),
+        string("\"", "\"", false),
+        raw_string("'", "'", false),
+    ],
+    constants: &["true", "false", "inf", "nan"],
+    extra_ident_chars: "-",
+    key_separator: Some('='),
+    section_headers: true,
+    ..PLAIN
+};
+
+const JSON: Grammar = Grammar {
+    strings: &[string("\"", "\"", false)],
+    constants: &["true", "false", "null"],
+    key_separator: Some(':'),
+    ..PLAIN
+};
+
+const MARKDOWN: Grammar = Grammar {
+    block_comment: Some(("<!--", "-->")),
+    strings: &[raw_string("```", "```", true), raw_string("`", "`", false)],
+    numbers: false,
+    markup: true,
+    ..PLAIN
+};
+
+const PYTHON: Grammar = Grammar {
+    line_comments: &["#"],
+    strings: &[
+        string("\"\"\"", "\"\"\"", true),
+        string("'''", "'''", true),
+        string("\"", "\"", false),
+        string("'", "'", false),
+    ],
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is",
+        "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with",
+        "yield", "self",
+    ],
+    types: &[
+        "int", "float", "str", "bool", "bytes", "list", "dict", "set", "tuple", "object",
+    ],
+    constants: &["True", "False", "None"],
+    capitalized_types: true,
+    ..PLAIN
+};
+
+const SHELL: Grammar = Grammar {
+    line_comments: &["#"],
+    strings: &[string("\"", "\"", true), raw_string("'", "'", true)],
+    keywords: &[
+        "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done", "case", "esac",
+        "in", "function", "return", "local", "export", "readonly", "source", "exit",
+    ],
+    constants: &["true", "false"],
+    variable_prefix: Some('$'),
+    ..PLAIN
+};
+
+const YAML: Grammar = Grammar {
+    line_comments: &["#"],
+    strings: &[string("\"", "\"", false), raw_string("'", "'", false)],
+    constants: &["true", "false", "yes", "no", "on", "off", "null"],
+    extra_ident_chars: "-.",
+    key_separator: Some(':'),
+    ..PLAIN
+};
+
+impl Language {
+    pub fn detect(path: &Path, first_line: Option<&str>) -> Option<Self> {
+        let file_name = path.file_name()?.to_string_lossy().to_lowercase();
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let by_name = match file_name.as_str() {
+            "cargo.lock" | "pipfile" => Some(Self::Toml),
+            ".bashrc" | ".bash_profile" | ".zshrc" | ".profile" => Some(Self::Shell),
+            _ => None,
+        };
+
+        let by_extension = match extension.as_str() {
+            "rs" => Some(Self::Rust),
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            "md" | "markdown" => Some(Self::Markdown),
+            "py" | "pyw" => Some(Self::Python),
+            "sh" | "bash" | "zsh" => Some(Self::Shell),
+            "yml" | "yaml" => Some(Self::Yaml),
+            _ => None,
+        };
+
+        by_name
+            .or(by_extension)
+            .or_else(|| first_line.and_then(Self::from_shebang))
+    }
+
+    // `#!/usr/bin/env python3`, `#!/bin/bash -e`, ...
+    fn from_shebang(line: &str) -> Option<Self> {
+        let mut parts = line.strip_prefix("#!")?.split_whitespace();
+        let mut program = parts.next()?.rsplit('/').next()?;
+
+        if program == "env" {
+            program = parts.find(|p| !p.starts_with('-'))?;
+        }
+
+        match program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.') {
+            "python" => Some(Self::Python),
+            "sh" | "bash" | "zsh" | "dash" | "ksh" => Some(Self::Shell),
+            _ => None,
+        }
+    }
+
+    pub fn grammar(self) -> &'static Grammar {
+        match self {
+            Self::Rust => &RUST,
+            Self::Toml => &TOML,
+            Self::Json => &JSON,
+            Self::Markdown => &MARKDOWN,
+            Self::Python => &PYTHON,
+            Self::Shell => &SHELL,
+            Self::Yaml => &YAML,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Rust => "Rust",
+            Self::Toml => "TOML",
+            Self::Json => "JSON",
+            Self::Markdown => "Markdown",
+            Self::Python => "Python",
+            Self::Shell => "Shell",
+            Self::Yaml => "YAML",
+        };
+
+        write!(f, "{name}")
+    }
+}
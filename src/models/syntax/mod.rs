@@ -0,0 +1,321 @@
+pub mod highlighter;
+pub mod languages;
+
+use languages::{Grammar, StringRule};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Keyword,
+    Type,
+    Constant,
+    Number,
+    String,
+    Comment,
+    Function,
+    Key,
+    Heading,
+    Variable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub kind: TokenKind,
+}
+
+// what a line leaves open for the next one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineState {
+    #[default]
+    Normal,
+    BlockComment,
+    String(usize),
+}
+
+impl TokenKind {
+    pub fn style(self) -> &'static str {
+        match self {
+            TokenKind::Keyword => "color: var(--syntax-keyword);",
+            TokenKind::Type => "color: var(--syntax-type);",
+            TokenKind::Constant => "color: var(--syntax-constant);",
+            TokenKind::Number => "color: var(--syntax-number);",
+            TokenKind::String => "color: var(--syntax-string);",
+            TokenKind::Comment => "color: var(--syntax-comment);",
+            TokenKind::Function => "color: var(--syntax-function);",
+            TokenKind::Key => "color: var(--syntax-key);",
+            TokenKind::Heading => "color: var(--syntax-heading); font-weight: bold;",
+            TokenKind::Variable => "color: var(--syntax-variable);",
+        }
+    }
+}
+
+pub fn tokenize_line(grammar: &Grammar, line: &[char], state: LineState) -> (Vec<Span>, LineState) {
+    let mut spans = Vec::new();
+    let len = line.len();
+
+    let mut push = |start: usize, end: usize, kind: TokenKind| {
+        if start < end {
+            spans.push(Span { start, end, kind });
+        }
+    };
+
+    // finish whatever the previous line left open
+    let mut i = match state {
+        LineState::Normal => 0,
+        LineState::BlockComment => {
+            let (_, close) = grammar.block_comment.unwrap_or_default();
+
+            match find_at(line, 0, close) {
+                Some(end) => {
+                    push(0, end + close.chars().count(), TokenKind::Comment);
+                    end + close.chars().count()
+                }
+                None => {
+                    push(0, len, TokenKind::Comment);
+                    return (spans, state);
+                }
+            }
+        }
+        LineState::String(rule) => match find_string_end(&grammar.strings[rule], line, 0) {
+            Some(end) => {
+                push(0, end, TokenKind::String);
+                end
+            }
+            None => {
+                push(0, len, TokenKind::String);
+                return (spans, state);
+            }
+        },
+    };
+
+    if grammar.markup && i == 0 {
+        if let Some((end, kind)) = markup_prefix(line) {
+            push(0, end, kind);
+
+            if kind == TokenKind::Heading {
+                return (spans, LineState::Normal);
+            }
+
+            i = end;
+        }
+    }
+
+    while i < len {
+        let c = line[i];
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if grammar
+            .line_comments
+            .iter()
+            .any(|comment| starts_with_at(line, i, comment))
+        {
+            push(i, len, TokenKind::Comment);
+            break;
+        }
+
+        if let Some((open, close)) = grammar.block_comment {
+            if starts_with_at(line, i, open) {
+                let from = i + open.chars().count();
+
+                match find_at(line, from, close) {
+                    Some(end) => {
+                        push(i, end + close.chars().count(), TokenKind::Comment);
+                        i = end + close.chars().count();
+                        continue;
+                    }
+                    None => {
+                        push(i, len, TokenKind::Comment);
+                        return (spans, LineState::BlockComment);
+                    }
+                }
+            }
+        }
+
+        if let Some((rule_idx, rule)) =
+            grammar.strings.iter().enumerate().find(|(_, rule)| {
+                starts_with_at(line, i, rule.open) && is_string_start(rule, line, i)
+            })
+        {
+            let from = i + rule.open.chars().count();
+
+            match find_string_end(rule, line, from) {
+                Some(end) => {
+                    let kind = match is_key(grammar, line, end) {
+                        true => TokenKind::Key,
+                        false => TokenKind::String,
+                    };
+
+                    push(i, end, kind);
+                    i = end;
+                    continue;
+                }
+                None => {
+                    push(i, len, TokenKind::String);
+
+                    return match rule.multiline {
+                        true => (spans, LineState::String(rule_idx)),
+                        false => (spans, LineState::Normal),
+                    };
+                }
+            }
+        }
+
+        if grammar.section_headers && c == '[' && line[..i].iter().all(|c| c.is_whitespace()) {
+            let end = line.iter().rposition(|c| *c == ']').map_or(len, |e| e + 1);
+            push(i, end, TokenKind::Heading);
+            i = end;
+            continue;
+        }
+
+        if grammar.variable_prefix == Some(c) {
+            let end = match line.get(i + 1) {
+                Some('{') => line[i..]
+                    .iter()
+                    .position(|c| *c == '}')
+                    .map_or(len, |e| i + e + 1),
+                _ => scan_while(line, i + 1, |c| is_ident_char(grammar, c)),
+            };
+
+            push(i, end, TokenKind::Variable);
+            i = end.max(i + 1);
+            continue;
+        }
+
+        if grammar.numbers && c.is_ascii_digit() {
+            let mut end = i;
+            while end < len
+                && (line[end].is_alphanumeric()
+                    || line[end] == '_'
+                    || (line[end] == '.' && line.get(end + 1) != Some(&'.')))
+            {
+                end += 1;
+            }
+
+            push(i, end, TokenKind::Number);
+            i = end;
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let end = scan_while(line, i, |c| is_ident_char(grammar, c));
+            let word = line[i..end].iter().collect::<String>();
+
+            let kind = if is_key(grammar, line, end) {
+                Some(TokenKind::Key)
+            } else if grammar.keywords.contains(&word.as_str()) {
+                Some(TokenKind::Keyword)
+            } else if grammar.types.contains(&word.as_str()) {
+                Some(TokenKind::Type)
+            } else if grammar.constants.contains(&word.as_str()) {
+                Some(TokenKind::Constant)
+            } else if line.get(end) == Some(&'(')
+                || (grammar.macro_suffix.is_some()
+                    && line.get(end) == grammar.macro_suffix.as_ref())
+            {
+                Some(TokenKind::Function)
+            } else if grammar.capitalized_types && c.is_uppercase() {
+                Some(TokenKind::Type)
+            } else {
+                None
+            };
+
+            if let Some(kind) = kind {
+                push(i, end, kind);
+            }
+
+            i = end;
+            continue;
+        }
+
+        i += 1;
+    }
+
+    (spans, LineState::Normal)
+}
+
+// markdown decides most of its styling from how a line starts
+fn markup_prefix(line: &[char]) -> Option<(usize, TokenKind)> {
+    let indent = scan_while(line, 0, |c| c == ' ');
+    let rest = &line[indent..];
+
+    match rest {
+        ['#', ..] => Some((line.len(), TokenKind::Heading)),
+        ['>', ..] => Some((line.len(), TokenKind::Comment)),
+        ['-' | '*' | '+', ' ', ..] => Some((indent + 1, TokenKind::Keyword)),
+        _ => {
+            let digits = scan_while(rest, 0, |c| c.is_ascii_digit());
+
+            match digits > 0 && rest.get(digits) == Some(&'.') {
+                true => Some((indent + digits + 1, TokenKind::Keyword)),
+                false => None,
+            }
+        }
+    }
+}
+
+fn is_key(grammar: &Grammar, line: &[char], end: usize) -> bool {
+    let Some(separator) = grammar.key_separator else {
+        return false;
+    };
+
+    let next = scan_while(line, end, |c| c == ' ' || c == '\t');
+
+    line.get(next) == Some(&separator) && line.get(next + 1) != Some(&separator)
+}
+
+// a lone quote in rust is a lifetime unless it closes right after one (escaped) char
+fn is_string_start(rule: &StringRule, line: &[char], i: usize) -> bool {
+    if !rule.char_literal {
+        return true;
+    }
+
+    match line.get(i + 1) {
+        Some('\\') => line[i + 2..].iter().take(10).any(|c| *c == '\''),
+        Some(_) => line.get(i + 2) == Some(&'\''),
+        None => false,
+    }
+}
+
+fn find_string_end(rule: &StringRule, line: &[char], from: usize) -> Option<usize> {
+    let mut j = from;
+
+    while j < line.len() {
+        if rule.escape && line[j] == '\\' {
+            j += 2;
+        } else if starts_with_at(line, j, rule.close) {
+            return Some(j + rule.close.chars().count());
+        } else {
+            j += 1;
+        }
+    }
+
+    None
+}
+
+fn find_at(line: &[char], from: usize, pattern: &str) -> Option<usize> {
+    (from..line.len()).find(|&j| starts_with_at(line, j, pattern))
+}
+
+fn starts_with_at(line: &[char], i: usize, pattern: &str) -> bool {
+    !pattern.is_empty()
+        && pattern
+            .chars()
+            .enumerate()
+            .all(|(j, p)| line.get(i + j) == Some(&p))
+}
+
+fn scan_while(line: &[char], from: usize, f: impl Fn(char) -> bool) -> usize {
+    from + line[from.min(line.len())..]
+        .iter()
+        .take_while(|c| f(**c))
+        .count()
+}
+
+fn is_ident_char(grammar: &Grammar, c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || grammar.extra_ident_chars.contains(c)
+}
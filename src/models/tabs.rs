@@ -1,23 +1,222 @@
-use std::{cmp::min, path::PathBuf};
+use std::{
+    cmp::min,
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
 
-use super::text::TextFile;
+use ropey::Rope;
+
+use super::{
+    hex::{is_binary, HexFile},
+    large_file::{is_large, Load},
+    permissions::is_writable,
+    text::{PaneText, TextFile, View},
+};
 
 #[derive(PartialEq, Clone, Debug)]
 pub struct Tab {
     pub file: TextFile,
     pub exists: bool,
+    pub load: Load,
+    // binary files are edited as bytes, the text file only stands in for them
+    pub hex: Option<HexFile>,
+    // the pane whose caret and scroll are in `file`, the other panes keep theirs in `file.views`
+    view_of: Option<usize>,
 }
 
 impl Tab {
     pub fn new(file: TextFile) -> Self {
-        Self { file, exists: true }
+        Self {
+            file,
+            exists: true,
+            load: Load::Loaded,
+            hex: None,
+            view_of: None,
+        }
+    }
+
+    // stands in for a large file until it is confirmed and loaded
+    fn placeholder(path: PathBuf, size: u64) -> Self {
+        let mut file = TextFile::from_content(path, "");
+        file.large = true;
+        file.read_only = true;
+
+        Self {
+            load: Load::Confirm { size },
+            ..Self::new(file)
+        }
+    }
+
+    // the empty text file can never be saved over the binary one
+    fn binary(path: PathBuf) -> Self {
+        let (load, hex) = match HexFile::load(&path) {
+            Ok(hex) => (
+                Load::Loaded,
+                Some(HexFile {
+                    read_only: !is_writable(&path),
+                    ..hex
+                }),
+            ),
+            Err(e) => (Load::Failed(e.to_string()), None),
+        };
+
+        let mut file = TextFile::from_content(path, "");
+        file.read_only = true;
+
+        Self {
+            load,
+            hex,
+            ..Self::new(file)
+        }
+    }
+
+    // a pane that has not shown the file yet starts where the last one was
+    pub fn view(&self, pane: usize) -> View {
+        match self.view_of == Some(pane) {
+            true => self.file.view(),
+            false => self
+                .file
+                .views
+                .iter()
+                .find(|(id, _)| *id == pane)
+                .map_or_else(|| self.file.view(), |(_, view)| view.clone()),
+        }
+    }
+
+    fn load_view(&mut self, pane: usize) {
+        if self.view_of == Some(pane) {
+            return;
+        }
+
+        let view = self.view(pane);
+        if let Some(old) = self.view_of {
+            self.file.views.push((old, self.file.view()));
+        }
+
+        self.file.views.retain(|(id, _)| *id != pane);
+        self.file.set_view(&view);
+        self.view_of = Some(pane);
+    }
+
+    fn forget_view(&mut self, pane: usize) {
+        self.file.views.retain(|(id, _)| *id != pane);
+
+        if self.view_of == Some(pane) {
+            self.view_of = None;
+        }
+    }
+
+    fn copy_view(&mut self, from: usize, to: usize) {
+        let view = self.view(from);
+
+        if self.view_of != Some(to) {
+            self.file.views.retain(|(id, _)| *id != to);
+            self.file.views.push((to, view));
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.hex
+            .as_ref()
+            .map_or(self.file.read_only, |hex| hex.read_only)
+    }
+
+    // replace in files only edits tabs that show their text and may change it
+    pub fn accepts_replace(&self) -> bool {
+        self.hex.is_none() && self.load == Load::Loaded && !self.is_read_only()
+    }
+
+    // the text file standing in for a binary or a not yet loaded file stays read-only
+    pub fn set_read_only(&mut self, read_only: bool) {
+        match self.hex.as_mut() {
+            Some(hex) => hex.read_only = read_only,
+            None if self.load == Load::Loaded => self.file.read_only = read_only,
+            None => {}
+        }
+    }
+
+    pub fn content(&self) -> Vec<u8> {
+        match self.hex.as_ref() {
+            Some(hex) => hex.bytes.clone(),
+            None => self.file.to_string().into_bytes(),
+        }
+    }
+
+    // what was written by other means, like an elevated save, is no longer unsaved
+    pub fn mark_saved(&mut self) {
+        match self.hex.as_mut() {
+            Some(hex) => hex.dirty = false,
+            None => self.file.dirty_changes = None,
+        }
+    }
+
+    pub fn save(&mut self) -> io::Result<()> {
+        if self.is_read_only() {
+            return Err(io::Error::other("the file is read-only"));
+        }
+
+        match self.hex.as_mut() {
+            Some(hex) => hex.save(&self.file.path),
+            None => self.file.save_to_file(),
+        }
+    }
+}
+
+// how the panes are laid out next to each other
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum Split {
+    // side by side
+    #[default]
+    Vertical,
+    // one above the other
+    Horizontal,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct Pane {
+    // stays the same while other panes come and go
+    pub id: usize,
+    // the pane's tab strip, in order
+    pub tabs: Vec<PathBuf>,
+    // that many tabs at the start of the strip are pinned
+    pub pinned: usize,
+    pub current_file: Option<PathBuf>,
+    // most recently used first, for switching with ctrl+tab
+    pub recent: Vec<PathBuf>,
+    // where ctrl+tab is in `recent` while ctrl is still held
+    cycling: Option<usize>,
+}
+
+impl Pane {
+    fn new(id: usize) -> Self {
+        Self {
+            id,
+            tabs: Vec::new(),
+            pinned: 0,
+            current_file: None,
+            recent: Vec::new(),
+            cycling: None,
+        }
+    }
+
+    pub fn is_pinned(&self, path: &Path) -> bool {
+        self.tabs[..self.pinned].iter().any(|tab| tab == path)
     }
 }
 
 #[derive(PartialEq, Clone, Debug)]
 pub struct Tabs {
+    // every open file once, however many panes show it
     pub opened_tabs: Vec<Tab>,
+    // the current file of the focused pane
     pub current_file: Option<PathBuf>,
+    pub panes: Vec<Pane>,
+    pub focused: usize,
+    pub split: Split,
+    next_pane: usize,
+    // a tab being dragged to another pane, and the pane it comes from
+    dragged: Option<(usize, PathBuf)>,
 }
 
 impl Default for Tabs {
@@ -31,6 +230,11 @@ impl Tabs {
         Self {
             opened_tabs: Vec::new(),
             current_file: None,
+            panes: vec![Pane::new(0)],
+            focused: 0,
+            split: Split::default(),
+            next_pane: 1,
+            dragged: None,
         }
     }
 
@@ -40,6 +244,15 @@ impl Tabs {
             .and_then(|path| self.get_file(path))
     }
 
+    pub fn get_current_file_ref(&self) -> Option<&TextFile> {
+        self.current_file.as_ref().and_then(|path| {
+            self.opened_tabs
+                .iter()
+                .find(|file| &file.file.path == path)
+                .map(|file| &file.file)
+        })
+    }
+
     pub fn get_current_file_mut(&mut self) -> Option<&mut TextFile> {
         self.current_file.as_ref().and_then(|path| {
             self.opened_tabs
@@ -49,6 +262,20 @@ impl Tabs {
         })
     }
 
+    pub fn get_current_tab(&self) -> Option<&Tab> {
+        self.current_file
+            .as_ref()
+            .and_then(|path| self.opened_tabs.iter().find(|tab| &tab.file.path == path))
+    }
+
+    pub fn get_current_tab_mut(&mut self) -> Option<&mut Tab> {
+        self.current_file.as_ref().and_then(|path| {
+            self.opened_tabs
+                .iter_mut()
+                .find(|tab| &tab.file.path == path)
+        })
+    }
+
     pub fn get_file(&self, path: &PathBuf) -> Option<TextFile> {
         self.opened_tabs
             .iter()
@@ -56,29 +283,131 @@ impl Tabs {
             .map(|file| file.file.clone())
     }
 
-    pub fn open_tab(&mut self, path: PathBuf) {
-        self.set_current_file(path.clone());
+    pub fn get_file_mut(&mut self, path: &PathBuf) -> Option<&mut TextFile> {
+        self.opened_tabs
+            .iter_mut()
+            .find(|file| &file.file.path == path)
+            .map(|file| &mut file.file)
+    }
 
+    // the text of opened files that differs from what is on disk
+    pub fn unsaved_buffers(&self) -> HashMap<PathBuf, String> {
+        self.opened_tabs
+            .iter()
+            .filter(|tab| tab.hex.is_none() && tab.file.dirty_changes.is_some())
+            .map(|tab| (tab.file.path.clone(), tab.file.to_string()))
+            .collect()
+    }
+
+    pub fn open_tab(&mut self, path: PathBuf) {
         if self.get_file(&path).is_none() {
-            self.opened_tabs.push(Tab::new(TextFile::new(path)));
+            let tab = match is_large(&path) {
+                _ if is_binary(&path) => Tab::binary(path.clone()),
+                Some(size) => Tab::placeholder(path.clone(), size),
+                None => Tab::new(TextFile::new(path.clone())),
+            };
+
+            self.opened_tabs.push(tab);
+        }
+
+        self.set_current_file(path);
+    }
+
+    // files shared by someone else, like those of a session joined as a guest
+    pub fn open_read_only(&mut self, path: PathBuf) {
+        self.open_tab(path.clone());
+
+        if let Some(tab) = self.get_tab_mut(&path) {
+            tab.set_read_only(true);
+        }
+    }
+
+    pub fn get_tab(&self, path: &Path) -> Option<&Tab> {
+        self.opened_tabs.iter().find(|tab| tab.file.path == path)
+    }
+
+    pub fn get_tab_mut(&mut self, path: &Path) -> Option<&mut Tab> {
+        self.opened_tabs
+            .iter_mut()
+            .find(|tab| tab.file.path == path)
+    }
+
+    // does nothing when the tab was closed in the meantime
+    pub fn set_load(&mut self, path: &Path, load: Load) {
+        if let Some(tab) = self.get_tab_mut(path) {
+            tab.load = load;
         }
     }
 
+    pub fn finish_load(&mut self, path: &Path, rope: Rope) {
+        if let Some(tab) = self.get_tab_mut(path) {
+            tab.file = TextFile::from_rope(path.to_path_buf(), rope);
+            tab.load = Load::Loaded;
+        }
+    }
+
+    // closes the file in every pane showing it
     pub fn close_tab(&mut self, path: PathBuf) {
-        for (i, tab) in self.opened_tabs.iter().enumerate() {
-            if tab.file.path == path {
-                self.opened_tabs.remove(i);
-                self.current_file = match self.opened_tabs.len() {
-                    0 => None,
-                    l => self
-                        .opened_tabs
-                        .get(min(i, l - 1))
-                        .map(|f| f.file.path.clone()),
-                };
-
-                break;
+        let panes = self
+            .panes
+            .iter()
+            .filter(|pane| pane.tabs.contains(&path))
+            .map(|pane| pane.id)
+            .collect::<Vec<_>>();
+
+        for pane in panes {
+            self.close_tab_in(pane, &path);
+        }
+
+        self.opened_tabs.retain(|tab| tab.file.path != path);
+    }
+
+    // the file stays open while another pane still shows it, an emptied pane goes away unless
+    // it is the last one
+    pub fn close_tab_in(&mut self, pane: usize, path: &Path) {
+        let Some(i) = self.panes.iter().position(|p| p.id == pane) else {
+            return;
+        };
+
+        let strip = &mut self.panes[i];
+        let Some(at) = strip.tabs.iter().position(|tab| tab == path) else {
+            return;
+        };
+
+        strip.tabs.remove(at);
+        strip.recent.retain(|tab| tab != path);
+        strip.cycling = None;
+        if at < strip.pinned {
+            strip.pinned -= 1;
+        }
+        if strip.current_file.as_deref() == Some(path) {
+            strip.current_file = match strip.tabs.len() {
+                0 => None,
+                l => strip.tabs.get(min(at, l - 1)).cloned(),
+            };
+        }
+
+        if let Some(tab) = self.get_tab_mut(path) {
+            tab.forget_view(pane);
+        }
+
+        if !self
+            .panes
+            .iter()
+            .any(|p| p.tabs.iter().any(|tab| tab == path))
+        {
+            self.opened_tabs.retain(|tab| tab.file.path != path);
+        }
+
+        if self.panes[i].tabs.is_empty() && self.panes.len() > 1 {
+            self.panes.remove(i);
+
+            if self.focused == pane {
+                self.focused = self.panes[min(i, self.panes.len() - 1)].id;
             }
         }
+
+        self.focus_pane(self.focused);
     }
 
     pub fn update_existance(&mut self, path: PathBuf, exists: bool) {
@@ -91,7 +420,320 @@ impl Tabs {
         }
     }
 
+    // shows the file in the focused pane, adding it to the pane's tab strip when needed
     pub fn set_current_file(&mut self, path: PathBuf) {
-        self.current_file = Some(path.clone());
+        let focused = self.focused;
+
+        if let Some(pane) = self.panes.iter_mut().find(|pane| pane.id == focused) {
+            if !pane.tabs.contains(&path) {
+                pane.tabs.push(path.clone());
+            }
+            if pane.cycling.is_none() {
+                pane.recent.retain(|tab| tab != &path);
+                pane.recent.insert(0, path.clone());
+            }
+            pane.current_file = Some(path.clone());
+        }
+
+        if let Some(tab) = self.get_tab_mut(&path) {
+            tab.load_view(focused);
+        }
+
+        self.current_file = Some(path);
+    }
+
+    pub fn get_pane(&self, pane: usize) -> Option<&Pane> {
+        self.panes.iter().find(|p| p.id == pane)
+    }
+
+    // the focused pane's caret and scroll are always the ones in its current file
+    pub fn focus_pane(&mut self, pane: usize) {
+        let Some(current) = self.get_pane(pane).map(|p| p.current_file.clone()) else {
+            return;
+        };
+
+        self.focused = pane;
+        self.current_file = current.clone();
+
+        if let Some(tab) = current.and_then(|path| self.get_tab_mut(&path)) {
+            tab.load_view(pane);
+        }
     }
+
+    pub fn select_tab(&mut self, pane: usize, path: PathBuf) {
+        self.focus_pane(pane);
+        self.set_current_file(path);
+    }
+
+    pub fn get_pane_tab(&self, pane: usize) -> Option<&Tab> {
+        self.get_pane(pane)?
+            .current_file
+            .as_ref()
+            .and_then(|path| self.get_tab(path))
+    }
+
+    pub fn get_pane_tab_mut(&mut self, pane: usize) -> Option<&mut Tab> {
+        let path = self.get_pane(pane)?.current_file.clone()?;
+        self.get_tab_mut(&path)
+    }
+
+    // every pane draws the shared file with its own caret and scroll
+    pub fn get_pane_text(&self, pane: usize) -> Option<PaneText<'_>> {
+        let tab = self.get_pane_tab(pane)?;
+
+        Some(tab.file.pane_text(tab.view(pane)))
+    }
+
+    // changes what a pane shows without focusing it, like scrolling it with the wheel
+    pub fn with_pane_file<R>(
+        &mut self,
+        pane: usize,
+        f: impl FnOnce(&mut TextFile) -> R,
+    ) -> Option<R> {
+        let path = self.get_pane(pane)?.current_file.clone()?;
+        let focused = self.focused;
+        let shown_focused = self.current_file.as_ref() == Some(&path);
+
+        let tab = self.get_tab_mut(&path)?;
+        tab.load_view(pane);
+        let result = f(&mut tab.file);
+
+        if shown_focused {
+            tab.load_view(focused);
+        }
+
+        Some(result)
+    }
+
+    // a new pane next to the focused one, showing the same file with a caret of its own
+    pub fn split(&mut self, split: Split) {
+        let id = self.next_pane;
+        self.next_pane += 1;
+        self.split = split;
+
+        let at = self
+            .panes
+            .iter()
+            .position(|pane| pane.id == self.focused)
+            .map_or(self.panes.len(), |i| i + 1);
+        self.panes.insert(at, Pane::new(id));
+
+        let current = self.current_file.clone();
+        self.focus_pane(id);
+
+        if let Some(path) = current {
+            self.set_current_file(path);
+        }
+    }
+
+    pub fn move_tab(&mut self, path: PathBuf, from: usize, to: usize) {
+        if from == to || self.get_pane(to).is_none() {
+            return;
+        }
+
+        if let Some(tab) = self.get_tab_mut(&path) {
+            tab.copy_view(from, to);
+        }
+
+        self.focus_pane(to);
+        self.set_current_file(path.clone());
+        self.close_tab_in(from, &path);
+        self.focus_pane(to);
+    }
+
+    pub fn start_drag(&mut self, pane: usize, path: PathBuf) {
+        self.dragged = Some((pane, path));
+    }
+
+    // dropped onto a tab the dragged one takes its place, onto the rest of the strip it goes last
+    pub fn drop_tab(&mut self, pane: usize, at: Option<usize>) {
+        let Some((from, path)) = self.dragged.take() else {
+            return;
+        };
+
+        self.move_tab(path.clone(), from, pane);
+
+        if let Some(at) = at {
+            self.reorder_tab(pane, &path, at);
+        }
+    }
+
+    // pinned and unpinned tabs stay in their own part of the strip
+    pub fn reorder_tab(&mut self, pane: usize, path: &Path, to: usize) {
+        let Some(pane) = self.panes.iter_mut().find(|p| p.id == pane) else {
+            return;
+        };
+        let Some(from) = pane.tabs.iter().position(|tab| tab == path) else {
+            return;
+        };
+
+        let tab = pane.tabs.remove(from);
+        let to = match from < pane.pinned {
+            true => min(to, pane.pinned - 1),
+            false => to.clamp(pane.pinned, pane.tabs.len()),
+        };
+        pane.tabs.insert(to, tab);
+    }
+
+    pub fn toggle_pin(&mut self, pane: usize, path: &Path) {
+        let Some(pane) = self.panes.iter_mut().find(|p| p.id == pane) else {
+            return;
+        };
+        let Some(at) = pane.tabs.iter().position(|tab| tab == path) else {
+            return;
+        };
+
+        // either way the tab ends up where pinned and unpinned tabs meet
+        let tab = pane.tabs.remove(at);
+        if at < pane.pinned {
+            pane.pinned -= 1;
+            pane.tabs.insert(pane.pinned, tab);
+        } else {
+            pane.tabs.insert(pane.pinned, tab);
+            pane.pinned += 1;
+        }
+    }
+
+    // pinned tabs stay open
+    pub fn close_others(&mut self, pane: usize, path: &Path) {
+        self.close_unpinned(pane, |_, tab| tab != path);
+        self.select_tab(pane, path.to_path_buf());
+    }
+
+    pub fn close_to_the_right(&mut self, pane: usize, path: &Path) {
+        let Some(at) = self
+            .get_pane(pane)
+            .and_then(|p| p.tabs.iter().position(|tab| tab == path))
+        else {
+            return;
+        };
+
+        self.close_unpinned(pane, |i, _| i > at);
+    }
+
+    fn close_unpinned(&mut self, pane: usize, close: impl Fn(usize, &Path) -> bool) {
+        let Some(strip) = self.get_pane(pane) else {
+            return;
+        };
+
+        let closed = strip
+            .tabs
+            .iter()
+            .enumerate()
+            .skip(strip.pinned)
+            .filter(|(i, tab)| close(*i, tab))
+            .map(|(_, tab)| tab.clone())
+            .collect::<Vec<_>>();
+
+        for path in closed {
+            self.close_tab_in(pane, &path);
+        }
+    }
+
+    // each press goes one file further back in the focused pane's history, releasing ctrl ends it
+    pub fn cycle_recent(&mut self, reverse: bool) {
+        let focused = self.focused;
+        let Some(pane) = self.panes.iter_mut().find(|p| p.id == focused) else {
+            return;
+        };
+
+        let len = pane.recent.len();
+        if len < 2 {
+            return;
+        }
+
+        let at = match (pane.cycling, reverse) {
+            (None, false) => 1,
+            (None, true) => len - 1,
+            (Some(at), false) => (at + 1) % len,
+            (Some(at), true) => (at + len - 1) % len,
+        };
+        pane.cycling = Some(at);
+
+        let path = pane.recent[at].clone();
+        self.set_current_file(path);
+    }
+
+    pub fn finish_cycle(&mut self) {
+        let focused = self.focused;
+        let Some(pane) = self.panes.iter_mut().find(|p| p.id == focused) else {
+            return;
+        };
+
+        if pane.cycling.take().is_some() {
+            if let Some(path) = pane.current_file.clone() {
+                pane.recent.retain(|tab| tab != &path);
+                pane.recent.insert(0, path);
+            }
+        }
+    }
+
+    // keeps tabs open when their file, or a directory above it, was moved
+    pub fn rename_path(&mut self, old: &Path, new: &Path) {
+        let renamed = |path: &PathBuf| {
+            path.strip_prefix(old)
+                .ok()
+                .map(|rest| match rest.as_os_str().is_empty() {
+                    true => new.to_path_buf(),
+                    false => new.join(rest),
+                })
+        };
+
+        for tab in self.opened_tabs.iter_mut() {
+            if let Some(path) = renamed(&tab.file.path) {
+                tab.file.path = path;
+            }
+        }
+
+        for pane in self.panes.iter_mut() {
+            let paths = pane.tabs.iter_mut().chain(pane.recent.iter_mut());
+            for path in paths.chain(pane.current_file.as_mut()) {
+                if let Some(new_path) = renamed(path) {
+                    *path = new_path;
+                }
+            }
+        }
+
+        if let Some(path) = self.current_file.as_ref().and_then(renamed) {
+            self.current_file = Some(path);
+        }
+    }
+}
+
+// tabs of files with the same name also show as many of their parent directories as it takes
+// to tell them apart
+pub fn distinct_parents(paths: &[PathBuf]) -> Vec<Option<String>> {
+    let parents = |path: &Path, depth: usize| {
+        let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let skip = parent.components().count().saturating_sub(depth);
+        parent.components().skip(skip).collect::<PathBuf>()
+    };
+
+    paths
+        .iter()
+        .map(|path| {
+            let namesakes = paths
+                .iter()
+                .filter(|other| *other != path && other.file_name() == path.file_name())
+                .collect::<Vec<_>>();
+
+            if namesakes.is_empty() {
+                return None;
+            }
+
+            let depth = path
+                .parent()
+                .map_or(0, |parent| parent.components().count());
+            let depth = (1..depth)
+                .find(|depth| {
+                    namesakes
+                        .iter()
+                        .all(|other| parents(other, *depth) != parents(path, *depth))
+                })
+                .unwrap_or(depth);
+            let parent = parents(path, depth);
+
+            Some(parent.display().to_string())
+        })
+        .collect()
 }
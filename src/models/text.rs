@@ -1,10 +1,25 @@
 use std::{
     cmp::{max, min},
     fs::read_to_string,
+    io,
+    ops::{Range, RangeInclusive},
     path::PathBuf,
 };
 
-use super::historyevent::HistoryEvent;
+use super::{
+    brackets::{auto_pairs, closing_bracket, matching_bracket},
+    clipboard::ClipEntry,
+    folding::Folding,
+    historyevent::HistoryEvent,
+    indentation::Indentation,
+    lines::{split, LineCommand},
+    permissions::is_writable,
+    search::{find_first, MatchCache},
+    syntax::{highlighter::Highlighter, languages::Language},
+    transform::{Hash, Transform},
+    unicode::{display_width, grapheme_boundaries, word_segments},
+    wrap::{WrapCache, WrappedLine},
+};
 use ropey::Rope;
 
 #[derive(Debug, Clone, PartialEq, Copy)]
@@ -28,6 +43,49 @@ impl Caret {
     }
 }
 
+// what one pane shows of a file, panes showing the same file each keep their own
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct View {
+    pub char_idx: usize,
+    pub selection: Option<(usize, usize)>,
+    pub scroll_offset: usize,
+    pub wrap_width: Option<usize>,
+}
+
+// a pane's view of a file, drawn from the shared file instead of a copy of it
+pub struct PaneText<'a> {
+    pub file: &'a TextFile,
+    pub view: View,
+}
+
+impl PaneText<'_> {
+    pub fn caret(&self) -> Caret {
+        self.file.get_caret_from_idx(self.view.char_idx)
+    }
+
+    pub fn display_col(&self) -> usize {
+        self.file.display_col_at(self.view.char_idx)
+    }
+
+    pub fn selection_range(&self) -> Option<Range<usize>> {
+        self.view
+            .selection
+            .map(|(start, end)| min(start, end)..max(start, end))
+    }
+
+    pub fn matching_brackets(&self) -> Option<(usize, usize)> {
+        self.file.brackets_at(self.view.char_idx)
+    }
+
+    pub fn wrapped_line(&self, ln: usize) -> WrappedLine {
+        self.file.wrapped_line_in(ln, self.view.wrap_width)
+    }
+
+    pub fn char_idx_at_row(&self, row: usize, column: usize) -> usize {
+        self.file.char_idx_at_row_in(&self.view, row, column)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TextFile {
     pub path: PathBuf,
@@ -37,21 +95,49 @@ pub struct TextFile {
     pub event_history: Vec<HistoryEvent>,
     pub history_idx: usize,
     pub dirty_changes: Option<usize>,
+    // entries below this are not merged with new ones, see `break_history`
+    history_break: Option<usize>,
 
     pub selection: Option<(usize, usize)>,
+
+    pub scroll_offset: usize,
+    // columns per visual row, lines are not wrapped when unset
+    pub wrap_width: Option<usize>,
+    pub wraps: WrapCache,
+
+    pub highlighter: Highlighter,
+    pub folding: Folding,
+    pub matches: MatchCache,
+    pub indentation: Indentation,
+
+    // large files skip highlighting and other whole-file work
+    pub large: bool,
+    pub read_only: bool,
+
+    // the views of the other panes showing the file, edits move them along with the text
+    pub views: Vec<(usize, View)>,
 }
 
 impl TextFile {
     pub fn new(path: PathBuf) -> Self {
-        let mut content = read_to_string(&path)
-            .ok()
-            .unwrap_or_default()
-            .replace("\r\n", "\n");
+        let content = read_to_string(&path).ok().unwrap_or_default();
+        let read_only = !is_writable(&path);
+
+        Self {
+            read_only,
+            ..Self::from_content(path, &content)
+        }
+    }
+
+    pub fn from_content(path: PathBuf, content: &str) -> Self {
+        let mut content = content.replace("\r\n", "\n");
 
         if !content.ends_with('\n') {
             content.push('\n');
         }
 
+        let language = Language::detect(&path, content.lines().next());
+
         Self {
             path,
             rope: Rope::from_str(&content),
@@ -59,89 +145,203 @@ impl TextFile {
             event_history: Vec::new(),
             history_idx: 0,
             dirty_changes: None,
+            history_break: None,
             selection: None,
+            scroll_offset: 0,
+            wrap_width: None,
+            wraps: WrapCache::default(),
+            highlighter: Highlighter::new(language),
+            folding: Folding::default(),
+            matches: MatchCache::default(),
+            indentation: Indentation::detect(&content).unwrap_or_default(),
+            large: false,
+            read_only: false,
+            views: Vec::new(),
+        }
+    }
+
+    // a large file loaded in the background, it opens read-only and without a language
+    pub fn from_rope(path: PathBuf, rope: Rope) -> Self {
+        let head = rope
+            .lines()
+            .take(1000)
+            .map(|line| line.to_string())
+            .collect::<String>();
+
+        Self {
+            rope,
+            highlighter: Highlighter::new(None),
+            indentation: Indentation::detect(&head).unwrap_or_default(),
+            large: true,
+            read_only: true,
+            ..Self::from_content(path, "")
         }
     }
 
-    pub fn save_to_file(&mut self) {
-        let content = self.to_string();
+    // the caret of another pane showing the file, see `PaneText`
+    pub fn pane_text(&self, view: View) -> PaneText<'_> {
+        PaneText { file: self, view }
+    }
 
-        match std::fs::write(&self.path, content) {
-            Ok(_) => {
-                self.dirty_changes = None;
-            }
-            Err(_e) => {}
+    pub fn view(&self) -> View {
+        View {
+            char_idx: self.char_idx,
+            selection: self.selection,
+            scroll_offset: self.scroll_offset,
+            wrap_width: self.wrap_width,
+        }
+    }
+
+    // the text may have changed in another pane since the view was taken
+    pub fn set_view(&mut self, view: &View) {
+        let last = self.rope.len_chars().saturating_sub(1);
+        let clamp = |idx: usize| min(idx, last);
+
+        self.char_idx = self.snap_to_grapheme(clamp(view.char_idx));
+        self.selection = view
+            .selection
+            .map(|(anchor, caret)| (clamp(anchor), clamp(caret)))
+            .filter(|(anchor, caret)| anchor != caret);
+        self.scroll_offset = min(view.scroll_offset, self.len_lines().saturating_sub(1));
+        self.wrap_width = view.wrap_width;
+    }
+
+    pub fn save_to_file(&mut self) -> io::Result<()> {
+        if self.read_only {
+            return Err(io::Error::other("the file is read-only"));
         }
+
+        std::fs::write(&self.path, self.to_string())?;
+        self.dirty_changes = None;
+
+        Ok(())
     }
 
     pub fn to_string(&self) -> String {
         self.rope.to_string()
     }
 
-    pub fn chars(&self) -> Vec<String> {
-        self.rope
-            .lines()
-            .filter_map(|line| {
-                if line.len_chars() == 0 {
-                    None
-                } else {
-                    Some(line.to_string())
-                }
-            })
+    pub fn lines(&self, range: Range<usize>) -> Vec<String> {
+        range
+            .filter_map(|i| self.rope.get_line(i))
+            .filter(|line| line.len_chars() != 0)
+            .map(|line| line.to_string())
             .collect()
     }
 
-    pub fn get_caret(&self) -> Caret {
-        let mut char_sum = 0;
+    // the rope always ends with a newline, its empty last line is never rendered
+    pub fn len_lines(&self) -> usize {
+        max(self.rope.len_lines(), 2) - 1
+    }
 
-        for (i, line) in self.rope.lines().enumerate() {
-            char_sum += line.len_chars();
-            if char_sum > self.char_idx {
-                let caret_ln = i;
-                let caret_col = self.char_idx - (char_sum - line.len_chars());
-                return Caret::from(caret_ln, caret_col);
-            }
+    pub fn scroll_to(&mut self, line: usize) {
+        self.scroll_offset = self
+            .folding
+            .visible_line(&self.rope, min(line, self.len_lines() - 1));
+    }
+
+    // folded regions count as a single line
+    pub fn scroll_by(&mut self, lines: isize) {
+        for _ in 0..lines.unsigned_abs() {
+            self.scroll_offset = match lines > 0 {
+                true => min(
+                    self.folding.next_visible(&self.rope, self.scroll_offset),
+                    self.len_lines() - 1,
+                ),
+                false => self.prev_visible(self.scroll_offset),
+            };
+        }
+    }
+
+    // the lines on screen starting at `first`, skipping the ones inside folded regions
+    pub fn visible_lines(&self, first: usize, count: usize) -> Vec<usize> {
+        let mut lines = Vec::new();
+        let mut ln = self.folding.visible_line(&self.rope, first);
+
+        while ln < self.len_lines() && lines.len() < count {
+            lines.push(ln);
+            ln = self.folding.next_visible(&self.rope, ln);
         }
 
-        Caret::from(
-            self.rope.len_lines() - 1,
-            self.rope.line(self.rope.len_lines() - 1).len_chars(),
-        )
+        lines
     }
 
-    pub fn get_caret_from_idx(&self, idx: usize) -> Caret {
-        let mut char_sum = 0;
+    fn prev_visible(&self, ln: usize) -> usize {
+        self.folding.visible_line(&self.rope, ln.saturating_sub(1))
+    }
+
+    pub fn wrapped_line(&self, ln: usize) -> WrappedLine {
+        self.wrapped_line_in(ln, self.wrap_width)
+    }
+
+    fn wrapped_line_in(&self, ln: usize, wrap_width: Option<usize>) -> WrappedLine {
+        self.wraps.get(ln, wrap_width, || {
+            let line = self
+                .rope
+                .line(ln)
+                .chars()
+                .filter(|c| *c != '\n')
+                .collect::<Vec<_>>();
+
+            WrappedLine::new(&line, wrap_width)
+        })
+    }
+
+    // `visible_lines` counts visual rows, a wrapped line takes several of them
+    pub fn scroll_to_caret(&mut self, visible_lines: usize) {
+        let caret = self.get_caret();
+
+        if caret.ln < self.scroll_offset {
+            self.scroll_to(caret.ln);
+            return;
+        }
+
+        if visible_lines == 0 {
+            return;
+        }
 
-        for (i, line) in self.rope.lines().enumerate() {
-            char_sum += line.len_chars();
-            if char_sum > idx {
-                let caret_ln = i;
-                let caret_col = idx - (char_sum - line.len_chars());
-                return Caret::from(caret_ln, caret_col);
+        // walk up from the caret row while the lines above still fit
+        let mut rows = self.wrapped_line(caret.ln).row_of(caret.col) + 1;
+        let mut top = caret.ln;
+
+        while top > self.scroll_offset {
+            let prev = self.prev_visible(top);
+            let above = self.wrapped_line(prev).rows();
+            if rows + above > visible_lines {
+                break;
             }
+
+            rows += above;
+            top = prev;
+        }
+
+        if top > self.scroll_offset {
+            self.scroll_to(top);
         }
+    }
+
+    pub fn get_caret(&self) -> Caret {
+        self.get_caret_from_idx(self.char_idx)
+    }
 
-        Caret::from(
-            self.rope.len_lines() - 1,
-            self.rope.line(self.rope.len_lines() - 1).len_chars(),
-        )
+    pub fn get_caret_from_idx(&self, idx: usize) -> Caret {
+        let idx = min(idx, self.rope.len_chars());
+        let ln = self.rope.char_to_line(idx);
+
+        Caret::from(ln, idx - self.rope.line_to_char(ln))
     }
 
     pub fn get_char_idx(&self, caret: Caret) -> usize {
-        let mut char_sum = 0;
-        for (i, line) in self.rope.lines().enumerate() {
-            if i == caret.ln {
-                return char_sum + caret.col;
-            }
-            char_sum += line.len_chars();
+        match caret.ln < self.rope.len_lines() {
+            true => self.rope.line_to_char(caret.ln) + caret.col,
+            false => self.rope.len_chars() - 1,
         }
-
-        self.rope.len_chars() - 1
     }
 
     pub fn set_caret_position(&mut self, line: usize, column: usize, selection: bool) {
         let old_idx = self.char_idx;
         self.char_idx = self.get_char_idx(Caret::from(line, column));
+        self.reveal_caret();
 
         if selection {
             self.set_selection(true, old_idx);
@@ -157,32 +357,15 @@ impl TextFile {
             }
 
             (false, i) => {
-                self.char_idx = i - 1;
+                self.char_idx = self.prev_grapheme(i);
             }
 
             (true, i) => {
-                // let mut new_char_idx = i - 1;
-
-                // let mut skipped_whitespace = !self.rope.char(new_char_idx).is_ascii_whitespace();
-
-                // while new_char_idx > 0 &&
-                //     self.rope.char(new_char_idx - 1) != '\n' &&
-                //     (self.rope.char(new_char_idx).is_ascii_alphanumeric() || !skipped_whitespace) &&
-                //     (self.rope.char(new_char_idx).is_ascii_whitespace() || skipped_whitespace) {
-
-                //     new_char_idx -= 1;
-
-                //     if !self.rope.char(new_char_idx).is_ascii_whitespace() {
-                //         skipped_whitespace = true;
-                //     }
-                // }
-
-                // self.char_idx = new_char_idx;
-
-                self.char_idx = i - 1;
-                self.char_idx = self.ctrl_idx_move(true);
+                self.char_idx = self.word_move(i, true);
             }
         }
+
+        self.skip_folded(false);
     }
 
     pub fn caret_move_right(&mut self, ctrl: bool) {
@@ -192,108 +375,251 @@ impl TextFile {
             }
 
             (false, i) => {
-                self.char_idx = i + 1;
+                self.char_idx = self.next_grapheme(i);
             }
 
             (true, i) => {
-                self.char_idx = i + 1;
-                self.char_idx = self.ctrl_idx_move(false);
+                self.char_idx = self.word_move(i, false);
             }
         }
-    }
 
-    fn ctrl_idx_move(&self, go_left: bool) -> usize {
-        let mut idx = self.char_idx;
+        self.skip_folded(true);
+    }
 
-        let mv = |i: usize| match (go_left, i) {
-            (true, 0) => 0,
-            (false, i) if i + 1 >= self.rope.len_chars() => self.rope.len_chars() - 1,
-            (true, i) => i - 1,
-            (false, i) => i + 1,
+    // the caret never rests inside a folded region, it jumps over it or back to its first line
+    fn skip_folded(&mut self, forward: bool) {
+        let ln = self.rope.char_to_line(self.char_idx);
+        let Some(start) = self.folding.hidden_by(&self.rope, ln) else {
+            return;
         };
 
-        let mut skipped_whitespace = !self.rope.char(idx).is_ascii_whitespace();
+        let next = self.folding.next_visible(&self.rope, start);
 
-        while idx != mv(idx)
-            && self.rope.char(idx) != '\n'
-            && (self.rope.char(idx).is_ascii_alphanumeric() || !skipped_whitespace)
-            && (self.rope.char(idx).is_ascii_whitespace() || skipped_whitespace)
-            && (!go_left || !skipped_whitespace || !self.rope.char(mv(idx)).is_ascii_whitespace())
-        {
-            idx = mv(idx);
+        self.char_idx = match forward && next < self.len_lines() {
+            true => self.rope.line_to_char(next),
+            false => self.rope.line_to_char(start + 1) - 1,
+        };
+    }
 
-            if !self.rope.char(idx).is_ascii_whitespace() {
-                skipped_whitespace = true;
-            }
+    fn reveal_caret(&mut self) {
+        let ln = self.get_caret().ln;
+        self.folding.reveal(&self.rope, ln);
+    }
+
+    pub fn toggle_fold(&mut self, ln: usize) {
+        if self.large {
+            return;
         }
 
-        idx
+        self.folding.toggle(&self.rope, ln);
+        self.skip_folded(false);
     }
 
-    pub fn caret_move_down(&mut self) {
-        let caret = self.get_caret();
-
-        if caret.ln + 1 == self.rope.len_lines() {
+    pub fn fold_at_caret(&mut self) {
+        if self.large {
             return;
         }
 
-        let Some(line) = self.rope.get_line(caret.ln) else {
-            return;
-        };
+        let ln = self.get_caret().ln;
 
-        let Some(next_line) = self.rope.get_line(caret.ln + 1) else {
-            return;
-        };
+        if let Some(start) = self.folding.region_at(&self.rope, ln) {
+            self.folding.fold(&self.rope, start);
+            self.skip_folded(false);
+        }
+    }
 
-        if next_line.len_chars() == 0 {
-            self.char_idx = self.rope.len_chars() - 1;
+    pub fn unfold_at_caret(&mut self) {
+        let ln = self.get_caret().ln;
+        self.folding.unfold(ln);
+    }
+
+    pub fn fold_all(&mut self) {
+        if self.large {
             return;
         }
 
-        if caret.col < next_line.len_chars() {
-            self.char_idx += line.len_chars();
-        } else {
-            self.char_idx =
-                self.char_idx - caret.col + line.len_chars() + next_line.len_chars() - 1;
+        self.folding.fold_all(&self.rope);
+        self.scroll_to(self.scroll_offset);
+        self.skip_folded(false);
+    }
+
+    pub fn unfold_all(&mut self) {
+        self.folding.unfold_all();
+    }
+
+    fn line_text(&self, ln: usize) -> String {
+        let line = self.rope.line(ln);
+        line.slice(..self.line_len(ln)).to_string()
+    }
+
+    // the start of the grapheme before `idx`, the newline before a line is a grapheme of its own
+    pub fn prev_grapheme(&self, idx: usize) -> usize {
+        let ln = self.rope.char_to_line(idx);
+        let line_start = self.rope.line_to_char(ln);
+
+        if idx == line_start {
+            return idx.saturating_sub(1);
         }
+
+        grapheme_boundaries(&self.line_text(ln))
+            .into_iter()
+            .rev()
+            .find(|col| line_start + col < idx)
+            .map_or(idx - 1, |col| line_start + col)
     }
 
-    pub fn caret_move_up(&mut self) {
-        let caret = self.get_caret();
+    pub fn next_grapheme(&self, idx: usize) -> usize {
+        let ln = self.rope.char_to_line(idx);
+        let line_start = self.rope.line_to_char(ln);
 
-        if caret.ln == 0 {
-            self.char_idx = 0;
-            return;
+        let next = grapheme_boundaries(&self.line_text(ln))
+            .into_iter()
+            .find(|col| line_start + col > idx)
+            .map_or(idx + 1, |col| line_start + col);
+
+        min(next, self.rope.len_chars() - 1)
+    }
+
+    // a column in the middle of a grapheme goes back to where it starts
+    fn snap_to_grapheme(&self, idx: usize) -> usize {
+        let ln = self.rope.char_to_line(idx);
+        let line_start = self.rope.line_to_char(ln);
+
+        grapheme_boundaries(&self.line_text(ln))
+            .into_iter()
+            .rev()
+            .find(|col| line_start + col <= idx)
+            .map_or(idx, |col| line_start + col)
+    }
+
+    // ctrl+arrows go over unicode words, whitespace before a word is skipped along with it,
+    // symbols are passed one at a time
+    fn word_move(&self, idx: usize, go_left: bool) -> usize {
+        let ln = self.rope.char_to_line(idx);
+        let line_start = self.rope.line_to_char(ln);
+        let line_end = line_start + self.line_len(ln);
+
+        // the newline is a stop of its own, going right goes on to the first word of the next line
+        match go_left {
+            true if idx == line_start => return idx.saturating_sub(1),
+            false if idx >= line_end => {
+                let next = min(idx + 1, self.rope.len_chars() - 1);
+
+                return match next == idx || self.rope.char(next) == '\n' {
+                    true => next,
+                    false => self.word_move(next, false),
+                };
+            }
+            _ => {}
         }
 
-        let Some(_line) = self.rope.get_line(caret.ln) else {
-            return;
+        let line = self.line_text(ln);
+        let segments = word_segments(&line);
+        let col = idx - line_start;
+
+        let is_space = |i: usize| {
+            line.chars()
+                .skip(segments[i].start)
+                .take(segments[i].len())
+                .all(char::is_whitespace)
         };
 
-        let Some(prev_line) = self.rope.get_line(caret.ln - 1) else {
-            return;
+        let col = match go_left {
+            true => {
+                let mut i = segments.iter().rposition(|s| s.start < col).unwrap_or(0);
+                if is_space(i) && i > 0 {
+                    i -= 1;
+                }
+                segments[i].start
+            }
+            false => {
+                let mut i = segments.iter().position(|s| s.end > col).unwrap_or(0);
+                if is_space(i) && i + 1 < segments.len() {
+                    i += 1;
+                }
+                segments[i].end
+            }
         };
 
-        if caret.col < prev_line.len_chars() {
-            self.char_idx -= prev_line.len_chars();
-        } else {
-            self.char_idx -= caret.col + 1;
-        }
+        line_start + col
+    }
+
+    // the column the caret is drawn at, unlike `get_caret().col` it counts cells instead of chars
+    pub fn display_col(&self) -> usize {
+        self.display_col_at(self.char_idx)
+    }
+
+    fn display_col_at(&self, idx: usize) -> usize {
+        let caret = self.get_caret_from_idx(idx);
+        let line = self.rope.line(caret.ln).slice(..caret.col).to_string();
+
+        display_width(&line, self.indentation.width())
+    }
+
+    pub fn caret_move_down(&mut self) {
+        self.caret_move_row(true);
+    }
+
+    pub fn caret_move_up(&mut self) {
+        self.caret_move_row(false);
+    }
+
+    // moves by visual rows, keeping the column the caret is drawn at
+    fn caret_move_row(&mut self, go_down: bool) {
+        let caret = self.get_caret();
+        let wrapped = self.wrapped_line(caret.ln);
+        let row = wrapped.row_of(caret.col);
+        let x = wrapped.x_of(caret.col);
+
+        let next = self.folding.next_visible(&self.rope, caret.ln);
+        let prev = self.prev_visible(caret.ln);
+
+        let (ln, target) = match go_down {
+            true if row + 1 < wrapped.rows() => (caret.ln, wrapped),
+            true if next < self.len_lines() => (next, self.wrapped_line(next)),
+            false if row > 0 => (caret.ln, wrapped),
+            false if caret.ln > 0 => (prev, self.wrapped_line(prev)),
+            true => {
+                self.char_idx = self.rope.len_chars() - 1;
+                return;
+            }
+            false => {
+                self.char_idx = 0;
+                return;
+            }
+        };
+
+        let target_row = match (go_down, ln == caret.ln) {
+            (true, true) => row + 1,
+            (true, false) => 0,
+            (false, true) => row - 1,
+            (false, false) => target.rows() - 1,
+        };
+
+        self.char_idx =
+            self.snap_to_grapheme(self.rope.line_to_char(ln) + target.col_at(target_row, x));
     }
 
+    // a second press at the end of a wrapped row goes on to the end of the line
     pub fn caret_move_line_end(&mut self, ctrl: bool) {
         if ctrl {
             self.char_idx = self.rope.len_chars() - 1;
             return;
         }
 
-        let mut new_char_idx = self.char_idx;
+        let caret = self.get_caret();
+        let wrapped = self.wrapped_line(caret.ln);
+        let row_end = wrapped.row_end(wrapped.row_of(caret.col));
 
-        while new_char_idx < self.rope.len_chars() - 1 && self.rope.char(new_char_idx) != '\n' {
-            new_char_idx += 1;
-        }
+        let col = match caret.col == row_end {
+            true => wrapped.len,
+            false => row_end,
+        };
 
-        self.char_idx = new_char_idx;
+        self.char_idx = min(
+            self.rope.line_to_char(caret.ln) + col,
+            self.rope.len_chars() - 1,
+        );
     }
 
     pub fn caret_move_line_start(&mut self, ctrl: bool) {
@@ -302,13 +628,79 @@ impl TextFile {
             return;
         }
 
-        let mut new_char_idx = self.char_idx;
+        let caret = self.get_caret();
+        let wrapped = self.wrapped_line(caret.ln);
+        let row_start = wrapped.starts[wrapped.row_of(caret.col)];
+
+        let col = match caret.col == row_start {
+            true => 0,
+            false => row_start,
+        };
+
+        self.char_idx = self.rope.line_to_char(caret.ln) + col;
+    }
+
+    // `row` counts visual rows from the top of the viewport, clicks past the end of a line land on its newline
+    pub fn char_idx_at_row(&self, row: usize, column: usize) -> usize {
+        self.char_idx_at_row_in(&self.view(), row, column)
+    }
+
+    fn char_idx_at_row_in(&self, view: &View, row: usize, column: usize) -> usize {
+        let mut ln = view.scroll_offset;
+        let mut row = row;
+
+        loop {
+            let wrapped = self.wrapped_line_in(ln, view.wrap_width);
+            let next = self.folding.next_visible(&self.rope, ln);
+
+            if row < wrapped.rows() || next >= self.len_lines() {
+                return self
+                    .snap_to_grapheme(self.rope.line_to_char(ln) + wrapped.col_at(row, column));
+            }
+
+            row -= wrapped.rows();
+            ln = next;
+        }
+    }
+
+    pub fn select_to(&mut self, anchor: usize, idx: usize) {
+        self.char_idx = idx;
+        self.selection = (anchor != idx).then_some((anchor, idx));
+    }
 
-        while new_char_idx > 0 && self.rope.char(new_char_idx - 1) != '\n' {
-            new_char_idx -= 1;
+    // words are split the same way ctrl+arrow moves: unicode words, whitespace runs, single symbols
+    pub fn word_range_at(&self, idx: usize) -> Range<usize> {
+        if self.rope.get_char(idx).is_none_or(|c| c == '\n') {
+            return idx..idx;
         }
 
-        self.char_idx = new_char_idx;
+        let ln = self.rope.char_to_line(idx);
+        let line_start = self.rope.line_to_char(ln);
+
+        word_segments(&self.line_text(ln))
+            .into_iter()
+            .find(|segment| segment.contains(&(idx - line_start)))
+            .map_or(idx..idx + 1, |segment| {
+                line_start + segment.start..line_start + segment.end
+            })
+    }
+
+    pub fn select_word_at(&mut self, idx: usize) {
+        let range = self.word_range_at(idx);
+        self.select_range(range);
+    }
+
+    pub fn select_line_at(&mut self, idx: usize) {
+        let ln = self.rope.char_to_line(min(idx, self.rope.len_chars() - 1));
+        let start = self.rope.line_to_char(ln);
+        let end = self.rope.line_to_char(ln + 1);
+
+        self.select_range(start..end);
+    }
+
+    // the caret goes to the end of the range, like after selecting it with shift and the arrows
+    pub fn select_range(&mut self, range: Range<usize>) {
+        self.select_to(range.start, range.end);
     }
 
     pub fn clear_selection(&mut self) {
@@ -322,7 +714,7 @@ impl TextFile {
             }
 
             (true, None) => {
-                self.selection = Some((old_idx, self.char_idx));
+                self.selection = (old_idx != self.char_idx).then_some((old_idx, self.char_idx));
             }
 
             (true, Some((start, _end))) => {
@@ -346,16 +738,45 @@ impl TextFile {
         }
 
         if !ctrl {
-            self.apply_new_event(HistoryEvent::RemoveChar(
-                self.rope.char(self.char_idx - 1),
-                self.char_idx - 1,
-            ));
+            let idx = self.char_idx;
+            let prev = self.rope.char(idx - 1);
+
+            // in the leading spaces of a line a whole level goes away
+            if let Indentation::Spaces(width) = self.indentation {
+                let col = self.get_caret().col;
+                let line_start = idx - col;
+
+                if col > 0 && self.rope.slice(line_start..idx).chars().all(|c| c == ' ') {
+                    let removed = match col % width {
+                        0 => width,
+                        rest => rest,
+                    };
+
+                    self.apply_new_event(HistoryEvent::RemoveString(
+                        " ".repeat(removed),
+                        idx - removed,
+                    ));
+                    return;
+                }
+            }
+
+            // an empty auto-closed pair goes away as a whole
+            let pair = auto_pairs(self.highlighter.language)
+                .into_iter()
+                .find(|(open, close)| *open == prev && self.rope.get_char(idx) == Some(*close));
+
+            match pair {
+                Some((open, close)) => self.apply_new_event(HistoryEvent::RemoveString(
+                    format!("{open}{close}"),
+                    idx - 1,
+                )),
+                None => self.remove_grapheme(self.prev_grapheme(idx)),
+            }
             return;
         }
 
         let end_of_deletion_idx = self.char_idx;
-        self.char_idx -= 1;
-        let start_of_deletion_idx = self.ctrl_idx_move(true);
+        let start_of_deletion_idx = self.word_move(end_of_deletion_idx, true);
 
         self.apply_new_event(HistoryEvent::RemoveString(
             self.rope
@@ -376,16 +797,12 @@ impl TextFile {
         }
 
         if !ctrl {
-            self.apply_new_event(HistoryEvent::RemoveChar(
-                self.rope.char(self.char_idx),
-                self.char_idx,
-            ));
+            self.remove_grapheme(self.char_idx);
             return;
         }
 
         let start_of_deletion_idx = self.char_idx;
-        self.char_idx += 1;
-        let end_of_deletion_idx = self.ctrl_idx_move(false);
+        let end_of_deletion_idx = self.word_move(start_of_deletion_idx, false);
 
         self.apply_new_event(HistoryEvent::RemoveString(
             self.rope
@@ -395,6 +812,20 @@ impl TextFile {
         ));
     }
 
+    // a grapheme of several chars, like a letter with an accent or an emoji sequence, goes away whole
+    fn remove_grapheme(&mut self, idx: usize) {
+        let end = self.next_grapheme(idx);
+
+        match end - idx {
+            0 => {}
+            1 => self.apply_new_event(HistoryEvent::RemoveChar(self.rope.char(idx), idx)),
+            _ => self.apply_new_event(HistoryEvent::RemoveString(
+                self.rope.slice(idx..end).to_string(),
+                idx,
+            )),
+        }
+    }
+
     pub fn cut_line(&mut self) -> String {
         let start_idx = self.rope.line_to_char(self.get_caret().ln);
         let end_idx = self.rope.line_to_char(self.get_caret().ln + 1);
@@ -432,103 +863,870 @@ impl TextFile {
         }
     }
 
-    pub fn insert_char(&mut self, c: char) {
-        self.delete_selection();
-
-        self.apply_new_event(HistoryEvent::AddChar(c, self.char_idx));
-    }
-
-    pub fn insert_newline(&mut self) {
-        self.insert_char('\n');
+    // line length without the newline
+    fn line_len(&self, ln: usize) -> usize {
+        let line = self.rope.line(ln);
+        line.len_chars() - (line.chars().last() == Some('\n')) as usize
     }
 
-    pub fn insert_tab(&mut self) {
-        self.insert_string("    ".to_string());
+    fn leading_whitespace(&self, ln: usize) -> String {
+        self.rope
+            .line(ln)
+            .chars()
+            .take_while(|c| c.is_whitespace() && *c != '\n')
+            .collect()
     }
 
-    pub fn insert_string(&mut self, s: String) {
-        self.delete_selection();
+    // the selected lines without the empty one after the final newline
+    fn command_lines(&self) -> RangeInclusive<usize> {
+        let lines = self.selected_lines();
+        let last = self.len_lines() - 1;
 
-        self.apply_new_event(HistoryEvent::AddString(s, self.char_idx));
+        min(*lines.start(), last)..=min(*lines.end(), last)
     }
 
-    pub fn get_selection(&self) -> Option<String> {
-        match self.selection {
-            Some((start, end)) => {
-                let s = min(start, end);
-                let e = max(start, end);
-                Some(self.rope.slice(s..=e).to_string())
-            }
-            None => None,
-        }
+    fn line_strings(&self, lines: &RangeInclusive<usize>) -> Vec<String> {
+        let start = self.rope.line_to_char(*lines.start());
+        let end = self.rope.line_to_char(*lines.end()) + self.line_len(*lines.end());
+
+        self.rope
+            .slice(start..end)
+            .to_string()
+            .split('\n')
+            .map(str::to_string)
+            .collect()
     }
 
-    pub fn find_and_select(
-        &mut self,
-        start: usize,
-        mut needle: String,
-        reverse: bool,
-        case_sensitive: bool,
-    ) -> Option<usize> {
-        let mut s = match reverse {
-            true => self
-                .rope
-                .slice(..=start)
-                .to_string()
-                .chars()
-                .rev()
-                .collect(),
-            false => self.rope.slice(start..).to_string(),
-        };
+    // swaps whole lines for new ones, a selection then covers all of them
+    fn replace_lines(&mut self, name: &str, lines: RangeInclusive<usize>, new: Vec<String>) {
+        let old = self.line_strings(&lines).join("\n");
+        let new = new.join("\n");
 
-        if !case_sensitive {
-            s = s.to_lowercase();
-            needle = needle.to_lowercase();
+        if old == new {
+            return;
         }
 
-        if reverse {
-            needle = needle.chars().rev().collect();
-        }
+        let start = self.rope.line_to_char(*lines.start());
+        let len = new.chars().count();
+        let caret = self.get_caret();
+        let had_selection = self.selection.is_some();
 
-        let idx = s.find(&needle);
+        self.apply_edits(name, vec![(start, old, new)]);
 
-        match (idx, reverse) {
-            (Some(i), false) => {
-                let start_idx = start + i;
-                let end_idx = start_idx + needle.len();
-                self.selection = Some((start_idx, end_idx - 1));
-                self.char_idx = start_idx;
-                Some(start_idx)
-            }
+        if had_selection {
+            self.select_range(start..start + len);
+            return;
+        }
 
-            (Some(i), true) => {
-                let start_idx = start + 1 - i - needle.len();
-                let end_idx = start_idx + needle.len();
+        let ln = min(caret.ln, self.len_lines() - 1);
+        self.char_idx = self.rope.line_to_char(ln) + min(caret.col, self.line_len(ln));
+    }
 
-                self.selection = Some((start_idx, end_idx - 1));
-                self.char_idx = start_idx;
-                Some(start_idx)
-            }
+    pub fn run_line_command(&mut self, command: LineCommand) {
+        let mut lines = self.command_lines();
 
-            (None, _) => {
-                self.clear_selection();
-                None
-            }
+        // joining a single line pulls up the next one
+        if command == LineCommand::Join && lines.start() == lines.end() {
+            lines = *lines.start()..=min(lines.start() + 1, self.len_lines() - 1);
         }
+
+        let new = command.apply(self.line_strings(&lines));
+        self.replace_lines(&command.to_string(), lines, new);
     }
 
-    pub fn apply_new_event(&mut self, event: HistoryEvent) {
-        self.event_history.truncate(self.history_idx);
+    pub fn split_lines(&mut self, delimiter: &str) {
+        let lines = self.command_lines();
+        let new = split(self.line_strings(&lines), delimiter);
 
-        self.event_history.push(event.clone());
+        self.replace_lines("Split lines", lines, new);
+    }
 
-        self.apply_event(event);
+    pub fn delete_lines(&mut self) {
+        let lines = self.command_lines();
+        let col = self.get_caret().col;
 
-        self.history_idx += 1;
+        let start = self.rope.line_to_char(*lines.start());
+        let mut end = self.rope.line_to_char(lines.end() + 1);
 
-        self.dirty_changes = Some(self.dirty_changes.map(|d| d + 1).unwrap_or(1));
+        // the rope has to keep its final newline
+        if start == 0 && end == self.rope.len_chars() {
+            end -= 1;
+        }
+
+        self.clear_selection();
+        self.apply_new_event(HistoryEvent::RemoveString(
+            self.rope.slice(start..end).to_string(),
+            start,
+        ));
+
+        let ln = self.rope.char_to_line(start);
+        self.char_idx = start + min(col, self.line_len(ln));
+    }
+
+    // opens an empty line with the indentation of the caret line
+    pub fn insert_line(&mut self, below: bool) {
+        let ln = self.get_caret().ln;
+        let indent = self.leading_whitespace(ln);
+        let len = indent.chars().count();
+
+        self.clear_selection();
+
+        match below {
+            true => {
+                let idx = self.rope.line_to_char(ln) + self.line_len(ln);
+                self.apply_new_event(HistoryEvent::AddString(format!("\n{indent}"), idx));
+                self.char_idx = idx + 1 + len;
+            }
+            false => {
+                let idx = self.rope.line_to_char(ln);
+                self.apply_new_event(HistoryEvent::AddString(format!("{indent}\n"), idx));
+                self.char_idx = idx + len;
+            }
+        }
+    }
+
+    // swaps the graphemes around the caret, at the end of a line the last two
+    pub fn transpose_chars(&mut self) {
+        let caret = self.get_caret();
+        let line_start = self.rope.line_to_char(caret.ln);
+
+        let boundaries = grapheme_boundaries(&self.line_text(caret.ln));
+        let last = boundaries.len() - 1;
+        let i = boundaries
+            .iter()
+            .position(|col| *col >= caret.col)
+            .map_or(last, |i| min(i, last.saturating_sub(1)));
+
+        if i == 0 {
+            return;
+        }
+
+        let (start, middle, end) = (boundaries[i - 1], boundaries[i], boundaries[i + 1]);
+        let first = self.rope.slice(line_start + start..line_start + middle);
+        let second = self.rope.slice(line_start + middle..line_start + end);
+
+        let old = format!("{first}{second}");
+        let new = format!("{second}{first}");
+
+        self.clear_selection();
+        self.apply_edits("Transpose characters", vec![(line_start + start, old, new)]);
+        self.char_idx = line_start + end;
+    }
+
+    // swaps the word before the caret with the one after it, the caret ends up behind both,
+    // words are the unicode words ctrl+arrow stops at
+    pub fn transpose_words(&mut self) {
+        let caret = self.get_caret();
+        let line_start = self.rope.line_to_char(caret.ln);
+        let line = self.line_text(caret.ln);
+
+        let words = word_segments(&line)
+            .into_iter()
+            .filter(|segment| {
+                line.chars()
+                    .skip(segment.start)
+                    .take(segment.len())
+                    .any(char::is_alphanumeric)
+            })
+            .collect::<Vec<_>>();
+
+        // from inside a word the one it is part of goes first
+        let pivot = words
+            .iter()
+            .find(|word| word.start < caret.col && caret.col <= word.end)
+            .map_or(caret.col, |word| word.end);
+
+        let (Some(first), Some(second)) = (
+            words.iter().rfind(|word| word.end <= pivot),
+            words.iter().find(|word| word.start >= pivot),
+        ) else {
+            return;
+        };
+
+        let slice = |range: Range<usize>| {
+            self.rope
+                .slice(line_start + range.start..line_start + range.end)
+                .to_string()
+        };
+        let old = slice(first.start..second.end);
+        let new = format!(
+            "{}{}{}",
+            slice(second.clone()),
+            slice(first.end..second.start),
+            slice(first.clone()),
+        );
+
+        self.clear_selection();
+        self.apply_edits(
+            "Transpose words",
+            vec![(line_start + first.start, old, new)],
+        );
+        self.char_idx = line_start + second.end;
+    }
+
+    // the selection, or the word the caret is in or right behind
+    fn selection_or_word(&self) -> Range<usize> {
+        if let Some(range) = self.selection_range() {
+            return range;
+        }
+
+        let idx = self.char_idx;
+        let on_word = |i: usize| self.rope.get_char(i).is_some_and(|c| c.is_alphanumeric());
+
+        match (on_word(idx), idx.checked_sub(1)) {
+            (false, Some(prev)) if on_word(prev) => self.word_range_at(prev),
+            (true, _) => self.word_range_at(idx),
+            _ => idx..idx,
+        }
+    }
+
+    pub fn transform_selection(&mut self, transform: Transform) -> Result<(), String> {
+        let range = self.selection_or_word();
+        let old = self.rope.slice(range.clone()).to_string();
+        let new = transform.apply(&old)?;
+
+        if old == new {
+            return Ok(());
+        }
+
+        let len = new.chars().count();
+        self.apply_edits(&transform.to_string(), vec![(range.start, old, new)]);
+        self.select_range(range.start..range.start + len);
+
+        Ok(())
+    }
+
+    pub fn hash_selection(&self, hash: Hash) -> String {
+        let range = self.selection_or_word();
+        hash.hex_digest(&self.rope.slice(range).to_string())
+    }
+
+    // adds `delta` to the integer under the caret, zero padding stays
+    pub fn increment_number(&mut self, delta: i64) {
+        let is_digit = |i: usize| self.rope.get_char(i).is_some_and(|c| c.is_ascii_digit());
+
+        let mut start = match self.char_idx {
+            idx if is_digit(idx) => idx,
+            idx if idx > 0 && is_digit(idx - 1) => idx - 1,
+            _ => return,
+        };
+        while start > 0 && is_digit(start - 1) {
+            start -= 1;
+        }
+
+        let mut end = start;
+        while is_digit(end) {
+            end += 1;
+        }
+
+        // a minus right after a word is an operator, not a sign
+        if start > 0
+            && self.rope.char(start - 1) == '-'
+            && (start < 2 || !self.rope.char(start - 2).is_alphanumeric())
+        {
+            start -= 1;
+        }
+
+        let old = self.rope.slice(start..end).to_string();
+        let Ok(value) = old.parse::<i128>() else {
+            return;
+        };
+
+        let digits = old.trim_start_matches('-');
+        let value = value.saturating_add(delta as i128);
+        let new = match digits.len() > 1 && digits.starts_with('0') {
+            true => format!(
+                "{}{:0width$}",
+                if value < 0 { "-" } else { "" },
+                value.unsigned_abs(),
+                width = digits.len()
+            ),
+            false => value.to_string(),
+        };
+
+        let len = new.chars().count();
+        let name = match delta < 0 {
+            true => "Decrement number",
+            false => "Increment number",
+        };
+
+        self.clear_selection();
+        self.apply_edits(name, vec![(start, old, new)]);
+        self.char_idx = start + len - 1;
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        let pairs = auto_pairs(self.highlighter.language);
+        let close = pairs
+            .iter()
+            .find(|(open, _)| *open == c)
+            .map(|(_, close)| *close);
+
+        if let (Some(close), Some(range)) = (close, self.selection_range()) {
+            self.surround(range, c, close);
+            return;
+        }
+
+        self.delete_selection();
+
+        let idx = self.char_idx;
+        let next = self.rope.get_char(idx);
+
+        // typing the closing char in front of the same one steps over it
+        if next == Some(c) && pairs.iter().any(|(_, close)| *close == c) {
+            self.char_idx += 1;
+            return;
+        }
+
+        match close {
+            Some(close) if self.can_auto_close(c, idx) => {
+                self.apply_new_event(HistoryEvent::AddString(format!("{c}{close}"), idx));
+                self.char_idx = idx + 1;
+            }
+            _ => self.apply_new_event(HistoryEvent::AddChar(c, idx)),
+        }
+    }
+
+    // pairs are only closed in front of whitespace or closing chars, quotes not right after a word
+    fn can_auto_close(&self, open: char, idx: usize) -> bool {
+        let next_is_free = self
+            .rope
+            .get_char(idx)
+            .is_none_or(|next| next.is_whitespace() || ")]}\"'`".contains(next));
+
+        let prev = idx.checked_sub(1).map(|i| self.rope.char(i));
+        let is_quote = closing_bracket(open).is_none();
+
+        next_is_free
+            && !(is_quote && prev.is_some_and(|prev| prev.is_alphanumeric() || prev == open))
+    }
+
+    fn surround(&mut self, range: Range<usize>, open: char, close: char) {
+        self.apply_new_event(HistoryEvent::Compound(
+            "Surround".to_string(),
+            vec![
+                HistoryEvent::AddString(close.to_string(), range.end),
+                HistoryEvent::AddString(open.to_string(), range.start),
+            ],
+        ));
+
+        self.select_range(range.start + 1..range.end + 1);
+    }
+
+    // the new line keeps the indentation, and gets one more level after an opening bracket
+    pub fn insert_newline(&mut self) {
+        self.delete_selection();
+
+        let idx = self.char_idx;
+        let line_start = self.rope.line_to_char(self.rope.char_to_line(idx));
+        let before = self.rope.slice(line_start..idx);
+
+        let indent = before
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect::<String>();
+        let opened = before
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .last()
+            .and_then(closing_bracket);
+
+        let unit = self.indent_unit();
+        let (text, caret) = match opened {
+            // the closing bracket moves to its own line below the caret
+            Some(close) if self.rope.get_char(idx) == Some(close) => {
+                let inner = format!("\n{indent}{unit}");
+                (format!("{inner}\n{indent}"), inner.chars().count())
+            }
+            Some(_) => {
+                let text = format!("\n{indent}{unit}");
+                let len = text.chars().count();
+                (text, len)
+            }
+            None => {
+                let text = format!("\n{indent}");
+                let len = text.chars().count();
+                (text, len)
+            }
+        };
+
+        self.apply_new_event(HistoryEvent::AddString(text, idx));
+        self.char_idx = idx + caret;
+    }
+
+    pub fn indent_unit(&self) -> String {
+        self.indentation.unit()
+    }
+
+    // the bracket at the caret, or right before it, and its partner
+    pub fn matching_brackets(&self) -> Option<(usize, usize)> {
+        self.brackets_at(self.char_idx)
+    }
+
+    fn brackets_at(&self, idx: usize) -> Option<(usize, usize)> {
+        [Some(idx), idx.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .find_map(|i| matching_bracket(&self.rope, i).map(|other| (i, other)))
+    }
+
+    pub fn jump_to_bracket(&mut self) {
+        if let Some((_, other)) = self.matching_brackets() {
+            self.clear_selection();
+            self.char_idx = other;
+            self.reveal_caret();
+        }
+    }
+
+    // the selection is `(anchor, caret)`, the chars between the two are selected
+    pub fn selection_range(&self) -> Option<Range<usize>> {
+        self.selection
+            .map(|(start, end)| min(start, end)..max(start, end))
+    }
+
+    // a selection over several lines is indented as a block, otherwise tab goes to the next tab stop
+    pub fn insert_tab(&mut self) {
+        let lines = self.selected_lines();
+
+        if lines.start() != lines.end() {
+            self.indent_lines(lines);
+            return;
+        }
+
+        self.delete_selection();
+
+        let unit = match self.indentation {
+            Indentation::Spaces(width) => " ".repeat(width - self.display_col() % width),
+            Indentation::Tabs => "\t".to_string(),
+        };
+
+        self.apply_new_event(HistoryEvent::AddString(unit, self.char_idx));
+    }
+
+    // the lines touched by the selection, or the caret line
+    pub fn selected_lines(&self) -> RangeInclusive<usize> {
+        let range = self
+            .selection_range()
+            .unwrap_or(self.char_idx..self.char_idx + 1);
+
+        self.rope.char_to_line(range.start)..=self.rope.char_to_line(range.end - 1)
+    }
+
+    pub fn indent_lines(&mut self, lines: RangeInclusive<usize>) {
+        let unit = self.indentation.unit();
+
+        let edits = lines
+            .filter(|ln| !self.rope.line(*ln).chars().all(|c| c == '\n'))
+            .map(|ln| (self.rope.line_to_char(ln), String::new(), unit.clone()))
+            .collect();
+
+        self.apply_edits("Indent", edits);
+    }
+
+    // removes one level, a tab or up to a level of spaces, from every line
+    pub fn outdent_lines(&mut self, lines: RangeInclusive<usize>) {
+        let width = self.indentation.width();
+
+        let edits = lines
+            .filter_map(|ln| {
+                let line = self.rope.line(ln);
+                let removed = match line.get_char(0) {
+                    Some('\t') => "\t".to_string(),
+                    _ => line.chars().take(width).take_while(|c| *c == ' ').collect(),
+                };
+
+                (!removed.is_empty()).then(|| (self.rope.line_to_char(ln), removed, String::new()))
+            })
+            .collect();
+
+        self.apply_edits("Outdent", edits);
+    }
+
+    pub fn outdent(&mut self) {
+        self.outdent_lines(self.selected_lines());
+    }
+
+    // rewrites the leading whitespace of every line, one level of the old style becomes one of the new
+    pub fn convert_indentation(&mut self, to: Indentation) {
+        let from = self.indentation.width();
+
+        let edits = (0..self.len_lines())
+            .filter_map(|ln| {
+                let leading = self
+                    .rope
+                    .line(ln)
+                    .chars()
+                    .take_while(|c| *c == ' ' || *c == '\t')
+                    .collect::<String>();
+
+                let columns = leading
+                    .chars()
+                    .map(|c| if c == '\t' { from } else { 1 })
+                    .sum::<usize>();
+                let converted = to.unit().repeat(columns / from) + &" ".repeat(columns % from);
+
+                (converted != leading).then(|| (self.rope.line_to_char(ln), leading, converted))
+            })
+            .collect();
+
+        self.apply_edits("Convert indentation", edits);
+        self.indentation = to;
+    }
+
+    // comments out the selected lines at their common indentation, or uncomments them when all are
+    pub fn toggle_line_comment(&mut self) {
+        let Some(grammar) = self.highlighter.language.map(Language::grammar) else {
+            return;
+        };
+
+        let Some(token) = grammar.line_comments.first() else {
+            if grammar.block_comment.is_some() {
+                self.toggle_block_comment();
+            }
+            return;
+        };
+
+        let lines = self
+            .selected_lines()
+            .map(|ln| (self.rope.line_to_char(ln), self.rope.line(ln).to_string()))
+            .filter(|(_, line)| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+
+        let leading = |line: &str| line.chars().take_while(|c| c.is_whitespace()).count();
+
+        let Some(indent) = lines.iter().map(|(_, line)| leading(line)).min() else {
+            return;
+        };
+
+        let commented = lines
+            .iter()
+            .all(|(_, line)| line.trim_start().starts_with(token));
+
+        let edits = lines
+            .iter()
+            .map(|(start, line)| match commented {
+                true => {
+                    let removed = match line.trim_start()[token.len()..].starts_with(' ') {
+                        true => format!("{token} "),
+                        false => token.to_string(),
+                    };
+
+                    (start + leading(line), removed, String::new())
+                }
+                false => (start + indent, String::new(), format!("{token} ")),
+            })
+            .collect();
+
+        self.apply_edits("Toggle line comment", edits);
+    }
+
+    // wraps the selection, or the text of the caret line, in a block comment or unwraps it
+    pub fn toggle_block_comment(&mut self) {
+        let Some(grammar) = self.highlighter.language.map(Language::grammar) else {
+            return;
+        };
+
+        let Some((open, close)) = grammar.block_comment else {
+            if !grammar.line_comments.is_empty() {
+                self.toggle_line_comment();
+            }
+            return;
+        };
+
+        let range = self.selection_range().unwrap_or_else(|| {
+            let ln = self.get_caret().ln;
+            self.rope.line_to_char(ln)..self.rope.line_to_char(ln) + self.rope.line(ln).len_chars()
+        });
+
+        let text = self.rope.slice(range.clone()).to_string();
+        let start = range.start + text.chars().take_while(|c| c.is_whitespace()).count();
+        let end = range.start + text.trim_end().chars().count();
+
+        if start >= end {
+            return;
+        }
+
+        let text = text.trim();
+
+        let edits = match text.strip_prefix(open).and_then(|t| t.strip_suffix(close)) {
+            Some(inner) => {
+                let open_space = inner.starts_with(' ');
+                let close_space = inner.len() > open_space as usize && inner.ends_with(' ');
+
+                let open = format!("{open}{}", if open_space { " " } else { "" });
+                let close = format!("{}{close}", if close_space { " " } else { "" });
+                let close_start = end - close.chars().count();
+
+                vec![
+                    (start, open, String::new()),
+                    (close_start, close, String::new()),
+                ]
+            }
+            None => vec![
+                (start, String::new(), format!("{open} ")),
+                (end, String::new(), format!(" {close}")),
+            ],
+        };
+
+        self.apply_edits("Toggle block comment", edits);
+    }
+
+    // `(idx, removed, inserted)` edits in current indices, sorted and not overlapping, become one
+    // history entry while the caret and the selection stay on the same text
+    fn apply_edits(&mut self, name: &str, edits: Vec<(usize, String, String)>) {
+        if edits.is_empty() {
+            return;
+        }
+
+        // text inserted right at the end of the selection stays out of it
+        let selection_end = self.selection_range().map(|range| range.end);
+
+        let shift = |idx: usize| {
+            edits.iter().fold(idx, |new, (start, removed, inserted)| {
+                let (removed, inserted) = (removed.chars().count(), inserted.chars().count());
+
+                match idx {
+                    idx if idx == *start && removed == 0 && Some(idx) == selection_end => new,
+                    idx if idx >= start + removed => new + inserted - removed,
+                    idx if idx >= *start => new + inserted - (idx - start),
+                    _ => new,
+                }
+            })
+        };
+
+        let selection = self
+            .selection
+            .map(|(anchor, caret)| (shift(anchor), shift(caret)));
+        let char_idx = shift(self.char_idx);
+
+        let events = edits
+            .iter()
+            .rev()
+            .flat_map(|(start, removed, inserted)| {
+                [
+                    HistoryEvent::RemoveString(removed.clone(), *start),
+                    HistoryEvent::AddString(inserted.clone(), *start),
+                ]
+            })
+            .filter(|event| !matches!(event, HistoryEvent::AddString(s, _) | HistoryEvent::RemoveString(s, _) if s.is_empty()))
+            .collect();
+
+        self.apply_new_event(HistoryEvent::Compound(name.to_string(), events));
+        self.selection = selection;
+        self.char_idx = char_idx;
+    }
+
+    pub fn insert_string(&mut self, s: String) {
+        self.delete_selection();
+
+        self.apply_new_event(HistoryEvent::AddString(s, self.char_idx));
+    }
+
+    // whole lines go in above the caret line and the caret stays where it was in the text,
+    // over a selection they replace it like any other text
+    pub fn paste(&mut self, text: String, linewise: bool) {
+        if !linewise || self.selection.is_some() {
+            self.insert_string(text);
+            return;
+        }
+
+        let text = match text.ends_with('\n') {
+            true => text,
+            false => text + "\n",
+        };
+
+        let idx = self.rope.line_to_char(self.get_caret().ln);
+        let caret = self.char_idx + text.chars().count();
+
+        self.apply_new_event(HistoryEvent::AddString(text, idx));
+        self.char_idx = caret;
+    }
+
+    pub fn get_selection(&self) -> Option<String> {
+        self.selection_range()
+            .map(|range| self.rope.slice(range).to_string())
+    }
+
+    // without a selection the whole caret line is copied, and pasted as a line again
+    pub fn copy(&self) -> ClipEntry {
+        match self.get_selection() {
+            Some(text) => ClipEntry {
+                text,
+                linewise: false,
+            },
+            None => {
+                let ln = self.get_caret().ln;
+                let range = self.rope.line_to_char(ln)..self.rope.line_to_char(ln + 1);
+
+                ClipEntry {
+                    text: self.rope.slice(range).to_string(),
+                    linewise: true,
+                }
+            }
+        }
+    }
+
+    pub fn cut(&mut self) -> ClipEntry {
+        let entry = self.copy();
+
+        match entry.linewise {
+            true => {
+                self.cut_line();
+            }
+            false => self.delete_selection(),
+        }
+
+        entry
+    }
+
+    // searches on from the caret, or from the selection, without wrapping around
+    pub fn find_next(&mut self, needle: &str, case_sensitive: bool, reverse: bool) -> bool {
+        if needle.is_empty() {
+            return false;
+        }
+
+        let start = match (reverse, self.selection_range()) {
+            (false, Some(range)) => Some(range.end),
+            (false, None) => Some(self.char_idx),
+            (true, Some(range)) => range.start.checked_sub(1),
+            (true, None) => self.char_idx.checked_sub(1),
+        };
+
+        start.is_some_and(|start| {
+            self.find_and_select(start, needle.to_string(), reverse, case_sensitive)
+                .is_some()
+        })
+    }
+
+    pub fn find_and_select(
+        &mut self,
+        start: usize,
+        needle: String,
+        reverse: bool,
+        case_sensitive: bool,
+    ) -> Option<usize> {
+        // backwards both are reversed, so the match closest to `start` comes first
+        let found = match reverse {
+            true => {
+                let haystack = self.rope.chars_at(start + 1).reversed();
+                let needle = needle.chars().rev().collect::<String>();
+
+                find_first(haystack, &needle, case_sensitive)
+                    .map(|(s, e)| start + 1 - e..start + 1 - s)
+            }
+            false => find_first(self.rope.chars_at(start), &needle, case_sensitive)
+                .map(|(s, e)| start + s..start + e),
+        };
+
+        match found {
+            Some(range) => {
+                let start_idx = range.start;
+                self.select_range(range);
+                self.reveal_caret();
+                Some(start_idx)
+            }
+
+            None => {
+                self.clear_selection();
+                None
+            }
+        }
+    }
+
+    pub fn find_all(&self, needle: &str, case_sensitive: bool) -> Vec<(usize, usize)> {
+        self.matches.find_all(&self.rope, needle, case_sensitive)
+    }
+
+    pub fn find_in(
+        &self,
+        needle: &str,
+        case_sensitive: bool,
+        range: Range<usize>,
+    ) -> Vec<(usize, usize)> {
+        self.matches
+            .find_in(&self.rope, needle, case_sensitive, range)
+    }
+
+    pub fn first_match_in(
+        &self,
+        needle: &str,
+        case_sensitive: bool,
+        range: Range<usize>,
+    ) -> Option<(usize, usize)> {
+        self.matches
+            .first_in(&self.rope, needle, case_sensitive, range)
+    }
+
+    // `lines` has to be sorted, they do not need to be contiguous
+    pub fn ranges_by_line(
+        &self,
+        ranges: &[(usize, usize)],
+        lines: &[usize],
+    ) -> Vec<Vec<(usize, usize)>> {
+        let mut res = vec![Vec::new(); lines.len()];
+
+        for &(start, end) in ranges {
+            let start_ln = self.rope.char_to_line(start);
+            let end_ln = self.rope.char_to_line(end);
+
+            let first = lines.partition_point(|ln| *ln < start_ln);
+            for (i, &ln) in lines.iter().enumerate().skip(first) {
+                if ln > end_ln {
+                    break;
+                }
+
+                let line_start = self.rope.line_to_char(ln);
+                let line_end = line_start + self.rope.line(ln).len_chars();
+
+                let s = max(start, line_start) - line_start;
+                let e = min(end, line_end) - line_start;
+
+                if s < e {
+                    res[i].push((s, e));
+                }
+            }
+        }
+
+        res
+    }
+
+    pub fn apply_new_event(&mut self, event: HistoryEvent) {
+        self.event_history.truncate(self.history_idx);
+
+        self.event_history.push(event.clone());
+
+        self.apply_event(event);
+
+        self.history_idx += 1;
+
+        self.dirty_changes = Some(self.dirty_changes.map(|d| d + 1).unwrap_or(1));
 
         self.ammend_history();
+        self.reveal_caret();
+    }
+
+    // the next edit starts an entry of its own instead of being merged into the last one, until
+    // the entries are grouped
+    pub fn break_history(&mut self) {
+        self.history_break = Some(self.history_idx);
+    }
+
+    // the entries added since `from` become one, so they are undone together and nothing typed
+    // later is merged into them
+    pub fn group_history(&mut self, from: usize, name: &str) {
+        self.history_break = None;
+
+        if self.history_idx <= from {
+            return;
+        }
+
+        let events = self.event_history.drain(from..).collect::<Vec<_>>();
+        let grouped = events.len();
+
+        self.event_history
+            .push(HistoryEvent::Compound(name.to_string(), events));
+        self.history_idx = from + 1;
+        self.dirty_changes = self.dirty_changes.map(|d| d.saturating_sub(grouped - 1));
     }
 
     pub fn ammend_history(&mut self) {
@@ -536,6 +1734,13 @@ impl TextFile {
             return;
         }
 
+        if self
+            .history_break
+            .is_some_and(|start| self.history_idx - 2 < start)
+        {
+            return;
+        }
+
         let Some(new) = self.event_history.last().cloned() else {
             return;
         };
@@ -559,7 +1764,7 @@ impl TextFile {
             }
 
             (HistoryEvent::AddString(s1, idx1), HistoryEvent::AddChar(c2, idx2))
-                if idx1 + s1.len() == idx2
+                if idx1 + s1.chars().count() == idx2
                     && ((c2.is_ascii_whitespace()
                         && s1.chars().all(|c| c.is_ascii_whitespace()))
                         || (!c2.is_ascii_whitespace()
@@ -617,37 +1822,164 @@ impl TextFile {
     }
 
     pub fn delete_selection(&mut self) {
-        if let Some((start, end)) = self.selection {
-            let s = min(start, end);
-            let e = max(start, end);
-
+        if let Some(range) = self.selection_range() {
             self.apply_new_event(HistoryEvent::RemoveString(
-                self.rope.slice(s..e).to_string(),
-                s,
+                self.rope.slice(range.clone()).to_string(),
+                range.start,
             ));
 
             self.clear_selection();
         }
     }
 
+    // dropping a dragged selection, a move is a single undo step like any other edit
+    pub fn move_selection_to(&mut self, idx: usize, copy: bool) {
+        let Some(Range { start: s, end: e }) = self.selection_range() else {
+            return;
+        };
+
+        // dropping onto the selection itself just places the caret
+        if (s..=e).contains(&idx) {
+            self.clear_selection();
+            self.char_idx = idx;
+            return;
+        }
+
+        let text = self.rope.slice(s..e).to_string();
+        let len = e - s;
+
+        // the removal shifts everything after the selection to the left
+        let new_start = if !copy && idx > e { idx - len } else { idx };
+
+        let (name, events) = match copy {
+            true => ("Copy text", vec![HistoryEvent::AddString(text, idx)]),
+            false => (
+                "Move text",
+                vec![
+                    HistoryEvent::RemoveString(text.clone(), s),
+                    HistoryEvent::AddString(text, new_start),
+                ],
+            ),
+        };
+
+        self.apply_new_event(HistoryEvent::Compound(name.to_string(), events));
+        self.select_range(new_start..new_start + len);
+    }
+
+    pub fn replace_ranges(&mut self, name: &str, ranges: &[(usize, usize)], replacement: &str) {
+        let mut ranges = ranges.to_vec();
+        ranges.sort();
+
+        // replace back to front so the earlier indices stay valid
+        let events = ranges
+            .iter()
+            .rev()
+            .flat_map(|&(start, end)| {
+                [
+                    HistoryEvent::RemoveString(self.rope.slice(start..end).to_string(), start),
+                    HistoryEvent::AddString(replacement.to_string(), start),
+                ]
+            })
+            .filter(|event| !matches!(event, HistoryEvent::AddString(s, _) | HistoryEvent::RemoveString(s, _) if s.is_empty()))
+            .collect::<Vec<_>>();
+
+        if events.is_empty() {
+            return;
+        }
+
+        self.clear_selection();
+        self.apply_new_event(HistoryEvent::Compound(name.to_string(), events));
+    }
+
+    // every edit goes through these so the highlighter only re-tokenizes the touched lines
+    fn insert_text(&mut self, idx: usize, s: &str) {
+        let first_line = self.rope.char_to_line(idx);
+        let at_line_start = idx == self.rope.line_to_char(first_line);
+        self.rope.insert(idx, s);
+
+        let last_line = self.rope.char_to_line(idx + s.chars().count());
+        self.highlighter
+            .edit(&self.rope, first_line..=first_line, first_line..=last_line);
+
+        let (len, lines) = (s.chars().count(), last_line - first_line);
+        self.matches.edit(&self.rope, idx, 0, len);
+        self.wraps.edit(first_line);
+        self.shift_views(
+            |i| if i >= idx { i + len } else { i },
+            |ln| if ln > first_line { ln + lines } else { ln },
+        );
+
+        // text inserted in front of a line pushes it down with its fold
+        self.folding.edit(first_line, |ln| match ln {
+            ln if ln < first_line || (ln == first_line && !at_line_start) => Some(ln),
+            ln => Some(ln + last_line - first_line),
+        });
+    }
+
+    // the other panes' carets, selections and scroll stay on the same text, like the caret in
+    // `apply_edits`
+    fn shift_views(&mut self, shift: impl Fn(usize) -> usize, shift_line: impl Fn(usize) -> usize) {
+        for (_, view) in self.views.iter_mut() {
+            view.char_idx = shift(view.char_idx);
+            view.selection = view
+                .selection
+                .map(|(anchor, caret)| (shift(anchor), shift(caret)));
+            view.scroll_offset = shift_line(view.scroll_offset);
+        }
+    }
+
+    fn remove_text(&mut self, range: Range<usize>) {
+        let first_line = self.rope.char_to_line(range.start);
+        let last_line = self.rope.char_to_line(range.end);
+        let whole_lines = range.start == self.rope.line_to_char(first_line)
+            && range.end == self.rope.line_to_char(last_line);
+        self.rope.remove(range.clone());
+
+        self.highlighter
+            .edit(&self.rope, first_line..=last_line, first_line..=first_line);
+        self.matches.edit(&self.rope, range.start, range.len(), 0);
+        self.wraps.edit(first_line);
+
+        // what pointed into the removed text ends up where it was
+        self.shift_views(
+            |i| match i {
+                i if i >= range.end => i - range.len(),
+                i => min(i, range.start),
+            },
+            |ln| match ln {
+                ln if ln > last_line => ln - (last_line - first_line),
+                ln => min(ln, first_line),
+            },
+        );
+
+        // folds on lines that lost their start are dropped, the ones below move up
+        self.folding.edit(first_line, |ln| match ln {
+            ln if ln < first_line => Some(ln),
+            ln if ln == first_line && (!whole_lines || first_line == last_line) => Some(ln),
+            ln if ln == last_line && whole_lines => Some(first_line),
+            ln if ln > last_line => Some(ln - (last_line - first_line)),
+            _ => None,
+        });
+    }
+
     pub fn apply_event(&mut self, event: HistoryEvent) {
         match event {
             HistoryEvent::AddChar(c, idx) => {
-                self.rope.insert_char(idx, c);
+                self.insert_text(idx, &c.to_string());
                 self.char_idx = idx;
                 self.caret_move_right(false);
             }
             HistoryEvent::RemoveChar(_c, idx) => {
-                self.rope.remove(idx..idx + 1);
+                self.remove_text(idx..idx + 1);
                 self.char_idx = idx;
             }
             HistoryEvent::AddString(s, idx) => {
-                self.rope.insert(idx, &s);
-                let new_idx = idx + s.len();
+                self.insert_text(idx, &s);
+                let new_idx = idx + s.chars().count();
                 self.char_idx = new_idx;
             }
             HistoryEvent::RemoveString(s, idx) => {
-                self.rope.remove(idx..idx + s.len());
+                self.remove_text(idx..idx + s.chars().count());
                 self.char_idx = idx;
             }
 
@@ -665,14 +1997,19 @@ impl TextFile {
 
                 let removed_line_content = self.rope.slice(line_0..line_1).to_string();
 
-                self.rope.remove(line_0..line_1);
-                self.rope
-                    .insert(line_2 - (line_1 - line_0), &removed_line_content);
+                self.remove_text(line_0..line_1);
+                self.insert_text(line_2 - (line_1 - line_0), &removed_line_content);
 
                 self.char_idx = self
                     .rope
                     .line_to_char(if go_down { ln + 1 } else { ln - 1 });
             }
+
+            HistoryEvent::Compound(_, events) => {
+                for event in events {
+                    self.apply_event(event);
+                }
+            }
         }
     }
 
@@ -685,23 +2022,35 @@ impl TextFile {
             return;
         };
 
+        self.revert_event(event);
+        self.reveal_caret();
+
+        self.history_idx -= 1;
+        self.dirty_changes = Some(
+            self.dirty_changes
+                .and_then(|d| d.checked_sub(1))
+                .unwrap_or(0),
+        );
+    }
+
+    fn revert_event(&mut self, event: HistoryEvent) {
         match event {
             HistoryEvent::AddChar(_c, idx) => {
-                self.rope.remove(idx..idx + 1);
+                self.remove_text(idx..idx + 1);
                 self.char_idx = idx;
             }
             HistoryEvent::RemoveChar(c, idx) => {
-                self.rope.insert_char(idx, c);
+                self.insert_text(idx, &c.to_string());
                 self.char_idx = idx;
                 self.caret_move_right(false);
             }
             HistoryEvent::AddString(s, idx) => {
-                self.rope.remove(idx..idx + s.len());
+                self.remove_text(idx..idx + s.chars().count());
                 self.char_idx = idx;
             }
             HistoryEvent::RemoveString(s, idx) => {
-                self.rope.insert(idx, &s);
-                let new_idx = idx + s.len();
+                self.insert_text(idx, &s);
+                let new_idx = idx + s.chars().count();
                 self.char_idx = new_idx;
             }
 
@@ -709,14 +2058,13 @@ impl TextFile {
                 true => self.apply_event(HistoryEvent::MoveLine(ln + 1, false)),
                 false => self.apply_event(HistoryEvent::MoveLine(ln - 1, true)),
             },
-        }
 
-        self.history_idx -= 1;
-        self.dirty_changes = Some(
-            self.dirty_changes
-                .and_then(|d| d.checked_sub(1))
-                .unwrap_or(0),
-        );
+            HistoryEvent::Compound(_, events) => {
+                for event in events.into_iter().rev() {
+                    self.revert_event(event);
+                }
+            }
+        }
     }
 
     pub fn redo_event(&mut self) {
@@ -729,6 +2077,7 @@ impl TextFile {
         };
 
         self.apply_event(event);
+        self.reveal_caret();
         self.history_idx += 1;
 
         self.dirty_changes = Some(self.dirty_changes.map(|d| d + 1).unwrap_or(1));
@@ -0,0 +1,274 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const DARK: &str = include_str!("../../assets/themes/dark.json");
+const LIGHT: &str = include_str!("../../assets/themes/light.json");
+
+const APP_DIR: &str = "notepad-minus-minus";
+const CURRENT_THEME_FILE: &str = "theme";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    pub editor: EditorColors,
+    pub syntax: SyntaxColors,
+    pub chrome: ChromeColors,
+    pub terminal: TerminalColors,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditorColors {
+    pub background: String,
+    pub empty_background: String,
+    pub foreground: String,
+    pub selection: String,
+    pub caret: String,
+    pub caret_line: String,
+    pub gutter: String,
+    pub gutter_active: String,
+    pub search_match: String,
+    pub scrollbar: String,
+    pub scrollbar_thumb: String,
+    pub scrollbar_marker: String,
+    pub bracket_match: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyntaxColors {
+    pub keyword: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub constant: String,
+    pub number: String,
+    pub string: String,
+    pub comment: String,
+    pub function: String,
+    pub key: String,
+    pub heading: String,
+    pub variable: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChromeColors {
+    pub background: String,
+    pub foreground: String,
+    pub side_panel: String,
+    pub side_panel_icon: String,
+    pub side_panel_separator: String,
+    pub icon_hover_background: String,
+    pub icon_hover_border: String,
+    pub icon_selected_background: String,
+    pub icon_selected_border: String,
+    pub tab_bar: String,
+    pub tab_foreground: String,
+    pub tab_separator: String,
+    pub top_bar: String,
+    pub status_bar: String,
+    pub status_foreground: String,
+    pub separator: String,
+    pub panel: String,
+    pub panel_foreground: String,
+    pub control: String,
+    pub control_hover: String,
+    pub control_active: String,
+    pub button_foreground: String,
+    pub slider: String,
+    pub slider_hover: String,
+    pub muted_foreground: String,
+    pub error: String,
+    pub accent: String,
+    pub panel_header: String,
+    pub tree_guide: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerminalColors {
+    pub background: String,
+    pub foreground: String,
+    pub input: String,
+    pub input_foreground: String,
+    pub sidebar: String,
+    pub sidebar_border: String,
+    pub button: String,
+    pub button_active: String,
+    pub icon: String,
+    pub icon_active: String,
+    pub close_button: String,
+    pub close_button_active: String,
+    pub close_icon: String,
+    pub close_icon_active: String,
+    pub error_background: String,
+    pub error_foreground: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        serde_json::from_str(DARK).expect("the built-in dark theme is valid")
+    }
+}
+
+impl Theme {
+    // theme files are laid over the dark theme, so they only need the colors they change
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let error = |e: &dyn std::fmt::Display| format!("{}: {}", path.display(), e);
+
+        let content = fs::read_to_string(path).map_err(|e| error(&e))?;
+        let overrides: Value = serde_json::from_str(&content).map_err(|e| error(&e))?;
+
+        let mut theme: Value = serde_json::from_str(DARK).map_err(|e| error(&e))?;
+        theme["name"] = Value::String(
+            path.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        );
+        merge(&mut theme, overrides);
+
+        serde_json::from_value(theme).map_err(|e| error(&e))
+    }
+
+    // each color becomes a css variable named after its section, e.g. `--editor-caret-line`
+    pub fn to_css(&self) -> String {
+        let mut css = String::from(":root {\n");
+
+        if let Ok(Value::Object(sections)) = serde_json::to_value(self) {
+            for (section, colors) in sections {
+                let Value::Object(colors) = colors else {
+                    continue;
+                };
+
+                for (name, color) in colors {
+                    if let Value::String(color) = color {
+                        css +=
+                            &format!("    --{}-{}: {};\n", section, name.replace('_', "-"), color);
+                    }
+                }
+            }
+        }
+
+        css.push('}');
+        css
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Themes {
+    pub available: Vec<Theme>,
+    pub current: String,
+    pub preview: Option<String>,
+    pub errors: Vec<String>,
+}
+
+impl Default for Themes {
+    fn default() -> Self {
+        Self::load()
+    }
+}
+
+impl Themes {
+    pub fn load() -> Self {
+        let current = config_dir()
+            .and_then(|dir| fs::read_to_string(dir.join(CURRENT_THEME_FILE)).ok())
+            .map(|name| name.trim().to_string())
+            .unwrap_or_else(|| Theme::default().name);
+
+        let mut themes = Self {
+            available: Vec::new(),
+            current,
+            preview: None,
+            errors: Vec::new(),
+        };
+
+        themes.reload();
+        themes
+    }
+
+    // user themes with a built-in name replace the built-in one
+    pub fn reload(&mut self) {
+        self.available = [DARK, LIGHT]
+            .iter()
+            .filter_map(|theme| serde_json::from_str(theme).ok())
+            .collect();
+        self.errors.clear();
+
+        let mut paths = themes_dir()
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        paths.sort();
+
+        for path in paths {
+            match Theme::from_file(&path) {
+                Ok(theme) => {
+                    self.available.retain(|t| t.name != theme.name);
+                    self.available.push(theme);
+                }
+                Err(e) => self.errors.push(e),
+            }
+        }
+    }
+
+    pub fn active(&self) -> Theme {
+        let name = self.preview.as_ref().unwrap_or(&self.current);
+
+        self.available
+            .iter()
+            .find(|theme| &theme.name == name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn preview(&mut self, name: &str) {
+        self.preview = Some(name.to_string());
+    }
+
+    pub fn cancel_preview(&mut self) {
+        self.preview = None;
+    }
+
+    pub fn apply(&mut self, name: &str) -> io::Result<()> {
+        self.current = name.to_string();
+        self.preview = None;
+
+        let Some(dir) = config_dir() else {
+            return Ok(());
+        };
+
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(CURRENT_THEME_FILE), name)
+    }
+}
+
+fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, value) => *base = value,
+    }
+}
+
+pub fn themes_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("themes"))
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(base.join(APP_DIR))
+}
@@ -0,0 +1,343 @@
+use std::fmt;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use itertools::Itertools;
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+// rewrites of the selected text, every one is a single undo step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transform {
+    Upper,
+    Lower,
+    Title,
+    Snake,
+    Camel,
+    Kebab,
+    Base64Encode,
+    Base64Decode,
+    UrlEncode,
+    UrlDecode,
+    JsonEscape,
+    JsonUnescape,
+    XmlEscape,
+    XmlUnescape,
+    HexEncode,
+    HexDecode,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 16] = [
+        Transform::Upper,
+        Transform::Lower,
+        Transform::Title,
+        Transform::Snake,
+        Transform::Camel,
+        Transform::Kebab,
+        Transform::Base64Encode,
+        Transform::Base64Decode,
+        Transform::UrlEncode,
+        Transform::UrlDecode,
+        Transform::JsonEscape,
+        Transform::JsonUnescape,
+        Transform::XmlEscape,
+        Transform::XmlUnescape,
+        Transform::HexEncode,
+        Transform::HexDecode,
+    ];
+
+    pub fn apply(self, text: &str) -> Result<String, String> {
+        let transformed = match self {
+            Transform::Upper => text.to_uppercase(),
+            Transform::Lower => text.to_lowercase(),
+            Transform::Title => title_case(text),
+            Transform::Snake => per_line(text, |words| words.join("_")),
+            Transform::Kebab => per_line(text, |words| words.join("-")),
+            Transform::Camel => per_line(text, |words| {
+                words
+                    .iter()
+                    .enumerate()
+                    .map(|(i, word)| match i {
+                        0 => word.clone(),
+                        _ => capitalize(word),
+                    })
+                    .collect()
+            }),
+            Transform::Base64Encode => STANDARD.encode(text),
+            Transform::Base64Decode => {
+                let bytes = STANDARD
+                    .decode(text.trim())
+                    .map_err(|e| format!("Invalid Base64: {e}"))?;
+                utf8(bytes)?
+            }
+            Transform::UrlEncode => url_encode(text),
+            Transform::UrlDecode => utf8(url_decode(text)?)?,
+            Transform::JsonEscape => {
+                let quoted = serde_json::to_string(text).map_err(|e| e.to_string())?;
+                quoted[1..quoted.len() - 1].to_string()
+            }
+            Transform::JsonUnescape => serde_json::from_str(&format!("\"{text}\""))
+                .map_err(|e| format!("Invalid JSON string: {e}"))?,
+            Transform::XmlEscape => xml_escape(text),
+            Transform::XmlUnescape => xml_unescape(text),
+            Transform::HexEncode => text.bytes().map(|b| format!("{b:02x}")).collect(),
+            Transform::HexDecode => utf8(hex_decode(text)?)?,
+        };
+
+        Ok(transformed)
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Transform::Upper => write!(f, "UPPER CASE"),
+            Transform::Lower => write!(f, "lower case"),
+            Transform::Title => write!(f, "Title Case"),
+            Transform::Snake => write!(f, "snake_case"),
+            Transform::Camel => write!(f, "camelCase"),
+            Transform::Kebab => write!(f, "kebab-case"),
+            Transform::Base64Encode => write!(f, "Base64 encode"),
+            Transform::Base64Decode => write!(f, "Base64 decode"),
+            Transform::UrlEncode => write!(f, "URL encode"),
+            Transform::UrlDecode => write!(f, "URL decode"),
+            Transform::JsonEscape => write!(f, "JSON escape"),
+            Transform::JsonUnescape => write!(f, "JSON unescape"),
+            Transform::XmlEscape => write!(f, "XML escape"),
+            Transform::XmlUnescape => write!(f, "XML unescape"),
+            Transform::HexEncode => write!(f, "Hex encode"),
+            Transform::HexDecode => write!(f, "Hex decode"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hash {
+    Sha256,
+    Md5,
+}
+
+impl Hash {
+    pub const ALL: [Hash; 2] = [Hash::Sha256, Hash::Md5];
+
+    pub fn hex_digest(self, text: &str) -> String {
+        let digest = match self {
+            Hash::Sha256 => Sha256::digest(text).to_vec(),
+            Hash::Md5 => Md5::digest(text).to_vec(),
+        };
+
+        digest.iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
+
+impl fmt::Display for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Hash::Sha256 => write!(f, "SHA-256"),
+            Hash::Md5 => write!(f, "MD5"),
+        }
+    }
+}
+
+fn utf8(bytes: Vec<u8>) -> Result<String, String> {
+    String::from_utf8(bytes).map_err(|_| "The decoded bytes are not valid UTF-8".to_string())
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn title_case(text: &str) -> String {
+    text.split_inclusive(char::is_whitespace)
+        .map(capitalize)
+        .collect()
+}
+
+// identifiers split at separators and case changes, `parseHTTPResponse` gives parse, http, response
+fn words(text: &str) -> Vec<String> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut word = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+
+        let boundary = c.is_uppercase()
+            && prev.is_some_and(|prev| {
+                prev.is_lowercase()
+                    || prev.is_numeric()
+                    || (prev.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+            });
+
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+
+        word.extend(c.to_lowercase());
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+// identifier cases work line by line and keep the whitespace around each line
+fn per_line(text: &str, join: impl Fn(Vec<String>) -> String) -> String {
+    text.split('\n')
+        .map(|line| {
+            let trimmed = line.trim();
+            let start = line.len() - line.trim_start().len();
+            let end = start + trimmed.len();
+
+            format!("{}{}{}", &line[..start], join(words(trimmed)), &line[end..])
+        })
+        .join("\n")
+}
+
+fn url_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn url_decode(text: &str) -> Result<Vec<u8>, String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(hex_byte)
+                    .ok_or_else(|| format!("Invalid escape at byte {i}"))?;
+
+                decoded.push(hex);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            b => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    Ok(decoded)
+}
+
+fn hex_decode(text: &str) -> Result<Vec<u8>, String> {
+    let digits = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+
+    if digits.len() % 2 != 0 {
+        return Err("Hex input has an odd number of digits".to_string());
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let pair = pair.iter().collect::<String>();
+            hex_byte(&pair).ok_or_else(|| format!("Invalid hex byte: {pair}"))
+        })
+        .collect()
+}
+
+// `from_str_radix` also takes a leading `+`, which is not a hex digit
+pub fn hex_byte(digits: &str) -> Option<u8> {
+    match digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        true => u8::from_str_radix(digits, 16).ok(),
+        false => None,
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            '\'' => "&apos;".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+// named entities and numeric references, anything unknown is left as it is
+fn xml_unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..].find(';').map(|end| &rest[1..end + 1]);
+
+        let c = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => {
+                let hex = entity.strip_prefix("#x").or(entity.strip_prefix("#X"));
+                let (digits, radix) = match hex {
+                    Some(hex) => (hex, 16),
+                    None => (entity.strip_prefix('#')?, 10),
+                };
+
+                // like in `hex_byte`, a sign is not a digit
+                Some(digits)
+                    .filter(|digits| digits.chars().all(|c| c.is_digit(radix)))
+                    .and_then(|digits| u32::from_str_radix(digits, radix).ok())
+                    .and_then(char::from_u32)
+            }
+        });
+
+        match (c, entity) {
+            (Some(c), Some(entity)) => {
+                unescaped.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    unescaped.push_str(rest);
+    unescaped
+}
@@ -0,0 +1,42 @@
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// char offsets where the grapheme clusters of `text` start, followed by its length,
+// the caret only ever rests on one of these
+pub fn grapheme_boundaries(text: &str) -> Vec<usize> {
+    let mut boundaries = vec![0];
+    let mut offset = 0;
+
+    for grapheme in text.graphemes(true) {
+        offset += grapheme.chars().count();
+        boundaries.push(offset);
+    }
+
+    boundaries
+}
+
+// unicode word segments as char ranges, whitespace runs and single symbols are segments too
+pub fn word_segments(text: &str) -> Vec<Range<usize>> {
+    let mut segments = Vec::new();
+    let mut offset = 0;
+
+    for segment in text.split_word_bounds() {
+        let len = segment.chars().count();
+        segments.push(offset..offset + len);
+        offset += len;
+    }
+
+    segments
+}
+
+// the cells `text` takes on screen, wide chars take two, combining marks none and tabs go on to
+// the next tab stop
+pub fn display_width(text: &str, tab_width: usize) -> usize {
+    text.graphemes(true)
+        .fold(0, |width, grapheme| match grapheme {
+            "\t" => width + tab_width - width % tab_width,
+            grapheme => width + grapheme.width(),
+        })
+}
@@ -0,0 +1,498 @@
+use std::{
+    borrow::Cow,
+    cmp::{max, min},
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use rayon::prelude::*;
+use tempfile::NamedTempFile;
+
+use super::{search::find_char_ranges, text::TextFile};
+
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+const BINARY_SNIFF_LEN: usize = 8000;
+const PREVIEW_LEN: usize = 120;
+const DIFF_CONTEXT: usize = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineMatch {
+    pub ln: usize,
+    pub col: usize,
+    pub text: String,
+    // a part of the line around the match, which starts at `preview_col` in it
+    pub preview: String,
+    pub preview_col: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileMatches {
+    pub path: PathBuf,
+    pub matches: Vec<LineMatch>,
+}
+
+// a line of a replace preview, numbered by the line it has in the old or the new text
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Context(usize, String),
+    Removed(usize, String),
+    Added(usize, String),
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceQuery {
+    pub needle: String,
+    pub case_sensitive: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct IgnoreRule {
+    base: PathBuf,
+    pattern: String,
+    dir_only: bool,
+    anchored: bool,
+    // `!pattern` takes back an earlier rule, the last rule matching a path decides
+    negated: bool,
+}
+
+impl IgnoreRule {
+    fn parse(base: &Path, line: &str) -> Option<Self> {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let negated = line.starts_with('!');
+        let line = line.strip_prefix('!').unwrap_or(line);
+        // `\!` and `\#` start patterns that are meant literally
+        let line = line.strip_prefix('\\').unwrap_or(line);
+
+        let dir_only = line.ends_with('/');
+        let line = line.trim_end_matches('/');
+        let anchored = line.contains('/');
+
+        Some(Self {
+            base: base.to_path_buf(),
+            pattern: line.trim_start_matches('/').to_string(),
+            dir_only,
+            anchored,
+            negated,
+        })
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        let Ok(relative) = path.strip_prefix(&self.base) else {
+            return false;
+        };
+
+        match self.anchored {
+            true => glob_match(&self.pattern, &to_slash_path(relative)),
+            false => path
+                .file_name()
+                .is_some_and(|name| glob_match(&self.pattern, &name.to_string_lossy())),
+        }
+    }
+}
+
+// a directory that is ignored is not walked, so nothing in it can be taken back
+fn is_ignored(rules: &[IgnoreRule], path: &Path, is_dir: bool) -> bool {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule.matches(path, is_dir))
+        .is_some_and(|rule| !rule.negated)
+}
+
+impl WorkspaceQuery {
+    pub fn new(needle: String, case_sensitive: bool, include: &str, exclude: &str) -> Self {
+        Self {
+            needle,
+            case_sensitive,
+            include: split_patterns(include),
+            exclude: split_patterns(exclude),
+        }
+    }
+
+    // `buffers` holds the unsaved text of opened files, which is searched instead of the disk
+    pub fn run(
+        &self,
+        root: &Path,
+        buffers: &HashMap<PathBuf, String>,
+        cancel: &AtomicBool,
+    ) -> Vec<FileMatches> {
+        if self.needle.is_empty() {
+            return Vec::new();
+        }
+
+        let files = self.collect_files(root, root, &[], cancel);
+
+        let mut results = files
+            .par_iter()
+            .filter_map(|path| {
+                if cancel.load(Ordering::Relaxed) {
+                    return None;
+                }
+
+                match buffers.get(path) {
+                    Some(content) => self.search_content(path, content),
+                    None => self.search_file(path),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        results.sort_by(|a, b| a.path.cmp(&b.path));
+        results
+    }
+
+    // the subdirectories are walked in parallel, each with the ignore rules of its parents
+    fn collect_files(
+        &self,
+        root: &Path,
+        dir: &Path,
+        rules: &[IgnoreRule],
+        cancel: &AtomicBool,
+    ) -> Vec<PathBuf> {
+        if cancel.load(Ordering::Relaxed) {
+            return Vec::new();
+        }
+
+        let own_rules = IGNORE_FILES
+            .iter()
+            .filter_map(|ignore_file| fs::read_to_string(dir.join(ignore_file)).ok())
+            .flat_map(|content| {
+                content
+                    .lines()
+                    .filter_map(|l| IgnoreRule::parse(dir, l))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let rules = match own_rules.is_empty() {
+            true => Cow::Borrowed(rules),
+            false => Cow::Owned([rules, &own_rules].concat()),
+        };
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut files = Vec::new();
+        let mut dirs = Vec::new();
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            // linked directories are not walked, one linking back to a parent would never end,
+            // linked files are searched like any other
+            if file_type.is_symlink() && path.is_dir() {
+                continue;
+            }
+            let is_dir = file_type.is_dir();
+
+            if path.file_name().is_some_and(|name| name == ".git")
+                || is_ignored(&rules, &path, is_dir)
+            {
+                continue;
+            }
+
+            let relative = to_slash_path(path.strip_prefix(root).unwrap_or(&path));
+
+            if self.exclude.iter().any(|p| pattern_matches(p, &relative)) {
+                continue;
+            }
+
+            if is_dir {
+                dirs.push(path);
+            } else if self.include.is_empty()
+                || self.include.iter().any(|p| pattern_matches(p, &relative))
+            {
+                files.push(path);
+            }
+        }
+
+        files.par_extend(
+            dirs.par_iter()
+                .flat_map_iter(|dir| self.collect_files(root, dir, &rules, cancel)),
+        );
+        files
+    }
+
+    fn search_file(&self, path: &Path) -> Option<FileMatches> {
+        let bytes = fs::read(path).ok()?;
+
+        if is_binary(&bytes) {
+            return None;
+        }
+
+        let content = String::from_utf8(bytes).ok()?;
+
+        self.search_content(path, &content)
+    }
+
+    fn search_content(&self, path: &Path, content: &str) -> Option<FileMatches> {
+        let matches = content
+            .lines()
+            .enumerate()
+            .flat_map(|(ln, line)| {
+                find_char_ranges(line.chars(), &self.needle, self.case_sensitive)
+                    .into_iter()
+                    .map(move |(start, end)| {
+                        // the preview is centred on the match, so a match far into the line shows
+                        let line_len = line.trim_end().chars().count();
+                        let context = PREVIEW_LEN.saturating_sub(end - start) / 2;
+                        let preview_start = min(
+                            start.saturating_sub(context),
+                            line_len.saturating_sub(PREVIEW_LEN),
+                        );
+
+                        LineMatch {
+                            ln,
+                            col: start,
+                            text: line.chars().skip(start).take(end - start).collect(),
+                            preview: line
+                                .trim_end()
+                                .chars()
+                                .skip(preview_start)
+                                .take(max(PREVIEW_LEN, end - start))
+                                .collect(),
+                            preview_col: start - preview_start,
+                        }
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        match matches.is_empty() {
+            true => None,
+            false => Some(FileMatches {
+                path: path.to_path_buf(),
+                matches,
+            }),
+        }
+    }
+}
+
+impl LineMatch {
+    pub fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+}
+
+pub fn replace_in_buffer(file: &mut TextFile, matches: &[LineMatch], replacement: &str) -> usize {
+    // the buffer may have been edited since the search, only replace what still matches
+    let ranges = matches
+        .iter()
+        .filter(|m| m.ln < file.rope.len_lines())
+        .filter_map(|m| {
+            let start = file.rope.line_to_char(m.ln) + m.col;
+            let end = start + m.len();
+
+            match end <= file.rope.len_chars() && file.rope.slice(start..end) == m.text.as_str() {
+                true => Some((start, end)),
+                false => None,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    file.replace_ranges("Replace in files", &ranges, replacement);
+    ranges.len()
+}
+
+pub fn replace_in_file(path: &Path, matches: &[LineMatch], replacement: &str) -> io::Result<usize> {
+    let content = fs::read_to_string(path)?;
+    let (new_content, replaced) = replace_in_content(&content, matches, replacement);
+
+    if replaced > 0 {
+        write_atomically(path, &new_content)?;
+    }
+
+    Ok(replaced)
+}
+
+pub fn replace_in_content(
+    content: &str,
+    matches: &[LineMatch],
+    replacement: &str,
+) -> (String, usize) {
+    replace_lines(content, matches, replacement).fold(
+        (String::new(), 0),
+        |(mut new_content, replaced), (_, new_line, count)| {
+            new_content.push_str(&new_line);
+            (new_content, replaced + count)
+        },
+    )
+}
+
+// the changed lines of `replace_in_content` with a line of context around them
+pub fn replace_preview(content: &str, matches: &[LineMatch], replacement: &str) -> Vec<DiffLine> {
+    let lines = replace_lines(content, matches, replacement).collect::<Vec<_>>();
+    let changed = |ln: usize| lines.get(ln).is_some_and(|(old, new, _)| old != new);
+
+    let mut diff = Vec::new();
+    let mut new_ln = 0;
+
+    for (ln, (old, new, _)) in lines.iter().enumerate() {
+        let near_change = (ln.saturating_sub(DIFF_CONTEXT)..=ln + DIFF_CONTEXT).any(changed);
+
+        if old != new {
+            diff.push(DiffLine::Removed(ln, old.trim_end().to_string()));
+            diff.extend(
+                new.split_inclusive('\n')
+                    .enumerate()
+                    .map(|(i, line)| DiffLine::Added(new_ln + i, line.trim_end().to_string())),
+            );
+        } else if near_change {
+            diff.push(DiffLine::Context(ln, old.trim_end().to_string()));
+        } else if diff.last().is_some_and(|line| line != &DiffLine::Skipped) {
+            diff.push(DiffLine::Skipped);
+        }
+
+        new_ln += new.split_inclusive('\n').count();
+    }
+
+    if diff.last() == Some(&DiffLine::Skipped) {
+        diff.pop();
+    }
+
+    diff
+}
+
+// every line of `content` next to what it becomes and how many matches were replaced in it
+fn replace_lines<'a>(
+    content: &'a str,
+    matches: &'a [LineMatch],
+    replacement: &'a str,
+) -> impl Iterator<Item = (&'a str, String, usize)> {
+    content
+        .split_inclusive('\n')
+        .enumerate()
+        .map(move |(ln, line)| {
+            let chars = line.chars().collect::<Vec<_>>();
+            let mut line_matches = matches.iter().filter(|m| m.ln == ln).collect::<Vec<_>>();
+            line_matches.sort_by_key(|m| m.col);
+
+            let mut new_line = String::new();
+            let mut col = 0;
+            let mut replaced = 0;
+
+            for m in line_matches {
+                let end = m.col + m.len();
+
+                if m.col < col
+                    || end > chars.len()
+                    || chars[m.col..end].iter().collect::<String>() != m.text
+                {
+                    continue;
+                }
+
+                new_line.extend(&chars[col..m.col]);
+                new_line.push_str(replacement);
+                col = end;
+                replaced += 1;
+            }
+
+            new_line.extend(&chars[col..]);
+            (line, new_line, replaced)
+        })
+}
+
+// write next to the target and rename over it, so a failed write never leaves a truncated file,
+// the temp file gets a name of its own so it can't clobber another file or a parallel write
+pub fn write_atomically(path: &Path, content: impl AsRef<[u8]>) -> io::Result<()> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+
+    let mut temp = NamedTempFile::new_in(dir)?;
+    temp.write_all(content.as_ref())?;
+
+    if let Ok(metadata) = fs::metadata(path) {
+        temp.as_file().set_permissions(metadata.permissions())?;
+    }
+
+    temp.persist(path).map(|_| ()).map_err(|e| e.error)
+}
+
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
+
+fn split_patterns(patterns: &str) -> Vec<String> {
+    patterns
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn to_slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// patterns without a slash match the file name, e.g. `*.rs`, others match the relative path
+fn pattern_matches(pattern: &str, relative: &str) -> bool {
+    match pattern.contains('/') {
+        true => glob_match(pattern, relative),
+        false => relative
+            .rsplit('/')
+            .next()
+            .is_some_and(|name| glob_match(pattern, name)),
+    }
+}
+
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    glob_match_from(&pattern, &text)
+}
+
+fn glob_match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+
+        ['*', '*', rest @ ..] => match rest.strip_prefix(&['/']) {
+            // `**/` stands for zero or more whole directories
+            Some(rest) => (0..=text.len())
+                .filter(|&i| i == 0 || text[i - 1] == '/')
+                .any(|i| glob_match_from(rest, &text[i..])),
+            None => (0..=text.len()).any(|i| glob_match_from(rest, &text[i..])),
+        },
+
+        ['*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| glob_match_from(rest, &text[i..])),
+
+        ['?', rest @ ..] => match text {
+            [c, text_rest @ ..] if *c != '/' => glob_match_from(rest, text_rest),
+            _ => false,
+        },
+
+        [p, rest @ ..] => match text {
+            [c, text_rest @ ..] if c == p => glob_match_from(rest, text_rest),
+            _ => false,
+        },
+    }
+}
@@ -0,0 +1,208 @@
+use std::{
+    cell::RefCell,
+    cmp::min,
+    collections::{BTreeMap, HashMap},
+    fmt, fs, io,
+    ops::RangeInclusive,
+};
+
+use super::theme::config_dir;
+
+const WRAP_COLUMN_FILE: &str = "wrap_column";
+const DEFAULT_WRAP_COLUMN: usize = 80;
+pub const WRAP_COLUMNS: RangeInclusive<usize> = 10..=1000;
+const WIDTHS_KEPT: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum WrapMode {
+    #[default]
+    Off,
+    Viewport,
+    Column(usize),
+}
+
+impl WrapMode {
+    // `column` is where the column mode wraps, the one last set in the status bar
+    pub fn next(self, column: usize) -> Self {
+        match self {
+            WrapMode::Off => WrapMode::Viewport,
+            WrapMode::Viewport => WrapMode::Column(column),
+            WrapMode::Column(_) => WrapMode::Off,
+        }
+    }
+
+    pub fn width(self, viewport_columns: usize) -> Option<usize> {
+        match self {
+            WrapMode::Off => None,
+            WrapMode::Viewport => Some(viewport_columns),
+            WrapMode::Column(column) => Some(column),
+        }
+    }
+}
+
+impl fmt::Display for WrapMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WrapMode::Off => write!(f, "No wrap"),
+            WrapMode::Viewport => write!(f, "Wrap"),
+            WrapMode::Column(column) => write!(f, "Wrap at {column}"),
+        }
+    }
+}
+
+pub fn load_wrap_column() -> usize {
+    config_dir()
+        .and_then(|dir| fs::read_to_string(dir.join(WRAP_COLUMN_FILE)).ok())
+        .and_then(|column| parse_wrap_column(&column))
+        .unwrap_or(DEFAULT_WRAP_COLUMN)
+}
+
+pub fn save_wrap_column(column: usize) -> io::Result<()> {
+    let Some(dir) = config_dir() else {
+        return Ok(());
+    };
+
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(WRAP_COLUMN_FILE), column.to_string())
+}
+
+pub fn parse_wrap_column(input: &str) -> Option<usize> {
+    input
+        .trim()
+        .parse()
+        .ok()
+        .filter(|column| WRAP_COLUMNS.contains(column))
+}
+
+// the visual rows of one logical line, columns are always logical
+#[derive(Debug, Clone, PartialEq)]
+pub struct WrappedLine {
+    // column of the first char of every row, the first row starts at 0
+    pub starts: Vec<usize>,
+    // continuation rows are indented like the line itself
+    pub indent: usize,
+    // length without the newline
+    pub len: usize,
+}
+
+impl WrappedLine {
+    pub fn new(line: &[char], width: Option<usize>) -> Self {
+        let mut wrapped = Self {
+            starts: vec![0],
+            indent: 0,
+            len: line.len(),
+        };
+
+        let Some(width) = width.map(|w| w.max(1)) else {
+            return wrapped;
+        };
+
+        let indent = line
+            .iter()
+            .take_while(|c| **c == ' ' || **c == '\t')
+            .count();
+        if indent * 2 <= width {
+            wrapped.indent = indent;
+        }
+
+        let mut start = 0;
+        loop {
+            let available = match wrapped.starts.len() {
+                1 => width,
+                _ => width - wrapped.indent,
+            };
+
+            if line.len() - start <= available {
+                break;
+            }
+
+            // break after the last whitespace so words stay whole, long words are cut
+            let limit = start + available;
+            let end = (start + 1..=limit)
+                .rev()
+                .find(|&i| line[i - 1].is_whitespace() && !line[i].is_whitespace())
+                .unwrap_or(limit);
+
+            wrapped.starts.push(end);
+            start = end;
+        }
+
+        wrapped
+    }
+
+    pub fn rows(&self) -> usize {
+        self.starts.len()
+    }
+
+    pub fn row_of(&self, col: usize) -> usize {
+        self.starts.partition_point(|start| *start <= col) - 1
+    }
+
+    // the last column the caret can take on a row, the newline on the last one
+    pub fn row_end(&self, row: usize) -> usize {
+        match self.starts.get(row + 1) {
+            Some(next) => next - 1,
+            None => self.len,
+        }
+    }
+
+    pub fn row_range(&self, row: usize) -> std::ops::Range<usize> {
+        self.starts[row]..self.row_end(row) + 1
+    }
+
+    fn row_indent(&self, row: usize) -> usize {
+        match row {
+            0 => 0,
+            _ => self.indent,
+        }
+    }
+
+    // where a column is drawn, counted from the start of its row
+    pub fn x_of(&self, col: usize) -> usize {
+        let row = self.row_of(col);
+        col - self.starts[row] + self.row_indent(row)
+    }
+
+    pub fn col_at(&self, row: usize, x: usize) -> usize {
+        let row = min(row, self.rows() - 1);
+        let col = self.starts[row] + x.saturating_sub(self.row_indent(row));
+
+        min(col, self.row_end(row))
+    }
+}
+
+// wrapped lines by the width they were wrapped at, every pane can wrap at its own
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WrapCache {
+    lines: RefCell<HashMap<Option<usize>, BTreeMap<usize, WrappedLine>>>,
+}
+
+impl WrapCache {
+    pub fn get(
+        &self,
+        ln: usize,
+        width: Option<usize>,
+        wrap: impl FnOnce() -> WrappedLine,
+    ) -> WrappedLine {
+        let mut lines = self.lines.borrow_mut();
+
+        // resizing a pane wrapping at the viewport leaves the old widths behind
+        if !lines.contains_key(&width) && lines.len() >= WIDTHS_KEPT {
+            lines.clear();
+        }
+
+        lines
+            .entry(width)
+            .or_default()
+            .entry(ln)
+            .or_insert_with(wrap)
+            .clone()
+    }
+
+    // an edit can change its first line and move the ones below
+    pub fn edit(&mut self, first_line: usize) {
+        for lines in self.lines.get_mut().values_mut() {
+            lines.split_off(&first_line);
+        }
+    }
+}
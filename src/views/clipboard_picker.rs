@@ -0,0 +1,145 @@
+use dioxus::prelude::*;
+
+use crate::models::{
+    clipboard::{ClipEntry, ClipboardRing},
+    macros::{EditorCommand, Macros},
+    tabs::Tabs,
+};
+
+// Ctrl+Shift+V, pastes any of the last copies, with the arrows and enter, a digit or a click
+#[component]
+pub fn ClipboardPicker(tabs: Signal<Tabs>, shown: Signal<bool>, on_close: EventHandler) -> Element {
+    let mut ring = use_context::<Signal<ClipboardRing>>();
+    let mut macros = use_context::<Signal<Macros>>();
+    let mut selected = use_signal(|| 0);
+
+    if !shown() {
+        return rsx! {};
+    }
+
+    let entries = ring.read().entries.clone();
+
+    let mut close = move || {
+        selected.set(0);
+        shown.set(false);
+        on_close.call(());
+    };
+
+    let mut paste = move |entry: ClipEntry| {
+        let command = match entry.linewise {
+            true => EditorCommand::InsertLines(entry.text.clone()),
+            false => EditorCommand::InsertText(entry.text.clone()),
+        };
+
+        if let Some(file) = tabs.write().get_current_file_mut() {
+            command.run(file);
+            macros.write().record(&command);
+        }
+
+        // the picked entry becomes the newest, so Ctrl+V pastes it again
+        ring.write().copy(entry.text, entry.linewise);
+        close();
+    };
+
+    let onkeydown = {
+        let entries = entries.clone();
+
+        move |e: KeyboardEvent| {
+            e.stop_propagation();
+            e.prevent_default();
+
+            match e.key() {
+                Key::Escape => close(),
+                Key::ArrowDown if selected() + 1 < entries.len() => selected += 1,
+                Key::ArrowUp if selected() > 0 => selected -= 1,
+                Key::Enter => {
+                    if let Some(entry) = entries.get(selected()) {
+                        paste(entry.clone());
+                    }
+                }
+                Key::Character(c) => {
+                    let entry = c
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|n| n.checked_sub(1))
+                        .and_then(|i| entries.get(i));
+
+                    if let Some(entry) = entry {
+                        paste(entry.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+    };
+
+    rsx! {
+        div {
+            class: "right-click-menu",
+            tabindex: 0,
+            style: "position: fixed; top: 20%; left: 50%; transform: translateX(-50%); width: 500px; max-height: 60vh;
+                    overflow-y: auto; display: flex; flex-direction: column;",
+
+            onmounted: move |e| async move {
+                let _ = e.data().as_ref().set_focus(true).await;
+            },
+            onfocusout: move |_| close(),
+            onkeydown,
+
+            div {
+                style: "display: flex; padding: 5px 10px; font-family: JetBrains Mono; font-size: 12px; opacity: 0.7;
+                        color: var(--chrome-foreground);",
+                span { style: "flex: 1;", "Clipboard history" }
+                span {
+                    style: "cursor: pointer; margin-right: 15px;",
+                    title: "Keep the history after a restart, in a file only you can read",
+                    onmousedown: move |e| e.prevent_default(),
+                    onclick: move |_| {
+                        let persistent = ring.peek().persistent;
+                        ring.write().set_persistent(!persistent);
+                    },
+                    if ring.read().persistent { "Kept after restart" } else { "Not kept after restart" }
+                }
+                span {
+                    style: "cursor: pointer;",
+                    onmousedown: move |e| e.prevent_default(),
+                    onclick: move |_| ring.write().clear(),
+                    "Clear"
+                }
+            }
+
+            if entries.is_empty() {
+                div {
+                    style: "padding: 5px 10px; font-family: JetBrains Mono; font-size: 15px; color: var(--chrome-foreground);",
+                    "Nothing copied yet"
+                }
+            }
+
+            for (i, entry) in entries.into_iter().enumerate() {
+                div {
+                    class: "option-button",
+                    style: if i == selected() { "background-color: var(--chrome-control-active);" } else { "" },
+                    title: entry.text.clone(),
+                    onmouseenter: move |_| selected.set(i),
+                    onclick: {
+                        let entry = entry.clone();
+                        move |_| paste(entry.clone())
+                    },
+                    span {
+                        style: "white-space: nowrap; overflow: hidden; text-overflow: ellipsis;",
+                        if i < 9 { "{i + 1}  " }
+                        if entry.linewise { "¶ " }
+                        "{entry.preview()}"
+                    }
+                }
+            }
+
+            if let Some(error) = ring.read().error.clone() {
+                div {
+                    style: "padding: 5px 10px; font-size: 12px; color: var(--chrome-error); white-space: normal;",
+                    "{error}"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::{io, path::PathBuf};
+
+use dioxus::prelude::*;
+
+use crate::models::{permissions::save_elevated, tabs::Tabs};
+
+// asks before handing a file we may not write to the system's password prompt
+#[component]
+pub fn ElevatedSaveDialog(
+    mut tabs: Signal<Tabs>,
+    path: PathBuf,
+    mut elevate: Signal<Option<PathBuf>>,
+    mut notice: Signal<Option<String>>,
+) -> Element {
+    let save = {
+        let path = path.clone();
+
+        move |_| {
+            elevate.set(None);
+
+            let Some(content) = tabs.read().get_tab(&path).map(|tab| tab.content()) else {
+                return;
+            };
+
+            let path = path.clone();
+            notice.set(Some("Waiting for authentication...".to_string()));
+
+            spawn(async move {
+                let save_path = path.clone();
+                let result =
+                    tokio::task::spawn_blocking(move || save_elevated(&save_path, &content))
+                        .await
+                        .unwrap_or_else(|e| Err(io::Error::other(e)));
+
+                match result {
+                    Ok(()) => {
+                        if let Some(tab) = tabs.write().get_tab_mut(&path) {
+                            tab.mark_saved();
+                        }
+                        notice.set(Some("Saved with elevated permissions".to_string()));
+                    }
+                    Err(e) => notice.set(Some(format!("Could not save: {e}"))),
+                }
+            });
+        }
+    };
+
+    rsx! {
+        div {
+            class: "dialog-overlay",
+            font_family: "JetBrains Mono",
+            div {
+                class: "dialog",
+                div {
+                    class: "dialog-content",
+                    p { "You don't have permission to write {path.display()}." }
+                    p { "Save it as an administrator? The system asks for your password." }
+                    div {
+                        display: "flex",
+                        flex_direction: "row",
+                        div {
+                            class: "dialog-button",
+                            style: "width: auto; padding: 0 10px;",
+                            onclick: save,
+                            "Save as administrator"
+                        }
+                        div {
+                            class: "dialog-button",
+                            onclick: move |_| elevate.set(None),
+                            "Cancel"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,2 +1,3 @@
+pub mod elevated_save;
 pub mod error;
 pub mod fs_operations;
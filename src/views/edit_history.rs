@@ -9,10 +9,10 @@ pub fn EditHistory(tabs: Signal<Tabs>) -> Element {
     let Some(ref file) = *f.read() else {
         return rsx! {
             div {
-                style: "flex: 1; background-color: rgb(124, 123, 65); overflow: hidden;
-                        width: 100%; display: flex; color: white; justify-content: center; align-items: center; font-size: 20px; font-family: JetBrains Mono;",
+                style: "flex: 1; background-color: var(--chrome-panel); overflow: hidden;
+                        width: 100%; display: flex; color: var(--chrome-panel-foreground); justify-content: center; align-items: center; font-size: 20px; font-family: JetBrains Mono;",
                 div {
-                    style: "display: flex; justify-content: center; align-items: center; color: rgb(204, 204, 204)",
+                    style: "display: flex; justify-content: center; align-items: center; color: var(--chrome-muted-foreground)",
                     "No file selected"
                 }
             }
@@ -24,15 +24,15 @@ pub fn EditHistory(tabs: Signal<Tabs>) -> Element {
 
     rsx! {
         div {
-            style:"flex: 1; background-color: rgb(124, 123, 65); overflow: hidden; flex-direction: column;
-                   width: 100%; display: flex; color: white; justify-content: center; align-items: center; font-size: 20px; font-family: JetBrains Mono;",
+            style:"flex: 1; background-color: var(--chrome-panel); overflow: hidden; flex-direction: column;
+                   width: 100%; display: flex; color: var(--chrome-panel-foreground); justify-content: center; align-items: center; font-size: 20px; font-family: JetBrains Mono;",
             div {
                 style: "display: flex; justify-content: center; align-items: center; width: 100%; padding: 7px",
                 "History"
             }
             div {
                 // divider
-                style: "height: 3px; background-color: rgba(105, 211, 238, 0.43); width: 100%;",
+                style: "height: 3px; background-color: var(--chrome-accent); width: 100%;",
             }
             div {
                 style: "display: flex; flex-direction: column; width: 100%;
@@ -46,7 +46,7 @@ pub fn EditHistory(tabs: Signal<Tabs>) -> Element {
                     }
                     div {
                         //divider
-                        style: "height: 1px; background-color: rgba(206, 206, 206, 0.35); width: 90%;",
+                        style: "height: 1px; background-color: var(--chrome-separator); width: 90%;",
                     }
                 }
             }
@@ -1,43 +1,141 @@
 use std::{
     cmp::{max, min},
+    io,
+    path::PathBuf,
     rc::Rc,
+    time::{Duration, Instant},
 };
 
 use crate::models::{
-    tabs::Tabs,
+    clipboard::ClipboardRing,
+    large_file::Load,
+    macros::{play, EditorCommand, Macros, Playback},
+    search::Search,
+    syntax::{Span, TokenKind},
+    tabs::{Split, Tab, Tabs},
     text::{Caret, TextFile},
+    wrap::{
+        load_wrap_column, parse_wrap_column, save_wrap_column, WrapMode, WrappedLine, WRAP_COLUMNS,
+    },
 };
 
-use arboard::Clipboard;
+use crate::views::{
+    clipboard_picker::ClipboardPicker,
+    dialogs::{elevated_save::ElevatedSaveDialog, error::ErrorDialogHandler},
+    editor_menu::EditorMenu,
+    hex_view::HexView,
+    large_file::LargeFileNotice,
+};
+
+use dioxus::html::{geometry::WheelDelta, input_data::MouseButton, HasFileData};
 use dioxus::prelude::*;
+use dioxus_heroicons::{mini::Shape, Icon};
 use itertools::Itertools;
 use tracing::info;
 
-#[component]
-pub fn Editor(tabs: Signal<Tabs>) -> Element {
-    let text: Memo<Option<TextFile>> = use_memo(move || tabs.read().get_current_file());
+const LINE_HEIGHT: f64 = 22.0;
+const OVERSCAN: usize = 3;
+const WIDTH_PROBE: &str = "0000000000";
+const SCROLLBAR_WIDTH: f64 = 12.0;
+const FOLD_MARKER_COLUMNS: usize = 2;
+const LINE_PADDING: f64 = 10.0;
+const GUTTER_PADDING: f64 = 10.0;
+const MULTI_CLICK_TIME: Duration = Duration::from_millis(400);
+const AUTOSCROLL_INTERVAL: Duration = Duration::from_millis(50);
+// the scrollbar shows at most this many match markers, one per stretch of lines
+const MARKER_ROWS: usize = 200;
 
-    let caret = use_memo(move || match text.read().clone() {
-        Some(text) => text.get_caret(),
+#[component]
+pub fn Editor(tabs: Signal<Tabs>, pane: usize) -> Element {
+    // derive only what the editor needs instead of cloning the whole file on every change
+    let caret = use_memo(move || match tabs.read().get_pane_text(pane) {
+        Some(text) => text.caret(),
         None => Caret::new(),
     });
 
+    let char_idx = use_memo(move || {
+        tabs.read()
+            .get_pane_text(pane)
+            .map_or(0, |text| text.view.char_idx)
+    });
+
     let caret_col = use_memo(move || caret.read().col);
 
     let caret_line = use_memo(move || caret.read().ln);
 
+    let display_col = use_memo(move || {
+        tabs.read()
+            .get_pane_text(pane)
+            .map_or(0, |text| text.display_col())
+    });
+
+    let search = use_context::<Signal<Search>>();
+    let mut macros = use_context::<Signal<Macros>>();
+    let mut clipboard = use_context::<Signal<ClipboardRing>>();
+    let mut picker_shown = use_signal(|| false);
+    // shown in the status bar until the next key press, like why an edit was refused
+    let mut notice = use_signal(|| None::<String>);
+    let elevate = use_signal(|| None::<PathBuf>);
+    let mut editor_element = use_signal(|| None::<Rc<MountedData>>);
+    // large files skip the work that goes over the whole text on every change
+    let brackets = use_memo(move || {
+        tabs.read()
+            .get_pane_text(pane)
+            .filter(|text| !text.file.large)
+            .and_then(|text| text.matching_brackets())
+    });
+
+    let load = use_memo(move || {
+        tabs.read()
+            .get_pane_tab(pane)
+            .map(|tab| tab.load.clone())
+            .filter(|load| *load != Load::Loaded)
+    });
+
+    let hex = use_memo(move || {
+        tabs.read()
+            .get_pane_tab(pane)
+            .is_some_and(|tab| tab.hex.is_some())
+    });
+
+    let viewport_lines = use_signal(|| 0);
+    let mut wrap_mode = use_signal(WrapMode::default);
+    let wrap_column = use_signal(load_wrap_column);
+
+    // keep the caret in view whenever it moves to another line, or another row of a wrapped one
+    use_effect(move || {
+        caret_line();
+        if wrap_mode() != WrapMode::Off {
+            caret_col();
+        }
+        let visible_lines = viewport_lines();
+
+        tabs.write()
+            .with_pane_file(pane, |file| file.scroll_to_caret(visible_lines));
+    });
+
     rsx! {
         div {
             tabindex: 0,
 
+            onmounted: move |e| editor_element.set(Some(e.data())),
+
             onfocusin: move |e| {
                 info!("focused on editor: {:?}", e);
+                focus_pane(tabs, pane);
             },
 
             onfocusout: move |e| {
                 info!("unfocused on editor: {:?}", e);
             },
 
+            // ctrl+tab switches for good once ctrl is released
+            onkeyup: move |e| {
+                if e.key() == Key::Control {
+                    tabs.write().finish_cycle();
+                }
+            },
+
             onkeydown: move |e| {
 
                 let ctrl = e.modifiers().contains(Modifiers::CONTROL);
@@ -46,69 +144,75 @@ pub fn Editor(tabs: Signal<Tabs>) -> Element {
 
                 info!("key pressed: {:?}, ctrl: {}, shift: {}", e.key(), ctrl, shift);
 
-                let mut tabss = tabs.write();
-                let Some(ref mut file) = tabss.get_current_file_mut() else {
-                    return;
-                };
+                if notice.peek().is_some() {
+                    notice.set(None);
+                }
 
-                let old_idx = file.char_idx;
+                focus_pane(tabs, pane);
 
-                match (e.key(), ctrl, shift, altt) {
-                    (Key::End, ctrl, selection, false) => {
-                        file.caret_move_line_end(ctrl);
-                        file.set_selection(selection, old_idx);
-                    }
-                    (Key::Home, ctrl, selection, false) => {
-                        file.caret_move_line_start(ctrl);
-                        file.set_selection(selection, old_idx);
-                    }
-                    (Key::ArrowLeft, ctrl, selection, false) => {
-                        file.caret_move_left(ctrl);
-                        file.set_selection(selection, old_idx);
+                // these change the tabs, the key handler below only borrows the current one
+                match (e.key(), ctrl, shift) {
+                    (Key::Character(b), true, false) if b == "\\" => {
+                        info!("split pressed");
+                        tabs.write().split(if altt { Split::Horizontal } else { Split::Vertical });
+                        return;
                     }
-                    (Key::ArrowRight, ctrl, selection, false) => {
-                        file.caret_move_right(ctrl);
-                        file.set_selection(selection, old_idx);
-                    }
-                    (Key::ArrowUp, false, selection, false) => {
-                        file.caret_move_up();
-                        file.set_selection(selection, old_idx);
-                    }
-                    (Key::ArrowDown, false, selection, false) => {
-                        file.caret_move_down();
-                        file.set_selection(selection, old_idx);
+                    (Key::Tab, true, reverse) => {
+                        e.prevent_default();
+                        tabs.write().cycle_recent(reverse);
+                        return;
                     }
+                    _ => {}
+                }
 
-                    (Key::Character(s), false, _, false) => {
-                        if let Some(c) = s.chars().next() {
-                            info!("inserting char: {:?}", c);
-                            file.insert_char(c);
-                        }
-                    }
+                let mut tabss = tabs.write();
+                let Some(tab) = tabss.get_current_tab_mut() else {
+                    return;
+                };
+                let file = &mut tab.file;
 
-                    (Key::Backspace, ctrl, false, false) => {
-                        file.backspace(ctrl);
-                    }
+                // tab has to stay in the editor instead of moving the focus
+                if e.key() == Key::Tab {
+                    e.prevent_default();
+                }
 
-                    (Key::Delete, ctrl, false, false) => {
-                        file.delete(ctrl);
+                let command = match (e.key(), ctrl, shift, altt) {
+                    (Key::F3, false, reverse, false) => {
+                        let search = search.read();
+                        Some(EditorCommand::FindNext {
+                            needle: search.needle.clone(),
+                            case_sensitive: search.case_sensitive,
+                            reverse,
+                        })
                     }
+                    (key, ctrl, shift, alt) => key_command(&key, ctrl, shift, alt),
+                };
 
-                    (Key::Escape, false, _, false) => {
-                        file.clear_selection();
+                if let Some(command) = command {
+                    if file.read_only && command.is_edit() {
+                        info!("refused in a read-only file: {:?}", command);
+                        notice.set(Some(READ_ONLY_NOTICE.to_string()));
+                        return;
                     }
 
-                    (Key::Enter, false, _, false) => {
-                        file.clear_selection();
-                        file.insert_newline();
-                    }
+                    info!("running command: {:?}", command);
+                    command.run(file);
+                    macros.write().record(&command);
+                    return;
+                }
 
-                    (Key::Tab, false, _, false) => {
-                        file.clear_selection();
-                        file.insert_tab();
-                        e.prevent_default();
-                    }
+                // copying, folding and the like still work in a read-only file
+                let edits = match (e.key(), ctrl, shift, altt) {
+                    (Key::Character(k), true, _, false) => ["z", "x", "v"].contains(&k.to_ascii_lowercase().as_str()),
+                    _ => false,
+                };
+                if file.read_only && edits {
+                    info!("refused in a read-only file: {:?}", e.key());
+                    notice.set(Some(READ_ONLY_NOTICE.to_string()));
+                    return;
+                }
 
+                match (e.key(), ctrl, shift, altt) {
                     (Key::Character(z), true, false, false) if &z.to_ascii_lowercase() == "z" => {
                         info!("undo pressed");
                         file.clear_selection();
@@ -124,213 +228,801 @@ pub fn Editor(tabs: Signal<Tabs>) -> Element {
                     (Key::Character(s), true, false, false) if &s.to_ascii_lowercase() == "s" => {
                         info!("save pressed");
                         file.clear_selection();
-                        file.save_to_file();
+                        save_tab(tab, notice, elevate);
+                    }
+
+                    (Key::Character(l), true, true, false) if l.eq_ignore_ascii_case("l") => {
+                        info!("toggle read-only pressed");
+                        tab.set_read_only(!tab.is_read_only());
                     }
 
                     (Key::Character(x), true, false, false) if &x.to_ascii_lowercase() == "x" => {
                         info!("cut pressed");
-                        let line = file.cut_line();
-
-                        let mut clipboard = Clipboard::new().ok();
-                        if let Some(clip) = clipboard.as_mut() {
-                            if clip.set_text(line.clone()).is_err() {
-                                info!("failed to copy to clipboard");
-                            } else {
-                                info!("copied to clipboard: {:?}", line);
-                            }
-                        }
+                        let entry = file.cut();
+                        macros.write().record(&EditorCommand::Cut);
+                        clipboard.write().copy(entry.text, entry.linewise);
                     }
 
 
                     (Key::Character(c), true, false, false) if &c.to_ascii_lowercase() == "c" => {
                         info!("copy pressed");
-                        if let Some(selection) = file.get_selection() {
-                            let mut clipboard = Clipboard::new().ok();
-                            if let Some(clip) = clipboard.as_mut() {
-                                if clip.set_text(selection.clone()).is_err() {
-                                    info!("failed to copy to clipboard");
-                                } else {
-                                    info!("copied to clipboard: {:?}", selection);
-                                }
-                            }
-                        }
+                        let entry = file.copy();
+                        clipboard.write().copy(entry.text, entry.linewise);
                     }
 
+                    // the pasted text is recorded, not the paste, so a macro does not depend on the clipboard
                     (Key::Character(v), true, false, false) if &v.to_ascii_lowercase() == "v" => {
                         info!("paste pressed");
-                        let mut clipboard = Clipboard::new().ok();
-                        if let Some(clip) = clipboard.as_mut() {
-                            if let Ok(text) = clip.get_text() {
-                                file.insert_string(text);
-                            }
+                        if let Some(entry) = clipboard.read().current() {
+                            let command = match entry.linewise {
+                                true => EditorCommand::InsertLines(entry.text),
+                                false => EditorCommand::InsertText(entry.text),
+                            };
+                            command.run(file);
+                            macros.write().record(&command);
                         }
                     }
 
-                    (Key::ArrowDown, false, false, true) => {
-                        info!("move line down pressed");
-                        file.move_line(true);
+                    (Key::Character(v), true, true, false) if v.eq_ignore_ascii_case("v") => {
+                        info!("paste from history pressed");
+                        picker_shown.set(true);
+                    }
+
+                    (Key::Character(z), false, false, true) if z.eq_ignore_ascii_case("z") => {
+                        info!("toggle wrap pressed");
+                        wrap_mode.set(wrap_mode().next(wrap_column()));
                     }
 
-                    (Key::ArrowUp, false, false, true) => {
-                        info!("move line up pressed");
-                        file.move_line(false);
+                    // shift turns the brackets into braces on most layouts
+                    (Key::Character(b), true, true, false) if b == "[" || b == "{" => {
+                        info!("fold pressed");
+                        file.fold_at_caret();
                     }
 
-                    (Key::ArrowDown, false, true, true) => {
-                        info!("duplicate line down pressed");
-                        file.duplicate_line(true);
+                    (Key::Character(b), true, true, false) if b == "]" || b == "}" => {
+                        info!("unfold pressed");
+                        file.unfold_at_caret();
                     }
 
-                    (Key::ArrowUp, false, true, true) => {
-                        info!("duplicate line up pressed");
-                        file.duplicate_line(false);
+                    (Key::Character(b), true, false, true) if b == "[" => {
+                        info!("fold all pressed");
+                        file.fold_all();
+                    }
+
+                    (Key::Character(b), true, false, true) if b == "]" => {
+                        info!("unfold all pressed");
+                        file.unfold_all();
+                    }
+
+                    (Key::Character(r), true, true, false) if r.eq_ignore_ascii_case("r") => {
+                        info!("toggle macro recording pressed");
+                        macros.write().toggle_recording();
+                    }
+
+                    (Key::Character(p), true, true, false) if p.eq_ignore_ascii_case("p") => {
+                        info!("play last macro pressed");
+                        let last = macros.read().last.clone();
+                        play(file, &last, Playback::Times(1));
+                    }
+
+                    (Key::Character(k), true, false, true) => {
+                        let bound = k
+                            .chars()
+                            .next()
+                            .and_then(|key| macros.read().bound_to(key).cloned());
+
+                        if let Some(bound) = bound {
+                            info!("playing macro: {}", bound.name);
+                            play(file, &bound.commands, Playback::Times(1));
+                        }
                     }
 
                     (_,_,_,_) => {}
                 }
             },
             style: "display: flex; flex-direction: column; flex: 1; overflow: hidden",
-            TopStatusBar {tabs},
+            TopStatusBar {tabs, pane, notice, elevate},
             div {
-                style: "height: 2px; background-color: rgb(90, 89, 75); width: 100%; align-self: center;",
+                style: "height: 2px; background-color: var(--chrome-separator); width: 100%; align-self: center;",
+            }
+            if let Some(load) = load() {
+                LargeFileNotice {tabs, pane, load}
+            } else if hex() {
+                HexView {tabs, pane, notice, elevate}
+            } else {
+                EditorText {tabs, pane,
+                     caret_col: caret_col(), caret_line: caret_line(), brackets: brackets(), viewport_lines, wrap_mode},
+            }
+            // the hex view has a status bar of its own
+            if !hex() {
+                BottomStatusBar {tabs, pane, caret_col: display_col(), caret_line: caret_line(), char_idx: char_idx(), wrap_mode, wrap_column, notice: notice()},
+            }
+            if let Some(path) = elevate() {
+                ElevatedSaveDialog {tabs, path, elevate, notice}
+            }
+            ClipboardPicker {
+                tabs,
+                shown: picker_shown,
+                on_close: move |_| {
+                    if let Some(editor) = editor_element() {
+                        spawn(async move {
+                            let _ = editor.set_focus(true).await;
+                        });
+                    }
+                },
+            },
+        }
+    }
+}
+
+// everything acting on the current file goes to the pane the user is working in
+pub fn focus_pane(mut tabs: Signal<Tabs>, pane: usize) {
+    if tabs.peek().focused != pane {
+        tabs.write().focus_pane(pane);
+    }
+}
+
+pub const READ_ONLY_NOTICE: &str = "The file is read-only, Ctrl+Shift+L makes it editable";
+
+// a file we may not write can still be saved with elevated permissions, after asking
+pub fn save_tab(
+    tab: &mut Tab,
+    mut notice: Signal<Option<String>>,
+    mut elevate: Signal<Option<PathBuf>>,
+) {
+    match tab.save() {
+        Ok(()) => {}
+        Err(_) if tab.is_read_only() => notice.set(Some(READ_ONLY_NOTICE.to_string())),
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+            elevate.set(Some(tab.file.path.clone()))
+        }
+        Err(e) => notice.set(Some(format!("Could not save: {e}"))),
+    }
+}
+
+// the keys that edit the text or move the caret, everything they do can be recorded in a macro
+fn key_command(key: &Key, ctrl: bool, shift: bool, alt: bool) -> Option<EditorCommand> {
+    let command = match (key, ctrl, shift, alt) {
+        (Key::End, document, select, false) => EditorCommand::LineEnd { document, select },
+        (Key::Home, document, select, false) => EditorCommand::LineStart { document, select },
+        (Key::ArrowLeft, word, select, false) => EditorCommand::MoveLeft { word, select },
+        (Key::ArrowRight, word, select, false) => EditorCommand::MoveRight { word, select },
+        (Key::ArrowUp, false, select, false) => EditorCommand::MoveUp { select },
+        (Key::ArrowDown, false, select, false) => EditorCommand::MoveDown { select },
+
+        // composed input, like an emoji with modifiers, comes as several chars at once
+        (Key::Character(s), false, _, false) => match s.chars().count() {
+            1 => EditorCommand::InsertChar(s.chars().next()?),
+            _ => EditorCommand::InsertText(s.clone()),
+        },
+
+        (Key::Backspace, word, false, false) => EditorCommand::Backspace { word },
+        (Key::Delete, word, false, false) => EditorCommand::Delete { word },
+        (Key::Escape, false, _, false) => EditorCommand::ClearSelection,
+        (Key::Enter, false, _, false) => EditorCommand::Newline,
+        (Key::Enter, true, above, false) => EditorCommand::InsertLine { below: !above },
+        (Key::Tab, false, false, false) => EditorCommand::Indent,
+        (Key::Tab, false, true, false) => EditorCommand::Outdent,
+
+        (Key::Character(b), true, true, false) if b == "\\" || b == "|" => {
+            EditorCommand::JumpToBracket
+        }
+        (Key::Character(c), true, false, false) if c == "/" => EditorCommand::ToggleLineComment,
+        (Key::Character(c), true, true, false) if c == "/" || c == "?" => {
+            EditorCommand::ToggleBlockComment
+        }
+        (Key::Character(k), true, true, false) if k.eq_ignore_ascii_case("k") => {
+            EditorCommand::DeleteLines
+        }
+        (Key::Character(j), true, false, false) if j.eq_ignore_ascii_case("j") => {
+            EditorCommand::JoinLines
+        }
+        (Key::Character(t), true, false, false) if t.eq_ignore_ascii_case("t") => {
+            EditorCommand::TransposeChars
+        }
+        (Key::Character(t), false, false, true) if t.eq_ignore_ascii_case("t") => {
+            EditorCommand::TransposeWords
+        }
+
+        (Key::ArrowUp, true, false, true) => EditorCommand::Increment(1),
+        (Key::ArrowDown, true, false, true) => EditorCommand::Increment(-1),
+        (Key::ArrowDown, false, duplicate, true) => match duplicate {
+            true => EditorCommand::DuplicateLine { down: true },
+            false => EditorCommand::MoveLine { down: true },
+        },
+        (Key::ArrowUp, false, duplicate, true) => match duplicate {
+            true => EditorCommand::DuplicateLine { down: false },
+            false => EditorCommand::MoveLine { down: false },
+        },
+
+        _ => return None,
+    };
+
+    Some(command)
+}
+
+// what the pointer does while the primary button is held over the text
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Drag {
+    // extends the selection from an anchor
+    Select(usize),
+    // drags the selected text, keeping the caret it had before the drag started
+    Text(usize),
+}
+
+impl Drag {
+    fn update(self, file: &mut TextFile, idx: usize) {
+        match self {
+            Drag::Select(anchor) => file.select_to(anchor, idx),
+            // the caret shows where the text is going to be dropped
+            Drag::Text(_) => file.char_idx = idx,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CharKind {
+    Plain,
+    Selected,
+    Match,
+    Caret,
+    Bracket,
+}
+
+impl CharKind {
+    fn style(self) -> &'static str {
+        match self {
+            CharKind::Plain => "",
+            CharKind::Selected => "background-color: var(--editor-selection);",
+            CharKind::Match => "background-color: var(--editor-search-match);",
+            CharKind::Caret => "background-color: var(--editor-caret);",
+            CharKind::Bracket => {
+                "outline: 1px solid var(--editor-bracket-match); outline-offset: -1px;"
             }
-            EditorText {tabs,
-                 caret_col: caret_col(), caret_line: caret_line()},
-            BottomStatusBar {tabs, caret_col: caret_col(), caret_line: caret_line(), char_idx: text.read().clone().map_or(0, |t| t.char_idx)},
         }
     }
 }
 
 #[component]
-pub fn EditorText(tabs: Signal<Tabs>, caret_col: usize, caret_line: usize) -> Element {
-    let Some(ref text) = tabs.read().get_current_file() else {
+pub fn EditorText(
+    tabs: Signal<Tabs>,
+    pane: usize,
+    caret_col: usize,
+    caret_line: usize,
+    brackets: Option<(usize, usize)>,
+    viewport_lines: Signal<usize>,
+    wrap_mode: Signal<WrapMode>,
+) -> Element {
+    let search = use_context::<Signal<Search>>();
+    let mut viewport_height = use_signal(|| 0.0);
+    let mut viewport_width = use_signal(|| 0.0);
+    let mut char_width = use_signal(|| 9.6);
+    let mut wheel_rest = use_signal(|| 0.0);
+
+    let mut lines_element: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
+    // left, top and height of the text area in client coordinates
+    let mut lines_rect = use_signal(|| (0.0, 0.0, 0.0));
+    let mut pointer = use_signal(|| (0.0, 0.0));
+    let mut drag: Signal<Option<Drag>> = use_signal(|| None);
+    let mut last_click: Signal<Option<(Instant, usize, usize)>> = use_signal(|| None);
+    let mut menu_position: Signal<Option<(f64, f64)>> = use_signal(|| None);
+
+    let gutter_width = use_memo(move || {
+        let digits = tabs
+            .read()
+            .get_pane_text(pane)
+            .map_or(1, |text| text.file.len_lines().to_string().len());
+
+        (max(digits, 3) + FOLD_MARKER_COLUMNS) as f64 * char_width() + GUTTER_PADDING
+    });
+
+    let viewport_columns = use_memo(move || {
+        let width = viewport_width() - gutter_width() - 2.0 * LINE_PADDING - SCROLLBAR_WIDTH;
+        max((width / char_width()).floor() as usize, 1)
+    });
+
+    // the model moves the caret by visual rows, so it has to know where lines wrap
+    let current_file = use_memo(move || {
+        tabs.read()
+            .get_pane(pane)
+            .and_then(|pane| pane.current_file.clone())
+    });
+    use_effect(move || {
+        current_file();
+        let wrap_width = wrap_mode().width(viewport_columns());
+
+        tabs.write()
+            .with_pane_file(pane, |file| file.wrap_width = wrap_width);
+    });
+
+    let idx_at = move |x: f64, y: f64| {
+        let (left, top, _) = *lines_rect.peek();
+        let tabs = tabs.peek();
+        let text = tabs.get_pane_text(pane)?;
+
+        let row = ((y - top) / LINE_HEIGHT).floor().max(0.0) as usize;
+        let col = ((x - left - LINE_PADDING - gutter_width()) / char_width())
+            .round()
+            .max(0.0) as usize;
+
+        Some(text.char_idx_at_row(row, col))
+    };
+
+    // keeps extending the selection while the pointer rests above or below the text, the loop
+    // only runs during a drag
+    let mut autoscroll: Signal<Option<Task>> = use_signal(|| None);
+    use_effect(move || {
+        if drag().is_none() {
+            if let Some(task) = autoscroll.take() {
+                task.cancel();
+            }
+            return;
+        }
+
+        if autoscroll.peek().is_some() {
+            return;
+        }
+
+        autoscroll.set(Some(spawn(async move {
+            loop {
+                tokio::time::sleep(AUTOSCROLL_INTERVAL).await;
+
+                let Some(current_drag) = *drag.peek() else {
+                    break;
+                };
+
+                let (x, y) = *pointer.peek();
+                let (_, top, height) = *lines_rect.peek();
+
+                let lines = if y < top + LINE_HEIGHT / 2.0 {
+                    -1
+                } else if y > top + height - LINE_HEIGHT / 2.0 {
+                    1
+                } else {
+                    continue;
+                };
+
+                tabs.write()
+                    .with_pane_file(pane, |file| file.scroll_by(lines));
+
+                let y = y.clamp(top, top + height - LINE_HEIGHT);
+                if let Some(idx) = idx_at(x, y) {
+                    tabs.write()
+                        .with_pane_file(pane, |file| current_drag.update(file, idx));
+                }
+            }
+        })));
+    });
+
+    // files dropped from outside of the app are opened as tabs
+    let open_dropped_files = move |e: DragEvent| {
+        e.prevent_default();
+
+        let Some(files) = e.files() else {
+            return;
+        };
+
+        focus_pane(tabs, pane);
+
+        for path in files.files().into_iter().map(PathBuf::from) {
+            if path.is_file() {
+                tabs.write().open_tab(path);
+            }
+        }
+    };
+
+    let tabs_ref = tabs.read();
+    let Some(pane_text) = tabs_ref.get_pane_text(pane) else {
         return rsx! {
             div {
-                style: "background-color: rgb(24, 24, 24); display: flex; overflow-y: scroll;
-                        flex: 1; flex-direction: column; color: white; position: relative; justify-content: center; align-items: center;",
+                style: "background-color: var(--editor-empty-background); display: flex; overflow-y: scroll;
+                        flex: 1; flex-direction: column; color: var(--editor-foreground); position: relative; justify-content: center; align-items: center;",
+                ondragover: move |e| e.prevent_default(),
+                ondrop: open_dropped_files,
                 div {
-                    style: "color: gray; font-size: 20px; font-family: JetBrains Mono;",
+                    style: "color: var(--chrome-muted-foreground); font-size: 20px; font-family: JetBrains Mono;",
                     "No file selected"
                 }
             }
         };
     };
 
-    let mut element: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
+    let (text, view) = (pane_text.file, &pane_text.view);
+    let total_lines = text.len_lines();
+    let first_line = view.scroll_offset;
+    let visible = text.visible_lines(first_line, viewport_lines() + OVERSCAN);
+
+    // only the matches on screen are looked at, large files are not searched at all
+    let (line_matches, marker_lines) = match (text.large, visible.first(), visible.last()) {
+        (false, Some(&first), Some(&last)) => {
+            let range = text.rope.line_to_char(first)..text.rope.line_to_char(last + 1);
+            let matches = search.read().find_in(text, range);
+
+            (
+                text.ranges_by_line(&matches, &visible),
+                match_markers(text, &search.read()),
+            )
+        }
+        _ => (Vec::new(), Vec::new()),
+    };
+    let selection = view.selection.map(|(start, end)| {
+        (
+            text.get_caret_from_idx(min(start, end)),
+            text.get_caret_from_idx(max(start, end)),
+        )
+    });
+
+    let bracket_carets = brackets
+        .map(|(a, b)| vec![text.get_caret_from_idx(a), text.get_caret_from_idx(b)])
+        .unwrap_or_default();
+
+    let thumb_top = first_line as f64 * 100.0 / total_lines as f64;
+    let thumb_height = (viewport_lines() as f64 * 100.0 / total_lines as f64).clamp(2.0, 100.0);
+
+    let cursor = match drag() {
+        Some(Drag::Text(_)) => "default",
+        _ => "text",
+    };
+
+    let mut scroll_to_mouse = move |e: MouseEvent| {
+        let height = viewport_height();
+        if height <= 0.0 {
+            return;
+        }
+
+        tabs.write().with_pane_file(pane, |file| {
+            let line = e.element_coordinates().y / height * file.len_lines() as f64;
+            file.scroll_to((line as usize).saturating_sub(viewport_lines() / 2));
+        });
+    };
 
     rsx! {
         div {
-            onmounted: move |e| {
-                info!("mounted line: {:?}", e);
-                element.set(Some(e.data()));
+            style: "display: flex; flex: 1; position: relative; overflow: hidden; background-color: var(--editor-background);",
+            ondragover: move |e| e.prevent_default(),
+            ondrop: open_dropped_files,
+
+            onresize: move |e| {
+                if let Ok(size) = e.get_content_box_size() {
+                    viewport_height.set(size.height);
+                    viewport_width.set(size.width);
+                    viewport_lines.set((size.height / LINE_HEIGHT) as usize);
+                }
             },
 
-            style: "background-color: rgb(45, 47, 53); display: flex; overflow-y: scroll; flex: 1; flex-direction: column",
-            for (i, line) in text.chars().into_iter().enumerate() {
-
-                EditorLine {
-                    tabs: tabs,
-                    selection_start: tabs.read().get_current_file().and_then(|f| f.selection.map(|s| f.get_caret_from_idx(min(s.0, s.1)))),
-                    selection_end: tabs.read().get_current_file().and_then(|f| f.selection.map(|s| f.get_caret_from_idx(max(s.0, s.1)))),
-                    content: line,
-                    line_i: i,
-                    caret_col: caret_col,
-                    caret_line: caret_line,
-                    parent_element: element,
+            onwheel: move |e| {
+                let lines = match e.delta() {
+                    WheelDelta::Pixels(delta) => {
+                        // trackpads send many tiny deltas, keep the remainder for the next event
+                        let pixels = wheel_rest() + delta.y;
+                        wheel_rest.set(pixels % LINE_HEIGHT);
+                        (pixels / LINE_HEIGHT) as isize
+                    }
+                    WheelDelta::Lines(delta) => delta.y as isize,
+                    WheelDelta::Pages(delta) => (delta.y * viewport_lines() as f64) as isize,
+                };
+
+                tabs.write().with_pane_file(pane, |file| file.scroll_by(lines));
+            },
+
+            span {
+                style: "position: absolute; visibility: hidden; font-family: JetBrains Mono; font-size: 16px; white-space: pre;",
+                onmounted: move |e| async move {
+                    if let Ok(rect) = e.data().get_client_rect().await {
+                        char_width.set(rect.width() / WIDTH_PROBE.len() as f64);
+                    }
+                },
+                "{WIDTH_PROBE}"
+            }
+
+            div {
+                style: "display: flex; flex: 1; flex-direction: column; overflow: hidden; cursor: {cursor};",
+                onmounted: move |e| lines_element.set(Some(e.data())),
+
+                onmousedown: move |e| async move {
+                    if e.trigger_button() != Some(MouseButton::Primary) {
+                        return;
+                    }
+
+                    focus_pane(tabs, pane);
+
+                    // the editor may have moved since the last click, e.g. when a panel was resized
+                    if let Some(element) = lines_element() {
+                        if let Ok(rect) = element.get_client_rect().await {
+                            lines_rect.set((rect.origin.x, rect.origin.y, rect.height()));
+                        }
+                    }
+
+                    let point = e.client_coordinates();
+                    pointer.set((point.x, point.y));
+
+                    let Some(idx) = idx_at(point.x, point.y) else {
+                        return;
+                    };
+
+                    let now = Instant::now();
+                    let clicks = match last_click() {
+                        Some((time, count, last_idx)) if now - time < MULTI_CLICK_TIME && last_idx == idx => count % 3 + 1,
+                        _ => 1,
+                    };
+                    last_click.set(Some((now, clicks, idx)));
+
+                    tabs.write().with_pane_file(pane, |file| match clicks {
+                        2 => file.select_word_at(idx),
+                        3 => file.select_line_at(idx),
+                        _ => {
+                            let shift = e.modifiers().contains(Modifiers::SHIFT);
+                            let in_selection = file
+                                .selection_range()
+                                .is_some_and(|range| range.contains(&idx));
+
+                            // pressing on the selection picks it up instead of starting a new one
+                            if in_selection && !shift && !file.read_only {
+                                drag.set(Some(Drag::Text(file.char_idx)));
+                                return;
+                            }
+
+                            // shift extends the current selection instead of starting a new one
+                            let anchor = match (shift, file.selection) {
+                                (true, Some((start, _))) => start,
+                                (true, None) => file.char_idx,
+                                (false, _) => idx,
+                            };
+
+                            file.select_to(anchor, idx);
+                            drag.set(Some(Drag::Select(anchor)));
+                        }
+                    });
+                },
+
+                onmousemove: move |e| {
+                    let Some(current_drag) = drag() else {
+                        return;
+                    };
+
+                    // the button was released outside of the editor, a text drag is cancelled
+                    if !e.held_buttons().contains(MouseButton::Primary) {
+                        drag.set(None);
+
+                        if let Drag::Text(caret) = current_drag {
+                            tabs.write().with_pane_file(pane, |file| file.char_idx = caret);
+                        }
+                        return;
+                    }
+
+                    let point = e.client_coordinates();
+                    pointer.set((point.x, point.y));
+
+                    if let Some(idx) = idx_at(point.x, point.y) {
+                        tabs.write().with_pane_file(pane, |file| current_drag.update(file, idx));
+                    }
+                },
+
+                onmouseup: move |e| {
+                    let current_drag = drag();
+                    drag.set(None);
+
+                    let Some(Drag::Text(_)) = current_drag else {
+                        return;
+                    };
+
+                    let point = e.client_coordinates();
+                    let Some(idx) = idx_at(point.x, point.y) else {
+                        return;
+                    };
+
+                    // ctrl drops a copy and leaves the original text in place
+                    let copy = e.modifiers().contains(Modifiers::CONTROL);
+                    tabs.write().with_pane_file(pane, |file| file.move_selection_to(idx, copy));
+                },
+
+                oncontextmenu: move |e| {
+                    e.prevent_default();
+                    focus_pane(tabs, pane);
+                    let point = e.client_coordinates();
+                    menu_position.set(Some((point.x, point.y)));
+                },
+
+                for (i, &ln) in visible.iter().enumerate() {
+                    EditorLine {
+                        key: "{ln}",
+                        selection: selection,
+                        matches: line_matches.get(i).cloned().unwrap_or_default(),
+                        spans: text.highlighter.spans(&text.rope, ln..ln + 1).pop().unwrap_or_default(),
+                        wrap: pane_text.wrapped_line(ln),
+                        // finding the regions of a large file would scan all of it
+                        fold: (!text.large)
+                            .then(|| text.folding.region_end(&text.rope, ln))
+                            .flatten()
+                            .map(|_| text.folding.is_folded(&text.rope, ln)),
+                        on_toggle_fold: move |_| {
+                            focus_pane(tabs, pane);
+                            tabs.write().with_pane_file(pane, |file| file.toggle_fold(ln));
+                        },
+                        brackets: bracket_carets.iter().filter(|c| c.ln == ln).map(|c| c.col).collect::<Vec<_>>(),
+                        content: text.rope.line(ln).to_string(),
+                        line_i: ln,
+                        caret_col: caret_col,
+                        caret_line: caret_line,
+                        gutter_width: gutter_width(),
+                    }
+                }
+            }
+
+            div {
+                class: "editor-scrollbar",
+                onmousedown: scroll_to_mouse,
+                onmousemove: move |e| {
+                    if e.held_buttons().contains(MouseButton::Primary) {
+                        scroll_to_mouse(e);
+                    }
+                },
+
+                div {
+                    class: "editor-scrollbar-thumb",
+                    top: "{thumb_top}%",
+                    height: "{thumb_height}%",
+                }
+
+                for ln in marker_lines {
+                    div {
+                        class: "editor-scrollbar-marker",
+                        top: "{ln as f64 * 100.0 / total_lines as f64}%",
+                    }
                 }
             }
+
+            EditorMenu {tabs, position: menu_position}
         }
     }
 }
 
+// the first match in each stretch of lines, so the markers cost the same for any match count
+fn match_markers(text: &TextFile, search: &Search) -> Vec<usize> {
+    let total = text.len_lines();
+    let rows = min(total, MARKER_ROWS);
+
+    (0..rows)
+        .filter_map(|row| {
+            let start = text.rope.line_to_char(row * total / rows);
+            let end = text.rope.line_to_char((row + 1) * total / rows);
+
+            search
+                .first_in(text, start..end)
+                .map(|(start, _)| text.rope.char_to_line(start))
+        })
+        .collect()
+}
+
 #[component]
 pub fn EditorLine(
-    tabs: Signal<Tabs>,
-    selection_start: Option<Caret>,
-    selection_end: Option<Caret>,
+    selection: Option<(Caret, Caret)>,
+    matches: Vec<(usize, usize)>,
+    spans: Vec<Span>,
     content: String,
     line_i: usize,
     caret_col: usize,
-    caret_line: ReadOnlySignal<usize>,
-    parent_element: Signal<Option<Rc<MountedData>>>,
+    caret_line: usize,
+    gutter_width: f64,
+    brackets: Vec<usize>,
+    wrap: WrappedLine,
+    // whether a region starts on this line, and if it is folded
+    fold: Option<bool>,
+    on_toggle_fold: EventHandler<()>,
 ) -> Element {
-    let mut element: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
+    let is_selected = move |i: usize| match selection {
+        Some((start, end)) => {
+            (start.ln < line_i && line_i < end.ln)
+                || (start.ln == line_i && line_i == end.ln && start.col <= i && i < end.col)
+                || (start.ln == line_i && line_i < end.ln && start.col <= i)
+                || (start.ln < line_i && line_i == end.ln && i < end.col)
+        }
+        None => false,
+    };
 
-    let _ = use_resource(move || async move {
-        if line_i == caret_line() {
-            dbg!(element.read());
-            if let Some(ref elem) = *element.read() {
-                dbg!(parent_element.read());
-                if let Some(ref parent_elem) = *parent_element.read() {
-                    let scroll_offset = parent_elem.get_scroll_offset().await.unwrap();
-                    let scroll_size = parent_elem.get_scroll_size().await.unwrap();
-                    let parent_rect = parent_elem.get_client_rect().await.unwrap();
+    let char_kind = |i: usize| {
+        if is_selected(i) {
+            CharKind::Selected
+        } else if matches.iter().any(|(s, e)| *s <= i && i < *e) {
+            CharKind::Match
+        } else if selection.is_none() && i == caret_col && line_i == caret_line {
+            CharKind::Caret
+        } else if brackets.contains(&i) {
+            CharKind::Bracket
+        } else {
+            CharKind::Plain
+        }
+    };
 
-                    let client_rect = elem.get_client_rect().await.unwrap();
+    let token_kind = |i: usize| {
+        spans
+            .iter()
+            .find(|span| span.start <= i && i < span.end)
+            .map(|span| span.kind)
+    };
 
-                    dbg!(parent_rect.min_y(), parent_rect.max_y());
-                    dbg!(client_rect.min_y(), scroll_offset.y);
-                    dbg!(client_rect.max_y(), scroll_offset.y + scroll_size.height);
+    let chars = content
+        .chars()
+        .map(|c| if c != '\n' { c } else { ' ' })
+        .collect::<Vec<_>>();
 
-                    let height_underflown = client_rect.min_y() < parent_rect.min_y();
-                    let height_overflown = client_rect.max_y() > parent_rect.max_y();
+    // one span per run of equally styled chars instead of one per char, for every visual row
+    let rows = (0..wrap.rows()).map(|row| {
+        let mut runs: Vec<((CharKind, Option<TokenKind>), String)> = Vec::new();
 
-                    if height_underflown || height_overflown {
-                        let _ = elem.scroll_to(ScrollBehavior::Instant).await;
-                    }
-                }
+        for i in wrap.row_range(row).filter(|i| *i < chars.len()) {
+            let kind = match char_kind(i) {
+                CharKind::Selected => (CharKind::Selected, None),
+                kind => (kind, token_kind(i)),
+            };
+
+            match runs.last_mut() {
+                Some((last_kind, run)) if *last_kind == kind => run.push(chars[i]),
+                _ => runs.push((kind, chars[i].to_string())),
             }
         }
+
+        let indent = if row > 0 {
+            " ".repeat(wrap.indent)
+        } else {
+            String::new()
+        };
+        (row + 1 == wrap.rows(), indent, runs)
     });
 
     rsx! {
         div {
-            onmounted: move |e| {
-                element.set(Some(e.data()));
-            },
-
-            style: "display: flex; flex-direction: row; font-family: JetBrains Mono;
-                    font-size: 16px; white-space: pre; color: white; padding: 0 10px;",
-            background_color: if line_i == caret_line() { "rgb(65, 65, 65)" } else { "" },
+            style: "display: flex; flex-direction: row; font-family: JetBrains Mono; flex-shrink: 0;
+                    font-size: 16px; white-space: pre; color: var(--editor-foreground);",
+            padding: "0 {LINE_PADDING}px",
+            line_height: "{LINE_HEIGHT}px",
+            background_color: if line_i == caret_line { "var(--editor-caret-line)" } else { "" },
             span {
-                style: "padding-right: 10px; min-width: 40px;",
-                color: if line_i == caret_line() { "rgb(150, 111, 40)" } else { "rgb(85, 85, 85)" },
-                "{line_i}"
-            }
+                style: "flex-shrink: 0; display: flex; box-sizing: border-box;",
+                width: "{gutter_width}px",
+                padding_right: "{GUTTER_PADDING}px",
+                color: if line_i == caret_line { "var(--editor-gutter-active)" } else { "var(--editor-gutter)" },
 
-            for (i, c) in content.chars().map(|c| if c != '\n' { c} else {' '}).enumerate() {
+                span { style: "flex: 1;", "{line_i + 1}" }
+
+                // handled on mousedown so the text area below does not start a selection
                 span {
-                    onclick: move |e| {
-                        // info!("clicked on line: {:?}, col: {:?}, char: {}", line_i, i, c);
-                        let selection = e.modifiers().contains(Modifiers::SHIFT);
-                        if let Some(file) = tabs.write().get_current_file_mut() { file.set_caret_position(line_i, i, selection) }
+                    style: "width: {FOLD_MARKER_COLUMNS}ch; text-align: center; cursor: pointer;",
+                    onmousedown: move |e| {
+                        e.stop_propagation();
+                        on_toggle_fold.call(());
                     },
-
-                    style:
-                        if let (Some(start), Some(end)) = (selection_start, selection_end) {
-                            if (start.ln < line_i && line_i < end.ln ) ||
-                                (start.ln == line_i && line_i == end.ln && start.col <= i && i <= end.col) ||
-                                (start.ln == line_i && line_i < end.ln && start.col <= i) ||
-                                (start.ln < line_i && line_i == end.ln && i <= end.col) {
-                                "; background-color: lightblue;"
-                            } else {
-                                ""
-                            }
-                        } else if i == caret_col && line_i == caret_line() {
-                            "; background-color: #756336;"
-                        } else {
-                            ""
-                        },
-                    "{c}"
+                    match fold {
+                        Some(true) => "▸",
+                        Some(false) => "▾",
+                        None => "",
+                    }
                 }
             }
 
-            span {
-                style: "flex: 1;",
-                onclick: move |e| {
-                    info!("clicked on line: {:?}", line_i);
-                    let selection = e.modifiers().contains(Modifiers::SHIFT);
-                    if let Some(file) = tabs.write().get_current_file_mut() { file.set_caret_position(line_i, content.len() - 1, selection) }
+            div {
+                style: "display: flex; flex-direction: column;",
+
+                for (last_row, indent, runs) in rows {
+                    div {
+                        height: "{LINE_HEIGHT}px",
+
+                        "{indent}"
+
+                        for ((kind, token), run) in runs {
+                            span {
+                                style: "{kind.style()}{token.map_or(\"\", TokenKind::style)}",
+                                "{run}"
+                            }
+                        }
+
+                        if fold == Some(true) && last_row {
+                            span {
+                                class: "fold-placeholder",
+                                "⋯"
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -338,23 +1030,52 @@ pub fn EditorLine(
 }
 
 #[component]
-pub fn TopStatusBar(tabs: Signal<Tabs>) -> Element {
-    let path: Option<Vec<String>> = tabs()
-        .current_file
+pub fn TopStatusBar(
+    tabs: Signal<Tabs>,
+    pane: usize,
+    notice: Signal<Option<String>>,
+    elevate: Signal<Option<PathBuf>>,
+) -> Element {
+    let path: Option<Vec<String>> = tabs
+        .read()
+        .get_pane(pane)
+        .and_then(|pane| pane.current_file.as_ref())
         .map(|p| p.iter().map(|p| p.to_string_lossy().to_string()).collect());
 
+    let read_only = tabs.read().get_pane_tab(pane).map(|tab| tab.is_read_only());
+
     rsx! {
         div {
-            style: "background-color: rgb(54, 46, 46); height: 30px; display: flex;
+            style: "background-color: var(--chrome-top-bar); height: 30px; display: flex;
                     justify-content: space-between; align-items: center",
             Breadcrumbs {path},
+            if let Some(read_only) = read_only {
+                div {
+                    class: "save-button",
+                    style: "width: 40px;",
+                    title: if read_only { "Read-only, click to make it editable (Ctrl+Shift+L)" } else { "Make read-only (Ctrl+Shift+L)" },
+                    onclick: move |_| {
+                        focus_pane(tabs, pane);
+                        if let Some(tab) = tabs.write().get_current_tab_mut() {
+                            tab.set_read_only(!read_only);
+                        }
+                    },
+                    Icon {
+                        size: 18,
+                        icon: if read_only { Shape::LockClosed } else { Shape::LockOpen },
+                    }
+                }
+            }
             div {
-                style: "height: 100%; width: 1px; background-color: rgb(90, 89, 75);",
+                style: "height: 100%; width: 1px; background-color: var(--chrome-separator);",
             }
             div {
                 class: "save-button",
                 onclick: move |_| {
-                    if let Some(file) = tabs.write().get_current_file_mut() { file.save_to_file() }
+                    focus_pane(tabs, pane);
+                    if let Some(tab) = tabs.write().get_current_tab_mut() {
+                        save_tab(tab, notice, elevate);
+                    }
                 },
 
                 "Save"
@@ -365,19 +1086,28 @@ pub fn TopStatusBar(tabs: Signal<Tabs>) -> Element {
 
 #[component]
 pub fn BottomStatusBar(
-    tabs: ReadOnlySignal<Tabs>,
+    mut tabs: Signal<Tabs>,
+    pane: usize,
     caret_col: usize,
     caret_line: usize,
     char_idx: usize,
+    wrap_mode: Signal<WrapMode>,
+    wrap_column: Signal<usize>,
+    notice: Option<String>,
 ) -> Element {
-    let status = if let Some(ref f) = tabs.read().get_current_file() {
-        if let Some((start, end)) = f.selection {
-            let s = min(start, end);
-            let e = max(start, end);
-            let len = e - s;
+    let mut error_dialog_handler = use_context::<ErrorDialogHandler>();
+
+    let status = if let Some(text) = tabs.read().get_pane_text(pane) {
+        let f = text.file;
+        if let Some(notice) = notice {
+            notice
+        } else if let Some(range) = text.selection_range().filter(|_| f.large) {
+            format!("Selection: {} chars", range.len())
+        } else if let Some(range) = text.selection_range() {
+            let len = range.len();
             let words = f
                 .rope
-                .slice(s..=e)
+                .slice(range)
                 .chars()
                 .tuple_windows()
                 .filter(|(a, b)| a.is_whitespace() && !b.is_whitespace())
@@ -394,10 +1124,28 @@ pub fn BottomStatusBar(
         String::new()
     };
 
+    let language = tabs
+        .read()
+        .get_pane_tab(pane)
+        .map(|tab| match tab.file.highlighter.language {
+            Some(language) => language.to_string(),
+            None => "Plain text".to_string(),
+        })
+        .unwrap_or_default();
+
+    let indentation = tabs
+        .read()
+        .get_pane_tab(pane)
+        .map(|tab| tab.file.indentation);
+    let large = tabs
+        .read()
+        .get_pane_tab(pane)
+        .is_some_and(|tab| tab.file.large);
+
     rsx! {
         div {
-            style: "background-color: rgb(40, 42, 53); height: 30px; display: flex;
-                    justify-content: flex-end; align-items: center; color: rgb(198, 208, 235); 
+            style: "background-color: var(--chrome-status-bar); height: 30px; display: flex;
+                    justify-content: flex-end; align-items: center; color: var(--chrome-status-foreground);
                     font-family: JetBrains Mono; font-size: 14px;",
             span {
                 style: "margin-left: 10px;",
@@ -410,7 +1158,70 @@ pub fn BottomStatusBar(
 
             span {
                 style: "margin-right: 10px;",
-                "Line: {caret_line}, Col: {caret_col} | Char: {char_idx}"
+                "Line: {caret_line + 1}, Col: {caret_col + 1} | Char: {char_idx}"
+            }
+
+            span {
+                style: "margin-right: 10px; cursor: pointer;",
+                title: "Toggle word wrap (Alt+Z)",
+                onclick: move |_| wrap_mode.set(wrap_mode().next(wrap_column())),
+                if let WrapMode::Column(_) = wrap_mode() {
+                    "Wrap at"
+                } else {
+                    "{wrap_mode}"
+                }
+            }
+
+            if let WrapMode::Column(column) = wrap_mode() {
+                input {
+                    type: "number",
+                    style: "width: 55px; margin-right: 10px; font-family: JetBrains Mono; font-size: 14px;
+                            background-color: var(--chrome-control); color: var(--chrome-status-foreground); border: none; outline: none;",
+                    title: "Wrap column, from {WRAP_COLUMNS.start()} to {WRAP_COLUMNS.end()}",
+                    min: *WRAP_COLUMNS.start() as i64,
+                    max: *WRAP_COLUMNS.end() as i64,
+                    value: "{column}",
+                    // typing here must not edit the text
+                    onkeydown: move |e| e.stop_propagation(),
+                    onchange: move |e| {
+                        let Some(column) = parse_wrap_column(&e.value()) else {
+                            return;
+                        };
+
+                        wrap_mode.set(WrapMode::Column(column));
+                        wrap_column.set(column);
+                        if let Err(e) = save_wrap_column(column) {
+                            error_dialog_handler.show(format!("Failed to save the wrap column: {e}"));
+                        }
+                    },
+                }
+            }
+
+            if let Some(indentation) = indentation {
+                span {
+                    style: "margin-right: 10px; cursor: pointer;",
+                    title: "Convert indentation to {indentation.toggled()}",
+                    onclick: move |_| {
+                        focus_pane(tabs, pane);
+                        if let Some(file) = tabs.write().get_current_file_mut().filter(|f| !f.read_only) {
+                            file.convert_indentation(indentation.toggled());
+                        }
+                    },
+                    "{indentation}"
+                }
+            }
+
+            if large {
+                span {
+                    style: "margin-right: 10px;",
+                    title: "Highlighting, match highlights and word counts are off for large files",
+                    "Large file"
+                }
+            }
+
+            span {
+                style: "margin-right: 10px;",
+                "{language}"
             }
         }
     }
@@ -422,10 +1233,10 @@ pub fn Breadcrumbs(path: ReadOnlySignal<Option<Vec<String>>>) -> Element {
         return rsx! {
             div {
                 style: "display: flex; overflow-x: auto;
-                        flex: 1; flex-direction: column; color: white; justify-content: center;
+                        flex: 1; flex-direction: column; color: var(--chrome-foreground); justify-content: center;
                         width: 100%; height: 100%;",
                 div {
-                    style: "color: gray; font-size: 15px; font-family: JetBrains Mono;",
+                    style: "color: var(--chrome-muted-foreground); font-size: 15px; font-family: JetBrains Mono;",
                     "No file selected"
                 }
             }
@@ -435,7 +1246,7 @@ pub fn Breadcrumbs(path: ReadOnlySignal<Option<Vec<String>>>) -> Element {
     rsx! {
         div {
             class: "scrollbar-thin",
-            style: "display: flex; overflow-x: auto; flex: 1; color: white; align-items: center;
+            style: "display: flex; overflow-x: auto; flex: 1; color: var(--chrome-foreground); align-items: center;
                         width: 100%; height: 100%; font-family: JetBrains Mono; font-size: 12px; margin-left: 5px;",
             for (i, part) in path.iter().enumerate() {
                 span {
@@ -449,7 +1260,7 @@ pub fn Breadcrumbs(path: ReadOnlySignal<Option<Vec<String>>>) -> Element {
 
                 if i < path.len() - 1 {
                     span {
-                        style: "color: orange; margin: 0 5px;",
+                        style: "color: var(--editor-gutter-active); margin: 0 5px;",
                         ">"
                     }
                 }
@@ -0,0 +1,185 @@
+use dioxus::prelude::*;
+
+use crate::models::{
+    clipboard::ClipboardRing,
+    lines::LineCommand,
+    tabs::Tabs,
+    text::TextFile,
+    transform::{Hash, Transform},
+};
+use crate::views::dialogs::error::ErrorDialogHandler;
+
+const SECTION_STYLE: &str = "display: flex; flex-direction: column; min-width: 250px;";
+const HEADER_STYLE: &str =
+    "padding: 5px 10px; font-family: JetBrains Mono; font-size: 12px; opacity: 0.7; color: var(--chrome-foreground);";
+
+// right click menu of the editor, line commands work on the caret line or the selected lines,
+// transformations on the selection or the word under the caret
+#[component]
+pub fn EditorMenu(tabs: Signal<Tabs>, position: Signal<Option<(f64, f64)>>) -> Element {
+    let error_dialog_handler = use_context::<ErrorDialogHandler>();
+    let mut clipboard = use_context::<Signal<ClipboardRing>>();
+    let mut delimiter = use_signal(|| ",".to_string());
+    let mut button_pressed = use_signal(|| false);
+
+    let Some((x, y)) = position() else {
+        return rsx! {};
+    };
+
+    let mut run = move |command: &dyn Fn(&mut TextFile)| {
+        if let Some(file) = tabs
+            .write()
+            .get_current_file_mut()
+            .filter(|file| !file.read_only)
+        {
+            command(file);
+        }
+        position.set(None);
+    };
+
+    let transform = move |transform: Transform| {
+        let mut error_dialog_handler = error_dialog_handler.clone();
+
+        move |_| {
+            let result = tabs
+                .write()
+                .get_current_file_mut()
+                .filter(|file| !file.read_only)
+                .map(|file| file.transform_selection(transform));
+
+            if let Some(Err(e)) = result {
+                error_dialog_handler.show(format!("{transform} failed: {e}"));
+            }
+            position.set(None);
+        }
+    };
+
+    let mut copy_hash = move |hash: Hash| {
+        let digest = tabs
+            .read()
+            .get_current_file_ref()
+            .map(|file| file.hash_selection(hash));
+
+        if let Some(digest) = digest {
+            clipboard.write().copy(digest, false);
+        }
+        position.set(None);
+    };
+
+    rsx! {
+        div {
+            class: "right-click-menu",
+            tabindex: 0,
+            style: "position: fixed; top: {y}px; left: {x}px; display: flex; max-height: 80vh; overflow-y: auto;",
+
+            onmounted: move |e| async move {
+                let _ = e.data().as_ref().set_focus(true).await;
+            },
+
+            // clicks inside the menu move the focus to its input, that must not close it
+            onfocusout: move |_| {
+                if button_pressed() {
+                    button_pressed.set(false);
+                } else {
+                    position.set(None);
+                }
+            },
+            onmousedown: move |e| {
+                e.stop_propagation();
+                button_pressed.set(true);
+            },
+            onkeydown: move |e| e.stop_propagation(),
+
+            div {
+                style: SECTION_STYLE,
+                div { style: HEADER_STYLE, "Lines" }
+
+                for command in LineCommand::ALL {
+                    div {
+                        class: "option-button",
+                        onclick: move |_| run(&|file| file.run_line_command(command)),
+                        if command == LineCommand::Join {
+                            "{command} (Ctrl+J)"
+                        } else {
+                            "{command}"
+                        }
+                    }
+                }
+
+                div {
+                    style: "display: flex;",
+                    div {
+                        class: "option-button",
+                        onclick: move |_| run(&|file| file.split_lines(&delimiter.peek())),
+                        "Split at"
+                    }
+                    input {
+                        type: "text",
+                        style: "width: 60px; padding: 0 10px; font-family: JetBrains Mono; font-size: 15px;
+                                background-color: var(--chrome-control); color: var(--chrome-foreground); border: none; outline: none;",
+                        value: delimiter(),
+                        oninput: move |e| delimiter.set(e.value()),
+                    }
+                }
+
+                div {
+                    class: "option-button",
+                    onclick: move |_| run(&|file| file.delete_lines()),
+                    "Delete line (Ctrl+Shift+K)"
+                }
+                div {
+                    class: "option-button",
+                    onclick: move |_| run(&|file| file.insert_line(false)),
+                    "Insert line above (Ctrl+Shift+Enter)"
+                }
+                div {
+                    class: "option-button",
+                    onclick: move |_| run(&|file| file.insert_line(true)),
+                    "Insert line below (Ctrl+Enter)"
+                }
+                div {
+                    class: "option-button",
+                    onclick: move |_| run(&|file| file.transpose_chars()),
+                    "Transpose characters (Ctrl+T)"
+                }
+                div {
+                    class: "option-button",
+                    onclick: move |_| run(&|file| file.transpose_words()),
+                    "Transpose words (Alt+T)"
+                }
+            }
+
+            div {
+                style: SECTION_STYLE,
+                div { style: HEADER_STYLE, "Transform" }
+
+                for t in Transform::ALL {
+                    div {
+                        class: "option-button",
+                        onclick: transform(t),
+                        "{t}"
+                    }
+                }
+
+                div {
+                    class: "option-button",
+                    onclick: move |_| run(&|file| file.increment_number(1)),
+                    "Increment number (Ctrl+Alt+Up)"
+                }
+                div {
+                    class: "option-button",
+                    onclick: move |_| run(&|file| file.increment_number(-1)),
+                    "Decrement number (Ctrl+Alt+Down)"
+                }
+
+                for hash in Hash::ALL {
+                    div {
+                        class: "option-button",
+                        onclick: move |_| copy_hash(hash),
+                        "Copy {hash} hash"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,30 @@
 use crate::models::file_system::{Directory, FileSystem, FileSystemItem};
+use crate::models::tabs::Tabs;
+use crate::views::dialogs::error::ErrorDialogHandler;
 use crate::views::file_explorer::{context_menu::RightClickMenuHandler, file::File};
 use dioxus::prelude::*;
 use std::path::PathBuf;
 
+// moves the item being dragged in the explorer into `directory`, open tabs follow it
+pub fn drop_into(directory: PathBuf) -> impl FnMut(DragEvent) {
+    let mut file_system = use_context::<Signal<FileSystem>>();
+    let mut tabs = use_context::<Signal<Tabs>>();
+    let mut error_dialog_handler = use_context::<ErrorDialogHandler>();
+
+    move |event: DragEvent| {
+        event.prevent_default();
+
+        let Some(path) = file_system.write().take_dragged() else {
+            return;
+        };
+
+        match file_system.write().move_item(&path, &directory) {
+            Ok(new_path) => tabs.write().rename_path(&path, &new_path),
+            Err(error) => error_dialog_handler.show(error.to_string()),
+        }
+    }
+}
+
 #[component]
 pub fn DirectoryComponent(path: PathBuf) -> Element {
     let mut right_click_menu_handler = use_context::<Signal<RightClickMenuHandler>>();
@@ -50,6 +72,16 @@ pub fn DirectoryComponent(path: PathBuf) -> Element {
         }
     };
 
+    let start_drag = {
+        let path = path.clone();
+
+        move |_| {
+            file_system.write().start_drag(&path);
+        }
+    };
+
+    let drop = drop_into(path.clone());
+
     rsx!(
         div {
             class: "item-text",
@@ -63,15 +95,19 @@ pub fn DirectoryComponent(path: PathBuf) -> Element {
 
                 div {
                     class: item_text_class,
+                    draggable: true,
                     onclick: change_focus,
                     oncontextmenu: open_right_click_menu,
+                    ondragstart: start_drag,
+                    ondragover: move |event| event.prevent_default(),
+                    ondrop: drop,
 
                     " { file_system.read().get_directory_name(&path) } "
                 }
             }
 
             div {
-                style: " border-left: 1px solid rgb(131, 58, 58); margin-left: 10px; ",
+                style: " border-left: 1px solid var(--chrome-tree-guide); margin-left: 10px; ",
                 if file_system.read().directory_is_opened(&path) {
                     for item in file_system.read().get_directory_children(&path).iter() {
                         if let FileSystemItem::Directory(dir) = item {
@@ -1,6 +1,6 @@
 use crate::models::file_system::{FileSystem, FileSystemItem};
 use crate::models::tabs::Tabs;
-use crate::views::file_explorer::context_menu::RightClickMenuHandler;
+use crate::views::file_explorer::{context_menu::RightClickMenuHandler, directory::drop_into};
 use dioxus::prelude::*;
 use dioxus_heroicons::{mini::Shape, Icon};
 use std::path::PathBuf;
@@ -41,6 +41,17 @@ pub fn File(file: PathBuf) -> Element {
         }
     };
 
+    let start_drag = {
+        let file = file.clone();
+
+        move |_| {
+            file_system.write().start_drag(&file);
+        }
+    };
+
+    // dropping onto a file moves the dragged item next to it
+    let drop = drop_into(file.parent().map(PathBuf::from).unwrap_or_default());
+
     rsx!(
         div {
             class: if file_system.read().is_focused(&file) {
@@ -49,10 +60,14 @@ pub fn File(file: PathBuf) -> Element {
                 "file-text"
             },
             style: "",
+            draggable: true,
 
             ondoubleclick: open,
             onclick: focus,
             oncontextmenu: open_right_click_menu,
+            ondragstart: start_drag,
+            ondragover: move |event| event.prevent_default(),
+            ondrop: drop,
 
             Icon {
                 icon: Shape::Document,
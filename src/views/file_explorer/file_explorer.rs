@@ -33,22 +33,22 @@ pub fn FileExplorer(tabs: Signal<Tabs>) -> Element {
 
             div {
                 style: "display: flex; width: 100%; align-items: center;
-                        padding-left: 10px; height: 35px; background-color: rgb(26, 28, 48);",
+                        padding-left: 10px; height: 35px; background-color: var(--chrome-panel-header);",
                 "FileExplorer"
             }
 
             div {
-                style: "height: 1px; background-color: rgb(59, 63, 105); width: 100%;",
+                style: "height: 1px; background-color: var(--chrome-separator); width: 100%;",
             }
 
             div {
-                style: "width: 100%; height: 100%; display: flex; overflow-y: auto; color: white;
+                style: "width: 100%; height: 100%; display: flex; overflow-y: auto; color: var(--chrome-panel-foreground);
                         font-family: JetBrains Mono; font-size: 14px;",
                 if let Some(directory) = file_system.read().get_root() {
                     DirectoryComponent { path: directory.get_path().clone() }
                 } else {
                     div {
-                        style: "width: 100%; height: 100%; display: flex; justify-content: center; align-items: center; color: rgb(175, 175, 175)",
+                        style: "width: 100%; height: 100%; display: flex; justify-content: center; align-items: center; color: var(--chrome-muted-foreground)",
                         "No directory selected"
                     }
                 }
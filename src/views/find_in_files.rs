@@ -0,0 +1,490 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use dioxus::prelude::*;
+use itertools::Itertools;
+
+use crate::models::{
+    file_system::FileSystem,
+    tabs::Tabs,
+    workspace_search::{
+        replace_in_buffer, replace_in_file, replace_preview, DiffLine, FileMatches, LineMatch,
+        WorkspaceQuery,
+    },
+};
+use crate::views::dialogs::error::ErrorDialogHandler;
+
+static INPUT_STYLE: &str =
+    "width: 100%; padding: 7px 10px; font-family: JetBrains Mono; font-size: 14px;
+    background-color: var(--chrome-control); color: var(--chrome-foreground); border: none; outline: none; margin-top: 3px;";
+
+#[component]
+pub fn FindInFiles(tabs: Signal<Tabs>) -> Element {
+    let file_system = use_context::<Signal<FileSystem>>();
+    let mut error_dialog_handler = use_context::<ErrorDialogHandler>();
+
+    let mut needle = use_signal(String::new);
+    let mut include = use_signal(String::new);
+    let mut exclude = use_signal(String::new);
+    let mut case_sensitive = use_signal(|| false);
+
+    let mut replace_mode = use_signal(|| false);
+    let mut replacement = use_signal(String::new);
+    let mut excluded: Signal<HashSet<(PathBuf, usize)>> = use_signal(HashSet::new);
+    let mut summary: Signal<Option<String>> = use_signal(|| None);
+
+    let mut results: Signal<Vec<FileMatches>> = use_signal(Vec::new);
+    let mut job: Signal<Option<Arc<AtomicBool>>> = use_signal(|| None);
+
+    let mut cancel_search = move || {
+        if let Some(cancel) = job.peek().as_ref() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        job.set(None);
+    };
+
+    let mut run_search = move || {
+        cancel_search();
+        results.set(Vec::new());
+        excluded.write().clear();
+        summary.set(None);
+
+        let Some(root) = file_system.read().get_root().map(|r| r.get_path().clone()) else {
+            return;
+        };
+
+        let query = WorkspaceQuery::new(needle(), case_sensitive(), &include(), &exclude());
+        let buffers = tabs.read().unsaved_buffers();
+        let cancel = Arc::new(AtomicBool::new(false));
+        job.set(Some(cancel.clone()));
+
+        spawn(async move {
+            let job_cancel = cancel.clone();
+            let res =
+                tokio::task::spawn_blocking(move || query.run(&root, &buffers, &job_cancel)).await;
+
+            // a newer search or the cancel button already took over
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+
+            if let Ok(res) = res {
+                results.set(res);
+            }
+            job.set(None);
+        });
+    };
+
+    let mut apply_replace = move || {
+        let replacement = replacement();
+        let mut replaced = 0;
+        let mut replaced_files = 0;
+        let mut skipped = Vec::new();
+
+        for file in results.peek().iter() {
+            let selected = file
+                .matches
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !excluded.peek().contains(&(file.path.clone(), *i)))
+                .map(|(_, m)| m.clone())
+                .collect::<Vec<_>>();
+
+            if selected.is_empty() {
+                continue;
+            }
+
+            // opened files go through their history so the replace can be undone, the ones that
+            // must not change, or are not loaded yet, are left alone on disk too
+            let count = match tabs.write().get_tab_mut(&file.path) {
+                Some(tab) if !tab.accepts_replace() => {
+                    skipped.push(file.path.clone());
+                    continue;
+                }
+                Some(tab) => replace_in_buffer(&mut tab.file, &selected, &replacement),
+                None => match replace_in_file(&file.path, &selected, &replacement) {
+                    Ok(count) => count,
+                    Err(e) => {
+                        error_dialog_handler.show(format!("{}: {}", file.path.display(), e));
+                        0
+                    }
+                },
+            };
+
+            if count > 0 {
+                replaced += count;
+                replaced_files += 1;
+            }
+        }
+
+        results.set(Vec::new());
+        excluded.write().clear();
+
+        let mut notice = format!("Replaced {replaced} occurrences in {replaced_files} files");
+        if !skipped.is_empty() {
+            notice.push_str(&format!(
+                ", skipped read-only or unloaded files: {}",
+                skipped.iter().map(|path| path.display()).join(", ")
+            ));
+        }
+        summary.set(Some(notice));
+    };
+
+    let status = use_memo(move || {
+        let match_count = results
+            .read()
+            .iter()
+            .map(|f| f.matches.len())
+            .sum::<usize>();
+
+        match (job.read().is_some(), results.read().len()) {
+            (true, _) => "Searching...".to_string(),
+            (false, 0) => summary.read().clone().unwrap_or_default(),
+            (false, files) => format!("{match_count} results in {files} files"),
+        }
+    });
+
+    rsx! {
+        div {
+            style: "flex: 1; background-color: var(--chrome-panel); display: flex; flex-direction: column; overflow: hidden;
+                    font-family: JetBrains Mono; font-size: 20px; color: var(--chrome-panel-foreground); align-items: center;",
+
+            div {
+                style: "display: flex; width: 100%; justify-content: center; align-items: center; padding: 10px;",
+                "Find in files"
+            }
+
+            input {
+                type: "text",
+                style: INPUT_STYLE,
+                placeholder: "Search",
+                value: needle(),
+                oninput: move |e| needle.set(e.value()),
+                onkeydown: move |e| {
+                    if e.key() == Key::Enter {
+                        run_search();
+                    }
+                }
+            }
+
+            input {
+                type: "text",
+                style: INPUT_STYLE,
+                placeholder: "Files to include (e.g. *.rs, src/**)",
+                value: include(),
+                oninput: move |e| include.set(e.value()),
+            }
+
+            input {
+                type: "text",
+                style: INPUT_STYLE,
+                placeholder: "Files to exclude",
+                value: exclude(),
+                oninput: move |e| exclude.set(e.value()),
+            }
+
+            if replace_mode() {
+                input {
+                    type: "text",
+                    style: INPUT_STYLE,
+                    placeholder: "Replace",
+                    value: replacement(),
+                    oninput: move |e| replacement.set(e.value()),
+                }
+            }
+
+            div {
+                style: "display: flex; flex-direction: row; width: 100%; justify-content: center; margin: 5px 0;",
+
+                input {
+                    class: "custom-checkbox",
+                    type: "checkbox",
+                    checked: case_sensitive(),
+                    onchange: move |e| case_sensitive.set(e.checked()),
+                }
+
+                label {
+                    style: "margin-left: 5px; font-weight: 100; font-size: 17px; text-align: center;",
+                    "Case sensitive"
+                }
+
+                input {
+                    class: "custom-checkbox",
+                    style: "margin-left: 15px;",
+                    type: "checkbox",
+                    checked: replace_mode(),
+                    onchange: move |e| replace_mode.set(e.checked()),
+                }
+
+                label {
+                    style: "margin-left: 5px; font-weight: 100; font-size: 17px; text-align: center;",
+                    "Replace"
+                }
+            }
+
+            div {
+                style: "display: flex; flex-direction: row; width: 100%;",
+                button {
+                    class: "custom-button",
+                    onclick: move |_| run_search(),
+                    "Search"
+                }
+                div {
+                    style: "width: 1px; background-color: var(--chrome-separator); height: 100%;",
+                }
+                button {
+                    class: "custom-button",
+                    disabled: job.read().is_none(),
+                    onclick: move |_| cancel_search(),
+                    "Cancel"
+                }
+                if replace_mode() {
+                    div {
+                        style: "width: 1px; background-color: var(--chrome-separator); height: 100%;",
+                    }
+                    button {
+                        class: "custom-button",
+                        disabled: results.read().is_empty(),
+                        onclick: move |_| apply_replace(),
+                        "Replace all"
+                    }
+                }
+            }
+
+            div {
+                style: "display: flex; width: 100%; justify-content: center; font-size: 14px; min-height: 20px; margin: 5px 0;",
+                "{status}"
+            }
+
+            div {
+                style: "display: flex; flex-direction: column; width: 100%; overflow-y: auto; flex: 1; font-size: 13px;",
+                for file in results.read().iter() {
+                    FileResults {
+                        tabs,
+                        path: file.path.clone(),
+                        matches: file.matches.clone(),
+                        replacement: if replace_mode() { Some(replacement()) } else { None },
+                        excluded,
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn FileResults(
+    tabs: Signal<Tabs>,
+    path: PathBuf,
+    matches: Vec<LineMatch>,
+    replacement: Option<String>,
+    excluded: Signal<HashSet<(PathBuf, usize)>>,
+) -> Element {
+    let mut collapsed = use_signal(|| false);
+    let mut show_diff = use_signal(|| false);
+    let file_system = use_context::<Signal<FileSystem>>();
+
+    let file_name = path
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let parent = file_system
+        .read()
+        .get_root()
+        .and_then(|root| path.parent()?.strip_prefix(root.get_path()).ok())
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let match_count = matches.len();
+    let file_included = !(0..match_count).any(|i| excluded.read().contains(&(path.clone(), i)));
+
+    let toggle_file = {
+        let path = path.clone();
+
+        move |e: FormEvent| {
+            let mut excluded = excluded.write();
+
+            for i in 0..match_count {
+                match e.checked() {
+                    true => excluded.remove(&(path.clone(), i)),
+                    false => excluded.insert((path.clone(), i)),
+                };
+            }
+        }
+    };
+
+    // what the file becomes with the selected matches replaced, from the opened buffer if any
+    let diff = match (&replacement, show_diff()) {
+        (Some(replacement), true) => {
+            let selected = matches
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !excluded.read().contains(&(path.clone(), *i)))
+                .map(|(_, m)| m.clone())
+                .collect::<Vec<_>>();
+
+            let content = tabs
+                .read()
+                .opened_tabs
+                .iter()
+                .find(|tab| tab.file.path == path && tab.hex.is_none())
+                .map(|tab| tab.file.to_string())
+                .or_else(|| fs::read_to_string(&path).ok())
+                .unwrap_or_default();
+
+            replace_preview(&content, &selected, replacement)
+        }
+        _ => Vec::new(),
+    };
+
+    rsx! {
+        div {
+            class: "search-result-file",
+            onclick: move |_| collapsed.toggle(),
+            if replacement.is_some() {
+                input {
+                    class: "custom-checkbox",
+                    style: "margin-right: 5px; flex-shrink: 0;",
+                    type: "checkbox",
+                    checked: file_included,
+                    onclick: move |e| e.stop_propagation(),
+                    onchange: toggle_file,
+                }
+            }
+            span { if collapsed() { "> " } else { "v " } }
+            span { style: "font-weight: bold;", "{file_name}" }
+            span { style: "margin-left: 8px; color: var(--chrome-muted-foreground);", "{parent}" }
+            span { style: "margin-left: auto; margin-right: 8px; color: var(--chrome-muted-foreground);", "{match_count}" }
+            if replacement.is_some() {
+                button {
+                    class: "search-result-diff-toggle",
+                    title: "Preview the replaced file",
+                    onclick: move |e| {
+                        e.stop_propagation();
+                        show_diff.toggle();
+                    },
+                    if show_diff() { "Hide diff" } else { "Diff" }
+                }
+            }
+        }
+
+        if show_diff() && !diff.is_empty() {
+            div {
+                class: "search-result-diff",
+                for line in diff {
+                    DiffRow { line }
+                }
+            }
+        }
+
+        if !collapsed() {
+            for (index, line_match) in matches.iter().enumerate() {
+                ResultLine {
+                    tabs,
+                    path: path.clone(),
+                    index,
+                    line_match: line_match.clone(),
+                    replacement: replacement.clone(),
+                    excluded,
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ResultLine(
+    tabs: Signal<Tabs>,
+    path: PathBuf,
+    index: usize,
+    line_match: LineMatch,
+    replacement: Option<String>,
+    excluded: Signal<HashSet<(PathBuf, usize)>>,
+) -> Element {
+    let before = line_match
+        .preview
+        .chars()
+        .take(line_match.preview_col)
+        .collect::<String>();
+    let after = line_match
+        .preview
+        .chars()
+        .skip(line_match.preview_col + line_match.len())
+        .collect::<String>();
+
+    let ln = line_match.ln + 1;
+    let found = line_match.text.clone();
+    let included = !excluded.read().contains(&(path.clone(), index));
+
+    let toggle_match = {
+        let path = path.clone();
+
+        move |e: FormEvent| {
+            match e.checked() {
+                true => excluded.write().remove(&(path.clone(), index)),
+                false => excluded.write().insert((path.clone(), index)),
+            };
+        }
+    };
+
+    rsx! {
+        div {
+            class: "search-result-line",
+            title: "{path.to_string_lossy()}:{ln}",
+            onclick: move |_| {
+                let mut tabs = tabs.write();
+                tabs.open_tab(path.clone());
+
+                if let Some(file) = tabs.get_current_file_mut() {
+                    file.set_caret_position(line_match.ln, line_match.col, false);
+                    file.select_range(file.char_idx..file.char_idx + line_match.len());
+                }
+            },
+            if replacement.is_some() {
+                input {
+                    class: "custom-checkbox",
+                    style: "margin-right: 5px; flex-shrink: 0;",
+                    type: "checkbox",
+                    checked: included,
+                    onclick: move |e| e.stop_propagation(),
+                    onchange: toggle_match,
+                }
+            }
+            span { style: "color: var(--editor-gutter); min-width: 40px;", "{ln}" }
+            span { "{before.trim_start()}" }
+            if let Some(replacement) = replacement {
+                span { class: "search-result-removed", "{found}" }
+                span { class: "search-result-added", "{replacement}" }
+            } else {
+                span { style: "background-color: var(--editor-search-match);", "{found}" }
+            }
+            span { "{after}" }
+        }
+    }
+}
+
+#[component]
+fn DiffRow(line: DiffLine) -> Element {
+    let (class, ln, text) = match line {
+        DiffLine::Context(ln, text) => ("", format!("{}", ln + 1), text),
+        DiffLine::Removed(ln, text) => ("search-diff-removed", format!("-{}", ln + 1), text),
+        DiffLine::Added(ln, text) => ("search-diff-added", format!("+{}", ln + 1), text),
+        DiffLine::Skipped => ("", String::new(), "...".to_string()),
+    };
+
+    rsx! {
+        div {
+            class: "search-diff-line {class}",
+            span { style: "color: var(--editor-gutter); min-width: 50px;", "{ln}" }
+            span { "{text}" }
+        }
+    }
+}
@@ -1,17 +1,39 @@
 use dioxus::prelude::*;
 
-use crate::models::tabs::Tabs;
+use crate::models::{search::Search, tabs::Tabs};
 
 #[component]
 pub fn FindReplace(tabs: Signal<Tabs>) -> Element {
-    let mut needle = use_signal(|| "".to_string());
+    let mut search = use_context::<Signal<Search>>();
     let mut search_idx: Signal<Option<usize>> = use_signal(|| None);
-    let mut case_sensitive = use_signal(|| false);
+
+    let needle = use_memo(move || search.read().needle.clone());
+    let case_sensitive = use_memo(move || search.read().case_sensitive);
+
+    // the editor searched the same file already, this comes from the file's match cache
+    let matches = use_memo(move || match tabs.read().get_current_file_ref() {
+        Some(file) => search.read().find_all(file),
+        None => Vec::new(),
+    });
+
+    let match_count = use_memo(move || {
+        let selection = tabs.read().get_current_file_ref().and_then(|f| f.selection);
+
+        match (
+            matches.read().len(),
+            Search::current_match(&matches.read(), selection),
+        ) {
+            (0, _) if needle.read().is_empty() => String::new(),
+            (0, _) => "No matches".to_string(),
+            (total, Some(current)) => format!("{} of {}", current + 1, total),
+            (total, None) => format!("{} matches", total),
+        }
+    });
 
     rsx! {
         div {
-            style: "flex: 1; background-color: rgb(59, 53, 45); display: flex; flex-direction: column;
-                    font-family: JetBrains Mono; font-size: 20px; color: white; align-items: center;",
+            style: "flex: 1; background-color: var(--chrome-panel); display: flex; flex-direction: column;
+                    font-family: JetBrains Mono; font-size: 20px; color: var(--chrome-panel-foreground); align-items: center;",
 
             div {
                 style: "display: flex; width: 100%; justify-content: center; align-items: center; padding: 10px;
@@ -22,13 +44,18 @@ pub fn FindReplace(tabs: Signal<Tabs>) -> Element {
             input {
                 type: "text",
                 style: "width: 100%; padding: 10px; font-family: JetBrains Mono;
-                        font-size: 20px; background-color: #1e1e1e; color: white; border: none; outline: none;",
+                        font-size: 20px; background-color: var(--chrome-control); color: var(--chrome-foreground); border: none; outline: none;",
                 value: needle(),
                 oninput: move |e| {
-                    needle.set(e.value());
+                    search.write().needle = e.value();
                 }
             }
 
+            div {
+                style: "display: flex; width: 100%; justify-content: center; font-size: 15px; min-height: 20px; margin: 5px 0;",
+                "{match_count}"
+            }
+
             div {
                 style: "display: flex; flex-direction: row; width: 100%; justify-content: center;",
 
@@ -37,7 +64,7 @@ pub fn FindReplace(tabs: Signal<Tabs>) -> Element {
                     type: "checkbox",
                     checked: case_sensitive(),
                     onchange: move |e| {
-                        case_sensitive.set(e.checked());
+                        search.write().case_sensitive = e.checked();
                     }
                 }
 
@@ -68,7 +95,7 @@ pub fn FindReplace(tabs: Signal<Tabs>) -> Element {
                     "Get next"
                 }
                 div {
-                    style: "width: 1px; background-color: rgba(73, 173, 255, 0.15); height: 100%;",
+                    style: "width: 1px; background-color: var(--chrome-separator); height: 100%;",
                 }
 
                 button {
@@ -0,0 +1,271 @@
+use std::path::PathBuf;
+
+use dioxus::{html::geometry::WheelDelta, prelude::*};
+
+use crate::models::{
+    hex::{parse_pattern, HexFile, Pane, BYTES_PER_ROW},
+    tabs::Tabs,
+};
+use crate::views::editor::{focus_pane, save_tab, READ_ONLY_NOTICE};
+
+const ROW_HEIGHT: f64 = 22.0;
+const INPUT_STYLE: &str = "padding: 5px 10px; font-family: JetBrains Mono; font-size: 14px; background-color: var(--chrome-control);
+                           color: var(--chrome-foreground); border: none; outline: none; flex: 1;";
+
+// binary files are shown as offsets, hex bytes and their ascii side by side
+#[component]
+pub fn HexView(
+    mut tabs: Signal<Tabs>,
+    pane: usize,
+    mut notice: Signal<Option<String>>,
+    elevate: Signal<Option<PathBuf>>,
+) -> Element {
+    let mut visible_rows = use_signal(|| 0);
+    let mut needle = use_signal(String::new);
+
+    let mut with_hex = move |edit: &mut dyn FnMut(&mut HexFile)| {
+        focus_pane(tabs, pane);
+        if let Some(hex) = tabs
+            .write()
+            .get_current_tab_mut()
+            .and_then(|tab| tab.hex.as_mut())
+        {
+            edit(hex);
+            hex.scroll_to_cursor(*visible_rows.peek());
+        }
+    };
+
+    let mut edit = move |change: &mut dyn FnMut(&mut HexFile)| {
+        with_hex(&mut |hex| match hex.read_only {
+            true => notice.set(Some(READ_ONLY_NOTICE.to_string())),
+            false => change(hex),
+        })
+    };
+
+    let mut find = move |reverse: bool| match parse_pattern(&needle.peek()) {
+        Ok(pattern) => {
+            notice.set(None);
+            with_hex(&mut |hex| match hex.find(&pattern, reverse) {
+                Some(offset) => hex.move_to(offset),
+                None => notice.set(Some("No match".to_string())),
+            });
+        }
+        Err(e) => notice.set(Some(e)),
+    };
+
+    let tabs_ref = tabs.read();
+    let Some(hex) = tabs_ref.get_pane_tab(pane).and_then(|tab| tab.hex.as_ref()) else {
+        return rsx! {};
+    };
+
+    let first_row = hex.scroll_row;
+    let rows = (first_row..hex.rows().min(first_row + visible_rows() + 1))
+        .map(|row| (row, hex.row(row).to_vec()))
+        .collect::<Vec<_>>();
+    let cursor = hex.cursor;
+    let side = hex.pane;
+    let len = hex.bytes.len();
+    let status = format!(
+        "Offset: {cursor:#x} | {len} bytes{} | {}",
+        if hex.dirty { ", modified" } else { "" },
+        if side == Pane::Hex { "Hex" } else { "ASCII" }
+    );
+
+    let cell_style =
+        move |offset: usize, cell_pane: Pane| match (offset == cursor, cell_pane == side) {
+            (true, true) => "background-color: var(--editor-caret);",
+            (true, false) => "background-color: var(--editor-selection);",
+            _ => "",
+        };
+
+    rsx! {
+        div {
+            style: "display: flex; flex-direction: column; flex: 1; overflow: hidden; background-color: var(--editor-background);
+                    font-family: JetBrains Mono; font-size: 16px; color: var(--editor-foreground);",
+
+            div {
+                style: "display: flex; flex-direction: row; background-color: var(--chrome-top-bar);",
+                input {
+                    type: "text",
+                    placeholder: "Bytes like 4d 5a ?? 00, or \"text\"",
+                    style: INPUT_STYLE,
+                    value: needle(),
+                    oninput: move |e| needle.set(e.value()),
+                    onkeydown: move |e| {
+                        e.stop_propagation();
+                        if e.key() == Key::Enter {
+                            find(e.modifiers().contains(Modifiers::SHIFT));
+                        }
+                    },
+                }
+                button {
+                    class: "custom-button",
+                    style: "flex: none; padding: 5px 10px;",
+                    onclick: move |_| find(true),
+                    "Previous"
+                }
+                button {
+                    class: "custom-button",
+                    style: "flex: none; padding: 5px 10px;",
+                    onclick: move |_| find(false),
+                    "Next"
+                }
+            }
+
+            div {
+                tabindex: 0,
+                style: "flex: 1; overflow: hidden; outline: none; padding: 0 10px; white-space: pre; cursor: default;",
+
+                onmounted: move |e| async move {
+                    let _ = e.data().as_ref().set_focus(true).await;
+                },
+
+                onresize: move |e| {
+                    if let Ok(size) = e.get_content_box_size() {
+                        visible_rows.set((size.height / ROW_HEIGHT) as usize);
+                    }
+                },
+
+                onwheel: move |e| {
+                    let rows = match e.delta() {
+                        WheelDelta::Pixels(delta) => (delta.y / ROW_HEIGHT) as isize,
+                        WheelDelta::Lines(delta) => delta.y as isize,
+                        WheelDelta::Pages(delta) => (delta.y * visible_rows() as f64) as isize,
+                    };
+
+                    if let Some(hex) = tabs.write().get_pane_tab_mut(pane).and_then(|tab| tab.hex.as_mut()) {
+                        hex.scroll_row = hex.scroll_row.saturating_add_signed(rows).min(hex.rows() - 1);
+                    }
+                },
+
+                // the editor around this view must not see the keys, it would edit the empty text file
+                onkeydown: move |e| {
+                    e.stop_propagation();
+
+                    if notice.peek().is_some() {
+                        notice.set(None);
+                    }
+
+                    focus_pane(tabs, pane);
+
+                    let ctrl = e.modifiers().contains(Modifiers::CONTROL);
+                    let shift = e.modifiers().contains(Modifiers::SHIFT);
+                    let page = (visible_rows() * BYTES_PER_ROW) as isize;
+                    let row = BYTES_PER_ROW as isize;
+
+                    match (e.key(), ctrl) {
+                        (Key::Character(s), true) if s.eq_ignore_ascii_case("s") => {
+                            if let Some(tab) = tabs.write().get_current_tab_mut() {
+                                save_tab(tab, notice, elevate);
+                            }
+                        }
+                        (Key::Character(l), true) if shift && l.eq_ignore_ascii_case("l") => {
+                            if let Some(tab) = tabs.write().get_current_tab_mut() {
+                                tab.set_read_only(!tab.is_read_only());
+                            }
+                        }
+                        (Key::Tab, true) => {
+                            e.prevent_default();
+                            tabs.write().cycle_recent(shift);
+                        }
+                        (Key::F3, false) => find(shift),
+                        (Key::ArrowLeft, false) => with_hex(&mut |hex| hex.move_by(-1)),
+                        (Key::ArrowRight, false) => with_hex(&mut |hex| hex.move_by(1)),
+                        (Key::ArrowUp, false) => with_hex(&mut |hex| hex.move_by(-row)),
+                        (Key::ArrowDown, false) => with_hex(&mut |hex| hex.move_by(row)),
+                        (Key::PageUp, false) => with_hex(&mut |hex| hex.move_by(-page)),
+                        (Key::PageDown, false) => with_hex(&mut |hex| hex.move_by(page)),
+                        (Key::Home, false) => with_hex(&mut |hex| hex.move_to(hex.cursor - hex.cursor % BYTES_PER_ROW)),
+                        (Key::End, false) => with_hex(&mut |hex| {
+                            hex.move_to(hex.cursor - hex.cursor % BYTES_PER_ROW + BYTES_PER_ROW - 1)
+                        }),
+                        (Key::Home, true) => with_hex(&mut |hex| hex.move_to(0)),
+                        (Key::End, true) => with_hex(&mut |hex| hex.move_to(hex.bytes.len())),
+                        (Key::Tab, false) => {
+                            e.prevent_default();
+                            with_hex(&mut |hex| hex.toggle_pane());
+                        }
+                        (Key::Insert, false) => edit(&mut |hex| hex.insert_byte()),
+                        (Key::Delete, false) => edit(&mut |hex| hex.delete()),
+                        (Key::Backspace, false) => edit(&mut |hex| hex.backspace()),
+                        (Key::Character(s), false) => {
+                            let Some(c) = s.chars().next() else {
+                                return;
+                            };
+
+                            edit(&mut |hex| {
+                                match hex.pane {
+                                    Pane::Hex => hex.type_hex(c),
+                                    Pane::Ascii => hex.type_ascii(c),
+                                };
+                            });
+                        }
+                        _ => {}
+                    }
+                },
+
+                for (row, bytes) in rows {
+                    div {
+                        key: "{row}",
+                        style: "height: {ROW_HEIGHT}px; line-height: {ROW_HEIGHT}px; display: flex;",
+
+                        span {
+                            style: "color: var(--editor-line-number); margin-right: 20px;",
+                            "{row * BYTES_PER_ROW:08x}"
+                        }
+
+                        span {
+                            style: "margin-right: 20px;",
+                            for i in 0..BYTES_PER_ROW {
+                                span {
+                                    style: "{cell_style(row * BYTES_PER_ROW + i, Pane::Hex)}",
+                                    onmousedown: move |_| with_hex(&mut |hex| {
+                                        hex.move_to(row * BYTES_PER_ROW + i);
+                                        hex.pane = Pane::Hex;
+                                    }),
+                                    match bytes.get(i) {
+                                        Some(byte) => format!("{byte:02x}"),
+                                        // the cursor can rest after the last byte, typing there appends
+                                        None if row * BYTES_PER_ROW + i == len => "__".to_string(),
+                                        None => "  ".to_string(),
+                                    }
+                                }
+                                span {
+                                    if i == BYTES_PER_ROW / 2 - 1 { "  " } else { " " }
+                                }
+                            }
+                        }
+
+                        span {
+                            for (i, byte) in bytes.iter().enumerate() {
+                                span {
+                                    style: "{cell_style(row * BYTES_PER_ROW + i, Pane::Ascii)}",
+                                    onmousedown: move |_| with_hex(&mut |hex| {
+                                        hex.move_to(row * BYTES_PER_ROW + i);
+                                        hex.pane = Pane::Ascii;
+                                    }),
+                                    match *byte {
+                                        byte @ 0x20..=0x7e => (byte as char).to_string(),
+                                        _ => ".".to_string(),
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div {
+                style: "background-color: var(--chrome-status-bar); height: 30px; display: flex; align-items: center;
+                        color: var(--chrome-status-foreground); font-size: 14px; padding: 0 10px;",
+                span {
+                    style: "flex: 1;",
+                    if let Some(notice) = notice() {
+                        "{notice}"
+                    }
+                }
+                span { "{status}" }
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use dioxus::prelude::*;
+use tokio::sync::mpsc;
+
+use crate::models::{
+    large_file::{load_rope, FileSize, Load, LoadEvent},
+    tabs::Tabs,
+};
+
+// shown instead of the text while a large file waits for confirmation or is still loading
+#[component]
+pub fn LargeFileNotice(mut tabs: Signal<Tabs>, pane: usize, load: Load) -> Element {
+    let Some(path) = tabs
+        .read()
+        .get_pane_tab(pane)
+        .map(|tab| tab.file.path.clone())
+    else {
+        return rsx! {};
+    };
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut open = {
+        let path = path.clone();
+
+        move |size: u64| {
+            let path = path.clone();
+            tabs.write()
+                .set_load(&path, Load::Loading { loaded: 0, size });
+
+            spawn(async move {
+                let (sender, mut receiver) = mpsc::unbounded_channel();
+
+                let load_path = path.clone();
+                tokio::task::spawn_blocking(move || {
+                    let progress_sender = sender.clone();
+                    let event = match load_rope(&load_path, |loaded| {
+                        let _ = progress_sender.send(LoadEvent::Progress(loaded));
+                    }) {
+                        Ok(rope) => LoadEvent::Done(rope),
+                        Err(e) => LoadEvent::Failed(e.to_string()),
+                    };
+                    let _ = sender.send(event);
+                });
+
+                while let Some(event) = receiver.recv().await {
+                    match event {
+                        LoadEvent::Progress(loaded) => {
+                            tabs.write().set_load(&path, Load::Loading { loaded, size })
+                        }
+                        LoadEvent::Done(rope) => tabs.write().finish_load(&path, rope),
+                        LoadEvent::Failed(e) => tabs.write().set_load(&path, Load::Failed(e)),
+                    }
+                }
+            });
+        }
+    };
+
+    let close = move |_| tabs.write().close_tab_in(pane, &path);
+
+    rsx! {
+        div {
+            style: "flex: 1; background-color: var(--editor-background); display: flex; flex-direction: column;
+                    justify-content: center; align-items: center; font-family: JetBrains Mono; font-size: 16px;
+                    color: var(--editor-foreground);",
+
+            match load {
+                Load::Confirm { size } => rsx! {
+                    div {
+                        style: "margin-bottom: 15px;",
+                        "{name} is {FileSize(size)}"
+                    }
+                    div {
+                        style: "margin-bottom: 20px; font-size: 14px; opacity: 0.7;",
+                        "It opens read-only, without syntax highlighting or word counts."
+                    }
+                    div {
+                        button {
+                            class: "custom-button",
+                            style: "padding: 5px 15px; margin: 0 5px;",
+                            onclick: move |_| open(size),
+                            "Open read-only"
+                        }
+                        button {
+                            class: "custom-button",
+                            style: "padding: 5px 15px; margin: 0 5px;",
+                            onclick: close,
+                            "Cancel"
+                        }
+                    }
+                },
+                Load::Loading { loaded, size } => rsx! {
+                    div {
+                        style: "margin-bottom: 15px;",
+                        "Loading {name}... {FileSize(loaded)} of {FileSize(size)}"
+                    }
+                    div {
+                        style: "width: 300px; height: 6px; background-color: var(--chrome-control);",
+                        div {
+                            style: "height: 100%; width: {loaded * 100 / size.max(1)}%; background-color: var(--editor-caret);",
+                        }
+                    }
+                },
+                Load::Failed(e) => rsx! {
+                    div {
+                        style: "margin-bottom: 20px;",
+                        "Could not load {name}: {e}"
+                    }
+                    button {
+                        class: "custom-button",
+                        style: "padding: 5px 15px; flex: none;",
+                        onclick: close,
+                        "Close"
+                    }
+                },
+                Load::Loaded => rsx! {},
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use dioxus::prelude::*;
+
+use crate::models::{
+    macros::{play, EditorCommand, Macros, Playback},
+    tabs::Tabs,
+};
+use crate::views::dialogs::error::ErrorDialogHandler;
+
+const INPUT_STYLE: &str = "padding: 5px 10px; font-family: JetBrains Mono; font-size: 15px; background-color: var(--chrome-control);
+                           color: var(--chrome-foreground); border: none; outline: none;";
+
+#[component]
+pub fn MacrosPanel(tabs: Signal<Tabs>) -> Element {
+    let mut macros = use_context::<Signal<Macros>>();
+    let error_dialog_handler = use_context::<ErrorDialogHandler>();
+
+    let mut name = use_signal(String::new);
+    let mut key = use_signal(String::new);
+    let mut times = use_signal(|| "1".to_string());
+
+    let recording = macros.read().is_recording();
+    let last_len = macros.read().last.len();
+    let saved = macros.read().saved.clone();
+    let error = macros.read().error.clone();
+
+    let mut run = move |commands: Vec<EditorCommand>, playback: Playback| {
+        if let Some(file) = tabs.write().get_current_file_mut() {
+            play(file, &commands, playback);
+        }
+    };
+
+    let repeat = move || Playback::Times(times.peek().trim().parse().unwrap_or(1));
+
+    let save = {
+        let mut error_dialog_handler = error_dialog_handler.clone();
+
+        move |_| {
+            let macro_name = name.peek().trim().to_string();
+            if macro_name.is_empty() {
+                return;
+            }
+
+            let macro_key = key.peek().chars().next();
+            if let Err(e) = macros.write().save_last(macro_name, macro_key) {
+                error_dialog_handler.show(format!("Failed to save the macro: {e}"));
+            }
+
+            name.set(String::new());
+            key.set(String::new());
+        }
+    };
+
+    rsx! {
+        div {
+            style: "flex: 1; background-color: var(--chrome-panel); display: flex; flex-direction: column; overflow: hidden;
+                    font-family: JetBrains Mono; font-size: 20px; color: var(--chrome-panel-foreground); align-items: center;",
+
+            div {
+                style: "display: flex; width: 100%; justify-content: center; align-items: center; padding: 10px;",
+                "Macros"
+            }
+
+            div {
+                style: "display: flex; flex-direction: row; width: 100%;",
+                button {
+                    class: "custom-button",
+                    title: "Ctrl+Shift+R",
+                    onclick: move |_| macros.write().toggle_recording(),
+                    if recording { "Stop recording" } else { "Record" }
+                }
+                button {
+                    class: "custom-button",
+                    title: "Ctrl+Shift+P plays it once",
+                    disabled: recording || last_len == 0,
+                    onclick: move |_| run(macros.peek().last.clone(), repeat()),
+                    "Play"
+                }
+                button {
+                    class: "custom-button",
+                    disabled: recording || last_len == 0,
+                    onclick: move |_| run(macros.peek().last.clone(), Playback::ToEnd),
+                    "To end"
+                }
+            }
+
+            div {
+                style: "display: flex; width: 100%; padding: 5px 10px; font-size: 12px; opacity: 0.7; align-items: center;",
+                span {
+                    style: "flex: 1;",
+                    if recording { "Recording..." } else { "Last recording: {last_len} commands" }
+                }
+                "Repeat"
+                input {
+                    type: "number",
+                    min: "1",
+                    style: "{INPUT_STYLE} width: 60px; margin-left: 5px;",
+                    value: times(),
+                    oninput: move |e| times.set(e.value()),
+                }
+            }
+
+            div {
+                style: "display: flex; flex-direction: row; width: 100%;",
+                input {
+                    type: "text",
+                    placeholder: "Name",
+                    style: "{INPUT_STYLE} flex: 1; min-width: 0;",
+                    value: name(),
+                    oninput: move |e| name.set(e.value()),
+                }
+                input {
+                    type: "text",
+                    placeholder: "Key",
+                    title: "Played with Ctrl+Alt and this key",
+                    maxlength: 1,
+                    style: "{INPUT_STYLE} width: 50px;",
+                    value: key(),
+                    oninput: move |e| key.set(e.value()),
+                }
+                button {
+                    class: "custom-button",
+                    style: "flex: 0;",
+                    disabled: recording || last_len == 0,
+                    onclick: save,
+                    "Save"
+                }
+            }
+
+            div {
+                style: "display: flex; flex-direction: column; width: 100%; overflow-y: auto; flex: 1; font-size: 15px;",
+
+                for saved_macro in saved {
+                    div {
+                        class: "history-item",
+                        style: "display: flex; align-items: center; height: auto;",
+
+                        span {
+                            style: "flex: 1; overflow: hidden; text-overflow: ellipsis;",
+                            title: "{saved_macro.commands.len()} commands",
+                            "{saved_macro.name}"
+                        }
+
+                        if let Some(k) = saved_macro.key {
+                            span {
+                                style: "font-size: 12px; opacity: 0.7; margin-right: 5px;",
+                                "Ctrl+Alt+{k}"
+                            }
+                        }
+
+                        button {
+                            class: "custom-button",
+                            style: "flex: 0; padding: 2px 6px;",
+                            onclick: {
+                                let commands = saved_macro.commands.clone();
+                                move |_| run(commands.clone(), repeat())
+                            },
+                            "Play"
+                        }
+                        button {
+                            class: "custom-button",
+                            style: "flex: 0; padding: 2px 6px;",
+                            onclick: {
+                                let commands = saved_macro.commands.clone();
+                                move |_| run(commands.clone(), Playback::ToEnd)
+                            },
+                            "To end"
+                        }
+                        button {
+                            class: "custom-button",
+                            style: "flex: 0; padding: 2px 6px;",
+                            onclick: {
+                                let name = saved_macro.name.clone();
+                                let mut error_dialog_handler = error_dialog_handler.clone();
+
+                                move |_| {
+                                    if let Err(e) = macros.write().remove(&name) {
+                                        error_dialog_handler.show(format!("Failed to delete the macro: {e}"));
+                                    }
+                                }
+                            },
+                            "Delete"
+                        }
+                    }
+                }
+            }
+
+            if let Some(error) = error {
+                div {
+                    style: "width: 100%; padding: 5px 10px; font-size: 12px; color: var(--chrome-error); white-space: normal;",
+                    "{error}"
+                }
+            }
+        }
+    }
+}
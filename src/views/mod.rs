@@ -1,9 +1,16 @@
+pub mod clipboard_picker;
 pub mod dialogs;
 pub mod edit_history;
 pub mod editor;
+pub mod editor_menu;
 pub mod file_explorer;
+pub mod find_in_files;
 pub mod find_replace;
+pub mod hex_view;
+pub mod large_file;
+pub mod macros;
 pub mod sessionexplorer;
 pub mod side_panel;
 pub mod tabs;
 pub mod terminal;
+pub mod theme_switcher;
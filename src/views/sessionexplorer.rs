@@ -4,7 +4,7 @@ use dioxus::prelude::*;
 pub fn SessionsExplorer() -> Element {
     rsx! {
         div {
-            style: "display: flex; flex: 1; background-color: rgb(53, 40, 40);  font-family: JetBrains Mono; font-size: 20px; color: white;
+            style: "display: flex; flex: 1; background-color: var(--chrome-panel);  font-family: JetBrains Mono; font-size: 20px; color: var(--chrome-panel-foreground);
                     justify-content: center; align-items: center; width: 100%;",
                 "Sessions"
         }
@@ -1,5 +1,6 @@
 use dioxus::prelude::*;
 use dioxus_heroicons::{mini::Shape, Icon};
+use tracing::info;
 
 use crate::models::panels::ShownPanels;
 
@@ -17,13 +18,12 @@ pub fn SidePanelIcon(
             title: title,
             onclick: move |_| on_click(()),
             Icon {
-                fill: "rgb(183, 188, 255)",
                 size: 30,
                 icon: icon,
             }
         }
         div {
-            style: "height: 1px; background-color: rgb(26, 28, 48); width: 100%; align-self: center;",
+            style: "height: 1px; background-color: var(--chrome-side-panel-separator); width: 100%; align-self: center;",
         }
     }
 }
@@ -32,7 +32,7 @@ pub fn SidePanelIcon(
 pub fn SidePanel(shown_panels: ShownPanels) -> Element {
     rsx! {
         div {
-            style: "display: flex; flex-direction: column; width: 50px; background-color: rgb(15, 16, 24);",
+            style: "display: flex; flex-direction: column; width: 50px; background-color: var(--chrome-side-panel);",
             SidePanelIcon {
                 title: "FileTree".to_string(),
                 icon: Shape::Folder,
@@ -66,6 +66,16 @@ pub fn SidePanel(shown_panels: ShownPanels) -> Element {
                 },
                 selected: *shown_panels.search.read()
             },
+            SidePanelIcon {
+                title: "Find in files".to_string(),
+                icon: Shape::DocumentMagnifyingGlass,
+                on_click: move || {
+                    let val = *shown_panels.find_in_files.read();
+                    shown_panels.find_in_files.set(!val);
+                    info!("find in files clicked, shown: {}", !val);
+                },
+                selected: *shown_panels.find_in_files.read()
+            },
             SidePanelIcon {
                 title: "History".to_string(),
                 icon: Shape::Clock,
@@ -77,6 +87,26 @@ pub fn SidePanel(shown_panels: ShownPanels) -> Element {
                 },
                 selected: *shown_panels.history.read()
             },
+            SidePanelIcon {
+                title: "Themes".to_string(),
+                icon: Shape::Swatch,
+                on_click: move || {
+                    let val = *shown_panels.themes.read();
+                    shown_panels.themes.set(!val);
+                    info!("themes clicked, shown: {}", !val);
+                },
+                selected: *shown_panels.themes.read()
+            },
+            SidePanelIcon {
+                title: "Macros".to_string(),
+                icon: Shape::Film,
+                on_click: move || {
+                    let val = *shown_panels.macros.read();
+                    shown_panels.macros.set(!val);
+                    info!("macros clicked, shown: {}", !val);
+                },
+                selected: *shown_panels.macros.read()
+            },
             SidePanelIcon {
                 title: "Terminal".to_string(),
                 icon: Shape::CommandLine,
@@ -1,26 +1,196 @@
 use dioxus::prelude::*;
 use dioxus_heroicons::{mini::Shape, Icon};
 
-use crate::models::tabs::{Tab, Tabs};
+use std::path::{Path, PathBuf};
+
+use crate::models::tabs::{distinct_parents, Split, Tab, Tabs};
 
 #[component]
-pub fn EditorTabs(tabs: Signal<Tabs>) -> Element {
+pub fn EditorTabs(tabs: Signal<Tabs>, pane: usize) -> Element {
+    let mut overflow_shown = use_signal(|| false);
+    let menu: Signal<Option<(f64, f64, PathBuf)>> = use_signal(|| None);
+
+    let (shown, pinned) = tabs
+        .read()
+        .get_pane(pane)
+        .map(|pane| {
+            let shown = pane
+                .tabs
+                .iter()
+                .filter_map(|path| tabs.read().get_tab(path).cloned())
+                .collect::<Vec<_>>();
+            (shown, pane.pinned)
+        })
+        .unwrap_or_default();
+
+    let paths = shown
+        .iter()
+        .map(|tab| tab.file.path.clone())
+        .collect::<Vec<_>>();
+    let parents = distinct_parents(&paths);
+
     rsx! {
         div {
-            style: "background-color: rgb(36, 24, 32); height: 40px; display: flex; overflow-x: auto;",
+            style: "background-color: var(--chrome-tab-bar); height: 40px; display: flex; position: relative;",
+            // tabs dragged from another pane are moved into this one
+            ondragover: move |e| e.prevent_default(),
+            ondrop: move |_| tabs.write().drop_tab(pane, None),
+
+            div {
+                style: "display: flex; flex: 1; overflow-x: auto;",
+                for (i, (tab, parent)) in shown.iter().zip(parents.clone()).enumerate() {
+                    TabView { key: "{tab.file.path.display()}", file: tab.clone(), tabs, pane, index: i, pinned: i < pinned, parent, menu }
+                    div {
+                        style: "width: 1px; background-color: var(--chrome-tab-separator); height: 100%;",
+                    }
+                }
+            }
 
-            for tab in tabs.read().opened_tabs.iter() {
-                TabView { file: tab.clone(), tabs }
+            div {
+                class: "save-button",
+                style: "width: 40px;",
+                title: "All tabs",
+                onclick: move |_| overflow_shown.set(!overflow_shown()),
+                Icon {
+                    size: 18,
+                    icon: Shape::ChevronDown
+                }
+            }
+            div {
+                class: "save-button",
+                style: "width: 40px;",
+                title: "Split right (Ctrl+\\)",
+                onclick: move |_| {
+                    tabs.write().focus_pane(pane);
+                    tabs.write().split(Split::Vertical);
+                },
+                Icon {
+                    size: 18,
+                    icon: Shape::ViewColumns
+                }
+            }
+            div {
+                class: "save-button",
+                style: "width: 40px;",
+                title: "Split down (Ctrl+Alt+\\)",
+                onclick: move |_| {
+                    tabs.write().focus_pane(pane);
+                    tabs.write().split(Split::Horizontal);
+                },
+                Icon {
+                    size: 18,
+                    icon: Shape::Bars2
+                }
+            }
+
+            if overflow_shown() {
                 div {
-                    style: "width: 1px; background-color: rgba(73, 173, 255, 0.15); height: 100%;",
+                    class: "right-click-menu",
+                    tabindex: 0,
+                    style: "top: 40px; right: 80px; display: flex; flex-direction: column; max-height: 60vh; overflow-y: auto; min-width: 200px;",
+                    onmounted: move |e| async move {
+                        let _ = e.data().as_ref().set_focus(true).await;
+                    },
+                    onfocusout: move |_| overflow_shown.set(false),
+
+                    for (path, parent) in paths.into_iter().zip(parents) {
+                        div {
+                            class: "option-button",
+                            onclick: move |_| {
+                                tabs.write().select_tab(pane, path.clone());
+                                overflow_shown.set(false);
+                            },
+                            "{file_name(&path)}"
+                            if let Some(parent) = parent {
+                                span {
+                                    style: "opacity: 0.6; margin-left: 8px;",
+                                    "{parent}"
+                                }
+                            }
+                        }
+                    }
                 }
             }
+
+            TabMenu {tabs, pane, menu}
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    match path.file_name() {
+        None => "Invalid file".to_string(),
+        Some(f) => f.to_string_lossy().to_string(),
+    }
+}
+
+// right click menu of a tab
+#[component]
+fn TabMenu(
+    mut tabs: Signal<Tabs>,
+    pane: usize,
+    mut menu: Signal<Option<(f64, f64, PathBuf)>>,
+) -> Element {
+    let Some((x, y, path)) = menu() else {
+        return rsx! {};
+    };
+
+    let pinned = tabs
+        .read()
+        .get_pane(pane)
+        .is_some_and(|p| p.is_pinned(&path));
+
+    let mut run = move |command: &dyn Fn(&mut Tabs, &Path)| {
+        if let Some((_, _, path)) = menu.take() {
+            command(&mut tabs.write(), &path);
+        }
+    };
+
+    rsx! {
+        div {
+            class: "right-click-menu",
+            tabindex: 0,
+            style: "position: fixed; top: {y}px; left: {x}px; display: flex; flex-direction: column; min-width: 200px;",
+            onmounted: move |e| async move {
+                let _ = e.data().as_ref().set_focus(true).await;
+            },
+            onfocusout: move |_| menu.set(None),
+
+            div {
+                class: "option-button",
+                onclick: move |_| run(&|tabs, path| tabs.toggle_pin(pane, path)),
+                if pinned { "Unpin" } else { "Pin" }
+            }
+            div {
+                class: "option-button",
+                onclick: move |_| run(&|tabs, path| tabs.close_tab_in(pane, path)),
+                "Close"
+            }
+            div {
+                class: "option-button",
+                onclick: move |_| run(&|tabs, path| tabs.close_others(pane, path)),
+                "Close others"
+            }
+            div {
+                class: "option-button",
+                onclick: move |_| run(&|tabs, path| tabs.close_to_the_right(pane, path)),
+                "Close to the right"
+            }
         }
     }
 }
 
 #[component]
-pub fn TabView(file: ReadOnlySignal<Tab>, tabs: Signal<Tabs>) -> Element {
+pub fn TabView(
+    file: ReadOnlySignal<Tab>,
+    tabs: Signal<Tabs>,
+    pane: usize,
+    index: usize,
+    pinned: bool,
+    // shown next to the name when another tab has a file of the same name
+    parent: Option<String>,
+    mut menu: Signal<Option<(f64, f64, PathBuf)>>,
+) -> Element {
     let file_name_short = use_memo(move || match file().file.path.file_name() {
         None => "Invalid file".to_string(),
         Some(f) => match f.to_str() {
@@ -29,7 +199,11 @@ pub fn TabView(file: ReadOnlySignal<Tab>, tabs: Signal<Tabs>) -> Element {
         },
     });
 
-    let is_current = use_memo(move || tabs.read().current_file == Some(file().file.path));
+    let is_current = use_memo(move || {
+        tabs.read()
+            .get_pane(pane)
+            .is_some_and(|pane| pane.current_file.as_ref() == Some(&file().file.path))
+    });
     let exists = use_memo(move || file().file.path.exists());
 
     use_future(move || async move {
@@ -55,24 +229,56 @@ pub fn TabView(file: ReadOnlySignal<Tab>, tabs: Signal<Tabs>) -> Element {
                 true => "",
                 false => " non-exists",
             },
+            draggable: true,
+            ondragstart: move |_| tabs.write().start_drag(pane, file().file.path),
+            ondragover: move |e| e.prevent_default(),
+            ondrop: move |_| tabs.write().drop_tab(pane, Some(index)),
+            oncontextmenu: move |e| {
+                e.prevent_default();
+                let point = e.client_coordinates();
+                menu.set(Some((point.x, point.y, file().file.path)));
+            },
             onclick: move |_| {
-                tabs.write().set_current_file(file().file.path);
+                tabs.write().select_tab(pane, file().file.path);
                 // info!("current file changed to: {:?}", file().file.path);
             },
             div {
                 style: "width: 100%; height: 100%; display: flex; overflow: hidden; align-items: center; white-space: nowrap;",
+                if file().is_read_only() {
+                    div {
+                        style: "margin-right: 5px; display: flex;",
+                        title: "Read-only",
+                        Icon {
+                            size: 14,
+                            icon: Shape::LockClosed
+                        }
+                    }
+                }
                 "{file_name_short}"
+                if let Some(parent) = parent {
+                    span {
+                        style: "opacity: 0.6; margin-left: 5px;",
+                        "{parent}"
+                    }
+                }
             }
 
+            // a pinned tab is unpinned before it can be closed
             div {
                 style: "margin-left: 5px; width: 20px; height: 20px; display: flex; justify-content: center; align-items: center;",
-                onclick: move |_| {
-                    tabs.write().close_tab(file().file.path);
+                title: if pinned { "Unpin" } else { "Close" },
+                onclick: move |e| {
+                    // the tab underneath would select the file again
+                    e.stop_propagation();
+                    match pinned {
+                        true => tabs.write().toggle_pin(pane, &file().file.path),
+                        false => tabs.write().close_tab_in(pane, &file().file.path),
+                    }
                     // info!("tab closed: {:?}", file().file.path);
                 },
                 Icon {
-                    size: 20,
-                    icon: Shape::XMark
+                    size: if pinned { 16 } else { 20 },
+                    icon: if pinned { Shape::MapPin } else { Shape::XMark }
                 }
             }
         }
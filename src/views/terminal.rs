@@ -35,7 +35,7 @@ fn TerminalLauncher(terminal_states: Signal<TerminalStates>) -> Element {
         div {
             class: "terminal-launcher-input",
             input {
-                style: "margin-top: 10px; padding: 10px; outline: none; border: 1px solid #555555; width: 80%; max-width: 300px; background-color: #2e2e2e; color: #ffffff;",
+                style: "margin-top: 10px; padding: 10px; outline: none; border: 1px solid var(--terminal-sidebar-border); width: 80%; max-width: 300px; background-color: var(--chrome-control-hover); color: var(--chrome-foreground);",
                 oninput: move |e| *input_text.write() = e.value(),
                 value: input_text,
                 placeholder: "Enter command to launch terminal (cmd)",
@@ -59,8 +59,8 @@ static ICON_STYLE: &str = "margin: 5px 0; padding: 10px; border: none;
     border-radius: 3px; cursor: pointer; display: flex; align-items: center; 
     justify-content: center; height: 25px; width: 40px";
 
-static HIGHLIGHT_COLOR: &str = "#6794a1";
-static DEFAULT_COLOR: &str = "#282c34";
+static HIGHLIGHT_COLOR: &str = "var(--terminal-button-active)";
+static DEFAULT_COLOR: &str = "var(--terminal-button)";
 
 #[component]
 pub fn Terminal() -> Element {
@@ -72,7 +72,7 @@ pub fn Terminal() -> Element {
 
             div {
                 tabindex: 0,
-                style: "background-color: black; color: white; height: 100%;  display: flex; flex-direction: row; flex: 1",
+                style: "background-color: var(--terminal-background); color: var(--terminal-foreground); height: 100%;  display: flex; flex-direction: row; flex: 1",
                 for (index, _) in terminal_states().states.iter().enumerate() {
                     div {
                         style: "display: flex; flex: 1;",
@@ -96,8 +96,8 @@ pub fn Terminal() -> Element {
             }
 
             div {
-                style: "display: flex; flex-direction: column; background-color: rgb(15, 16, 24);
-                        border-left: solid rgb(50, 52, 87) 1px; padding: 10px; overflow-y: auto;",
+                style: "display: flex; flex-direction: column; background-color: var(--terminal-sidebar);
+                        border-left: solid var(--terminal-sidebar-border) 1px; padding: 10px; overflow-y: auto;",
                 for (index, terminal_state) in terminal_states().states.iter().enumerate() {
                     div {
                         style: "display: flex; position: relative;",
@@ -105,27 +105,26 @@ pub fn Terminal() -> Element {
                             style: ICON_STYLE,
                             title: terminal_state.command.clone(),
                             background_color: if terminal_states().active_index == Some(index) {HIGHLIGHT_COLOR} else {DEFAULT_COLOR},
-                            color: "white",
+                            color: if terminal_states().active_index == Some(index) {"var(--terminal-icon-active)"} else {"var(--terminal-icon)"},
                             onclick: move |_| terminal_states.write().active_index = Some(index),
                             Icon {
-                                fill: if terminal_states().active_index == Some(index) {"black"} else {"rgb(185, 185, 185)"},
                                 icon: Shape::CommandLine,
                                 size: ICON_SIZE,
                             }
                         }
 
                         div {
-                            style: "position: absolute; top: 0; right: -6px; color: white;
+                            style: "position: absolute; top: 0; right: -6px;
                                 margin: 0;  border-radius: 50%; cursor: pointer; 
                                 width: 14px; height: 14px; display: flex; justify-content: center; align-items: center;",
-                            background_color: if terminal_states().active_index == Some(index ) {"rgb(97, 97, 97)"} else {"rgb(48, 48, 48)"},
+                            background_color: if terminal_states().active_index == Some(index ) {"var(--terminal-close-button-active)"} else {"var(--terminal-close-button)"},
+                            color: if terminal_states().active_index == Some(index) {"var(--terminal-close-icon-active)"} else {"var(--terminal-close-icon)"},
 
                             onclick: move |_| {
                                 let index = index;
                                 terminal_states.write().remove(index);
                             },
                             Icon {
-                                fill: if terminal_states().active_index == Some(index) {"white"} else {"grey"},
                                 icon: Shape::XMark,
                             }
                         }
@@ -136,14 +135,13 @@ pub fn Terminal() -> Element {
                     style: ICON_STYLE,
                     title: "Launch terminal".to_string(),
                     background_color: if terminal_states().active_index.clone().is_none() {HIGHLIGHT_COLOR} else {DEFAULT_COLOR},
-                    color: "#282c34",
+                    color: if terminal_states().active_index.is_none() {"var(--terminal-icon-active)"} else {"var(--terminal-icon)"},
                     oncontextmenu: move |_| {
                         terminal_states.write().push(TerminalData::new("cmd".to_string()));
                         terminal_states.write().active_index = Some(terminal_states().len() - 1);
                     },
                     onclick: move |_| terminal_states.write().active_index = None,
                     Icon {
-                        fill: if terminal_states().active_index.clone().is_none() {"black"} else {"rgb(185, 185, 185)"},
                         icon: Shape::Plus,
                         size: ICON_SIZE,
                     }
@@ -153,7 +151,8 @@ pub fn Terminal() -> Element {
     }
 }
 
-static TERMINAL_STYLE: &str = "background-color: rgb(6, 7, 17); color: white; 
+static TERMINAL_STYLE: &str =
+    "background-color: var(--terminal-background); color: var(--terminal-foreground); 
     width: 100% ; flex: 1; overflow-y: scroll;
     margin: 0; padding: 0; border: 0; cursor: text; scroll-behavior: smooth";
 
@@ -190,7 +189,7 @@ fn ConcreteTerminal(terminal_states: Signal<TerminalStates>, index: usize) -> El
                 onmounted: move |_| async move { sleep(Duration::from_secs(5)).await; terminal_states.write().remove(index); },
                 class: "terminal-launcher-input",
                 div {
-                    style: "padding: 10px; background-color: rgba(255, 185, 185, 0.61); color: black; font-size: 15px; font-weight: 500;
+                    style: "padding: 10px; background-color: var(--terminal-error-background); color: var(--terminal-error-foreground); font-size: 15px; font-weight: 500;
                         text-align: center; font-family: 'JetBrains Mono';",
                     "Failed to launch terminal with command: \"{terminal_states().states[index].command.clone()}\""
                 }
@@ -250,13 +249,11 @@ fn ConcreteTerminal(terminal_states: Signal<TerminalStates>, index: usize) -> El
             style: "display: flex; flex-direction: column; height: 100%; width: 100%; ",
             pre {
                 style: TERMINAL_STYLE,
-                background_color: "rgb(6, 7, 17)",
-                color: "rgb(140, 255, 111)",
                 "{terminal_states().buffers[index]}"
             }
             input {
-                style: "color: white; height: 30px; width: 100%; margin: 0; padding: 0; border: 0;",
-                background_color: "rgb(19, 18, 34)",
+                style: "color: var(--terminal-input-foreground); background-color: var(--terminal-input);
+                        height: 30px; width: 100%; margin: 0; padding: 0; border: 0;",
                 value: terminal_states().input_texts[index].clone(),
 
                 oninput: move |event| {
@@ -0,0 +1,110 @@
+use dioxus::prelude::*;
+
+use crate::models::theme::{themes_dir, Theme, Themes};
+use crate::views::dialogs::error::ErrorDialogHandler;
+
+#[component]
+pub fn ThemeSwitcher() -> Element {
+    let mut themes = use_context::<Signal<Themes>>();
+    let error_dialog_handler = use_context::<ErrorDialogHandler>();
+
+    let themes_dir = themes_dir()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let errors = themes.read().errors.clone();
+
+    rsx! {
+        div {
+            style: "flex: 1; background-color: var(--chrome-panel); display: flex; flex-direction: column; overflow: hidden;
+                    font-family: JetBrains Mono; font-size: 20px; color: var(--chrome-panel-foreground); align-items: center;",
+
+            div {
+                style: "display: flex; width: 100%; justify-content: center; align-items: center; padding: 10px;",
+                "Themes"
+            }
+
+            // hovering previews a theme, leaving the list goes back to the applied one
+            div {
+                style: "display: flex; flex-direction: column; width: 100%; overflow-y: auto; flex: 1; font-size: 15px;",
+                onmouseleave: move |_| themes.write().cancel_preview(),
+
+                for theme in themes.read().available.iter().cloned() {
+                    ThemeItem {
+                        selected: theme.name == themes.read().current,
+                        theme,
+                        on_preview: move |name: String| themes.write().preview(&name),
+                        on_apply: {
+                            let mut error_dialog_handler = error_dialog_handler.clone();
+
+                            move |name: String| {
+                                if let Err(e) = themes.write().apply(&name) {
+                                    error_dialog_handler.show(format!("Failed to save the theme: {e}"));
+                                }
+                            }
+                        },
+                    }
+                }
+            }
+
+            for error in errors {
+                div {
+                    style: "width: 100%; padding: 5px 10px; font-size: 12px; color: var(--chrome-error); white-space: normal;",
+                    "{error}"
+                }
+            }
+
+            div {
+                style: "width: 100%; padding: 5px 10px; font-size: 11px; opacity: 0.7; white-space: normal;",
+                title: "{themes_dir}",
+                "Theme files: {themes_dir}"
+            }
+
+            div {
+                style: "display: flex; flex-direction: row; width: 100%;",
+                button {
+                    class: "custom-button",
+                    onclick: move |_| themes.write().reload(),
+                    "Reload"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ThemeItem(
+    theme: Theme,
+    selected: bool,
+    on_preview: EventHandler<String>,
+    on_apply: EventHandler<String>,
+) -> Element {
+    let swatches = [
+        theme.editor.background.clone(),
+        theme.editor.foreground.clone(),
+        theme.syntax.keyword.clone(),
+        theme.syntax.string.clone(),
+        theme.chrome.side_panel.clone(),
+    ];
+
+    let name = theme.name.clone();
+    let preview_name = theme.name.clone();
+
+    rsx! {
+        div {
+            class: "history-item".to_string() + if selected { " selected" } else { "" },
+            style: "display: flex; align-items: center; height: auto;",
+            onmouseenter: move |_| on_preview.call(preview_name.clone()),
+            onclick: move |_| on_apply.call(name.clone()),
+
+            span { style: "flex: 1;", "{theme.name}" }
+
+            for color in swatches {
+                span {
+                    style: "width: 14px; height: 14px; margin-left: 3px; border: 1px solid rgba(128, 128, 128, 0.5);",
+                    background_color: "{color}",
+                }
+            }
+        }
+    }
+}
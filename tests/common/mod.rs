@@ -0,0 +1,37 @@
+// fixtures shared by the test crates, each of them only uses some
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use notepad_minus_minus::models::text::TextFile;
+use tempfile::TempDir;
+
+pub fn file(content: &str) -> TextFile {
+    TextFile::from_content(PathBuf::from("test.txt"), content)
+}
+
+// files in a directory of their own, which is removed once dropped, also when an assertion fails
+pub struct TempFiles {
+    dir: TempDir,
+}
+
+impl TempFiles {
+    pub fn new() -> Self {
+        Self {
+            dir: tempfile::tempdir().unwrap(),
+        }
+    }
+
+    pub fn add(&self, name: &str, content: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
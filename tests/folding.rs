@@ -0,0 +1,97 @@
+use notepad_minus_minus::models::folding::Folding;
+
+mod common;
+
+use common::file;
+
+// nested blocks, indentation and region markers, long enough for several checkpoints
+fn source() -> String {
+    let mut source = String::new();
+
+    for i in 0..60 {
+        source.push_str(&format!("// region part {i}\nfn f{i}() {{\n"));
+        for j in 0..3 {
+            source.push_str(&format!(
+                "    if x == {j} {{\n        call(\n            {j},\n        );\n    }}\n\n"
+            ));
+        }
+        source.push_str("}\n// endregion\nplain\n    indented\n");
+    }
+
+    source
+}
+
+#[test]
+fn regions_follow_edits() {
+    let mut text = file(&source());
+    let mut seed = 11usize;
+    let mut next = |max: usize| {
+        seed = (seed * 1103515245 + 12345) % (1 << 31);
+        seed % max
+    };
+
+    for step in 0..200 {
+        let len = text.rope.len_chars();
+        text.char_idx = next(len);
+
+        match next(3) {
+            0 => {
+                let end = (text.char_idx + 1 + next(40)).min(len - 1);
+                text.select_range(text.char_idx..end);
+                text.delete(false);
+            }
+            _ => {
+                let s = [
+                    "{",
+                    "}\n",
+                    "(",
+                    ")",
+                    "\n",
+                    "    ",
+                    "// region\n",
+                    "// endregion\n",
+                    "x",
+                ][next(9)];
+                text.insert_string(s.to_string());
+            }
+        }
+
+        // only part of the file is looked at most of the time
+        let lines = text.rope.len_lines();
+        text.folding.region_end(&text.rope, next(lines));
+
+        if step % 10 == 0 {
+            let fresh = Folding::default();
+            for ln in 0..lines {
+                assert_eq!(
+                    text.folding.region_end(&text.rope, ln),
+                    fresh.region_end(&text.rope, ln),
+                    "line {ln} after step {step}"
+                );
+                assert_eq!(
+                    text.folding.region_at(&text.rope, ln),
+                    fresh.region_at(&text.rope, ln),
+                    "line {ln} after step {step}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn folds_hide_the_lines_of_their_region() {
+    let mut text = file("fn main() {\n    one();\n    two();\n}\nafter\n");
+
+    assert_eq!(text.folding.region_end(&text.rope, 0), Some(2));
+    assert_eq!(text.folding.region_at(&text.rope, 2), Some(0));
+
+    text.toggle_fold(0);
+    assert_eq!(text.folding.hidden_by(&text.rope, 1), Some(0));
+    assert_eq!(text.folding.next_visible(&text.rope, 0), 3);
+
+    // closing the block earlier shrinks the folded region
+    text.char_idx = text.rope.line_to_char(2);
+    text.insert_string("}\n".to_string());
+    assert_eq!(text.folding.region_end(&text.rope, 0), Some(1));
+    assert_eq!(text.folding.hidden_by(&text.rope, 2), None);
+}
@@ -0,0 +1,153 @@
+use std::fs;
+
+use notepad_minus_minus::models::{
+    hex::{looks_binary, parse_pattern, HexFile, Pane},
+    tabs::Tabs,
+};
+
+mod common;
+
+use common::TempFiles;
+
+#[test]
+fn binary_content_is_detected() {
+    assert!(looks_binary(b"MZ\x90\x00\x03"));
+    assert!(looks_binary(b"abc\xff\xfedef"));
+    assert!(!looks_binary("plain text, ünïcödé\n".as_bytes()));
+    // a char cut off at the end of the sniffed bytes is still text
+    assert!(!looks_binary(&"ü".as_bytes()[..1]));
+}
+
+#[test]
+fn binary_files_open_in_the_hex_view() {
+    let files = TempFiles::new();
+    let path = files.add("open", b"\x7fELF\x00\x01");
+
+    let mut tabs = Tabs::new();
+    tabs.open_tab(path.clone());
+
+    let tab = tabs.get_current_tab().unwrap();
+    assert_eq!(tab.hex.as_ref().unwrap().bytes, b"\x7fELF\x00\x01");
+
+    // the text file standing in for it never writes
+    assert!(tabs.get_current_file_mut().unwrap().save_to_file().is_err());
+    assert_eq!(fs::read(&path).unwrap(), b"\x7fELF\x00\x01");
+}
+
+#[test]
+fn hex_digits_overwrite_nibbles() {
+    let mut hex = HexFile::from_bytes(vec![0x00, 0x11]);
+
+    assert!(hex.type_hex('a'));
+    assert_eq!(hex.bytes, [0xa0, 0x11]);
+    assert_eq!(hex.cursor, 0);
+
+    assert!(hex.type_hex('B'));
+    assert_eq!(hex.bytes, [0xab, 0x11]);
+    assert_eq!(hex.cursor, 1);
+
+    assert!(!hex.type_hex('g'));
+    assert!(hex.dirty);
+}
+
+#[test]
+fn typing_past_the_end_appends() {
+    let mut hex = HexFile::from_bytes(vec![0x01]);
+    hex.move_to(10);
+    assert_eq!(hex.cursor, 1);
+
+    hex.type_hex('f');
+    hex.type_hex('f');
+
+    assert_eq!(hex.bytes, [0x01, 0xff]);
+    assert_eq!(hex.cursor, 2);
+}
+
+#[test]
+fn ascii_pane_overwrites_whole_bytes() {
+    let mut hex = HexFile::from_bytes(b"abc".to_vec());
+    hex.toggle_pane();
+    assert_eq!(hex.pane, Pane::Ascii);
+
+    assert!(hex.type_ascii('X'));
+    assert!(!hex.type_ascii('é'));
+
+    assert_eq!(hex.bytes, b"Xbc");
+}
+
+#[test]
+fn bytes_are_inserted_and_removed() {
+    let mut hex = HexFile::from_bytes(vec![1, 2, 3]);
+    hex.move_to(1);
+
+    hex.insert_byte();
+    assert_eq!(hex.bytes, [1, 0, 2, 3]);
+
+    hex.delete();
+    hex.delete();
+    assert_eq!(hex.bytes, [1, 3]);
+
+    hex.backspace();
+    assert_eq!(hex.bytes, [3]);
+    assert_eq!(hex.cursor, 0);
+}
+
+#[test]
+fn patterns_are_parsed() {
+    assert_eq!(
+        parse_pattern("4d 5a ?? 00").unwrap(),
+        [Some(0x4d), Some(0x5a), None, Some(0)]
+    );
+    assert_eq!(parse_pattern("DEADbeef").unwrap().len(), 4);
+    assert_eq!(parse_pattern("\"PK\"").unwrap(), [Some(b'P'), Some(b'K')]);
+    assert!(parse_pattern("abc").is_err());
+    assert!(parse_pattern("zz").is_err());
+    // a sign is not a digit
+    assert!(parse_pattern("+f").is_err());
+    assert!(parse_pattern("00 -1").is_err());
+}
+
+#[test]
+fn search_wraps_around_and_goes_backwards() {
+    let mut hex = HexFile::from_bytes(vec![0xca, 0xfe, 0x00, 0xca, 0x01, 0xca, 0xfe]);
+    let pattern = parse_pattern("ca ??").unwrap();
+
+    assert_eq!(hex.find(&pattern, false), Some(3));
+    hex.move_to(3);
+    assert_eq!(hex.find(&pattern, false), Some(5));
+    hex.move_to(5);
+    assert_eq!(hex.find(&pattern, false), Some(0));
+
+    assert_eq!(hex.find(&pattern, true), Some(3));
+    hex.move_to(0);
+    assert_eq!(hex.find(&pattern, true), Some(5));
+
+    assert_eq!(hex.find(&parse_pattern("ff").unwrap(), false), None);
+}
+
+#[test]
+fn save_replaces_the_file() {
+    let files = TempFiles::new();
+    let path = files.add("save", b"\x00\x01");
+    let mut hex = HexFile::load(&path).unwrap();
+
+    hex.type_hex('7');
+    hex.save(&path).unwrap();
+
+    assert!(!hex.dirty);
+    assert_eq!(fs::read(&path).unwrap(), b"\x70\x01");
+
+    let name = path.file_name().unwrap().to_string_lossy().to_string();
+    assert!(!path.with_file_name(format!(".{name}.saving")).exists());
+}
+
+#[test]
+fn failed_save_keeps_the_edits() {
+    let mut hex = HexFile::from_bytes(vec![1]);
+    hex.type_hex('2');
+
+    let files = TempFiles::new();
+    let path = files.path().join("missing").join("file.bin");
+    assert!(hex.save(&path).is_err());
+    assert!(hex.dirty);
+}
@@ -0,0 +1,57 @@
+use notepad_minus_minus::models::syntax::{
+    highlighter::Highlighter, languages::Language, Span, TokenKind,
+};
+use ropey::Rope;
+
+fn comment(len: usize) -> Vec<Span> {
+    vec![Span {
+        start: 0,
+        end: len,
+        kind: TokenKind::Comment,
+    }]
+}
+
+// what a highlighter without a cache makes of the whole text
+fn fresh(rope: &Rope) -> Vec<Vec<Span>> {
+    Highlighter::new(Some(Language::Rust)).spans(rope, 0..rope.len_lines())
+}
+
+// inserts like `TextFile` does, telling the highlighter which lines were touched
+fn insert(highlighter: &mut Highlighter, rope: &mut Rope, (ln, col): (usize, usize), s: &str) {
+    let idx = rope.line_to_char(ln) + col;
+    rope.insert(idx, s);
+    let last_line = rope.char_to_line(idx + s.chars().count());
+
+    highlighter.edit(rope, ln..=ln, ln..=last_line);
+}
+
+#[test]
+fn block_comments_opened_and_closed_by_edits_reach_the_lines_below() {
+    let mut rope = Rope::from_str("let a = 1;\nlet b = 2;\nlet c = 3;\nlet d = 4;\n");
+    let mut highlighter = Highlighter::new(Some(Language::Rust));
+    let lines = 0..rope.len_lines();
+    let before = highlighter.spans(&rope, lines.clone());
+
+    // opening a comment turns every line below it into one
+    insert(&mut highlighter, &mut rope, (1, 0), "/* ");
+    let opened = highlighter.spans(&rope, lines.clone());
+    assert_eq!(opened[0], before[0]);
+    assert_eq!(opened[1], comment(13));
+    assert_eq!(opened[2], comment(10));
+    assert_eq!(opened[3], comment(10));
+    assert_eq!(opened, fresh(&rope));
+
+    // closing it on the next line gives the last line back its tokens
+    insert(&mut highlighter, &mut rope, (2, 10), " */");
+    let closed = highlighter.spans(&rope, lines.clone());
+    assert_eq!(closed[2], comment(13));
+    assert_eq!(closed[3], before[3]);
+    assert_eq!(closed, fresh(&rope));
+
+    // a close pasted with a line break moves the lines below and ends the comment early
+    insert(&mut highlighter, &mut rope, (1, 3), "x */\n");
+    let split = highlighter.spans(&rope, 0..rope.len_lines());
+    assert_eq!(split[1], comment(7));
+    assert_eq!(split[2], before[1]);
+    assert_eq!(split, fresh(&rope));
+}
@@ -0,0 +1,130 @@
+use std::path::{Path, PathBuf};
+
+use notepad_minus_minus::models::{
+    large_file::{is_large, load_rope, FileSize, Load, LARGE_FILE_SIZE},
+    macros::EditorCommand,
+    tabs::{Tab, Tabs},
+    text::TextFile,
+};
+use ropey::Rope;
+
+mod common;
+
+use common::TempFiles;
+
+const MIB: usize = 1024 * 1024;
+
+fn load(path: &Path) -> String {
+    load_rope(path, |_| {}).unwrap().to_string()
+}
+
+#[test]
+fn crlf_split_between_chunks_becomes_one_newline() {
+    let mut content = vec![b'a'; MIB - 1];
+    content.extend_from_slice(b"\r\nb\r\n");
+    let files = TempFiles::new();
+    let path = files.add("crlf", &content);
+
+    let text = load(&path);
+
+    assert_eq!(text.len(), MIB + 2);
+    assert!(text.ends_with("a\nb\n"));
+}
+
+#[test]
+fn char_split_between_chunks_stays_whole() {
+    let mut content = vec![b'a'; MIB - 1];
+    content.extend_from_slice("ü".as_bytes());
+    let files = TempFiles::new();
+    let path = files.add("utf8", &content);
+
+    let text = load(&path);
+
+    assert!(text.ends_with("aü\n"));
+    assert!(!text.contains(char::REPLACEMENT_CHARACTER));
+}
+
+#[test]
+fn invalid_bytes_become_replacement_chars() {
+    let files = TempFiles::new();
+    let path = files.add("invalid", b"a\xffb\n");
+
+    let text = load(&path);
+
+    assert_eq!(text, "a\u{fffd}b\n");
+}
+
+#[test]
+fn progress_reaches_the_file_size() {
+    let files = TempFiles::new();
+    let path = files.add("progress", vec![b'x'; 2 * MIB + 10]);
+
+    let mut reported = Vec::new();
+    let rope = load_rope(&path, |loaded| reported.push(loaded)).unwrap();
+
+    assert_eq!(reported, [MIB as u64, 2 * MIB as u64, 2 * MIB as u64 + 10]);
+    assert_eq!(rope.len_chars(), 2 * MIB + 11);
+}
+
+#[test]
+fn sizes_are_shown_in_units() {
+    assert_eq!(FileSize(512).to_string(), "512 B");
+    assert_eq!(FileSize(1536).to_string(), "1.5 KB");
+    assert_eq!(FileSize(20 * MIB as u64).to_string(), "20.0 MB");
+}
+
+#[test]
+fn large_files_wait_for_confirmation() {
+    let files = TempFiles::new();
+    let path = files.add("confirm", vec![b'x'; LARGE_FILE_SIZE as usize + 1]);
+    assert_eq!(is_large(&path), Some(LARGE_FILE_SIZE + 1));
+
+    let mut tabs = Tabs::new();
+    tabs.open_tab(path.clone());
+
+    let tab = tabs.get_current_tab().unwrap();
+    assert_eq!(
+        tab.load,
+        Load::Confirm {
+            size: LARGE_FILE_SIZE + 1
+        }
+    );
+    assert!(tab.file.read_only);
+
+    // saving the placeholder must not empty the file
+    assert!(tabs.get_current_file_mut().unwrap().save_to_file().is_err());
+    assert_eq!(is_large(&path), Some(LARGE_FILE_SIZE + 1));
+}
+
+#[test]
+fn loaded_large_files_are_read_only() {
+    let path = PathBuf::from("big.rs");
+    let mut tabs = Tabs::new();
+    tabs.opened_tabs
+        .push(Tab::new(TextFile::from_content(path.clone(), "")));
+    tabs.set_current_file(path.clone());
+
+    tabs.finish_load(&path, Rope::from_str("fn main() {\n    body();\n}\n"));
+
+    let tab = tabs.get_current_tab().unwrap();
+    assert_eq!(tab.load, Load::Loaded);
+    assert!(tab.file.large && tab.file.read_only);
+    assert_eq!(tab.file.highlighter.language, None);
+    assert_eq!(tab.file.indentation.to_string(), "Spaces: 4");
+
+    let file = tabs.get_current_file_mut().unwrap();
+    assert!(!EditorCommand::InsertChar('x').run(file));
+    assert!(EditorCommand::MoveDown { select: false }.run(file));
+    assert_eq!(file.to_string(), "fn main() {\n    body();\n}\n");
+}
+
+#[test]
+fn large_files_are_not_folded() {
+    let rope = Rope::from_str("fn main() {\n    body();\n}\n");
+    let mut file = TextFile::from_rope(PathBuf::from("big.rs"), rope);
+
+    file.fold_all();
+    file.toggle_fold(0);
+
+    assert!(!file.folding.is_folded(&file.rope, 0));
+}
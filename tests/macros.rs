@@ -0,0 +1,70 @@
+use std::fs;
+
+use notepad_minus_minus::models::macros::{play, EditorCommand, Macros, Playback};
+
+mod common;
+
+use common::{file, TempFiles};
+
+#[test]
+fn one_undo_reverts_a_whole_macro_run() {
+    let mut text = file("");
+    for c in "typed".chars() {
+        text.insert_char(c);
+    }
+
+    // would merge with the typed chars if the run started without a break
+    let commands = [
+        EditorCommand::InsertChar('x'),
+        EditorCommand::InsertChar('y'),
+        EditorCommand::InsertText(" z".to_string()),
+    ];
+    assert_eq!(play(&mut text, &commands, Playback::Times(2)), 2);
+    assert_eq!(text.to_string(), "typedxy zxy z\n");
+
+    text.undo_event();
+    assert_eq!(text.to_string(), "typed\n");
+
+    text.undo_event();
+    assert_eq!(text.to_string(), "\n");
+
+    text.redo_event();
+    text.redo_event();
+    assert_eq!(text.to_string(), "typedxy zxy z\n");
+}
+
+#[test]
+fn typing_after_a_macro_is_merged_again() {
+    let mut text = file("");
+    play(
+        &mut text,
+        &[EditorCommand::InsertChar('x')],
+        Playback::Times(1),
+    );
+
+    text.insert_char('a');
+    text.insert_char('b');
+    text.undo_event();
+    assert_eq!(text.to_string(), "x\n");
+}
+
+#[test]
+fn unreadable_macro_files_are_moved_aside_before_saving() {
+    let files = TempFiles::new();
+    let path = files.add("macros.json", "[{ broken");
+
+    let mut macros = Macros::load_from(&path);
+    assert!(macros.saved.is_empty());
+    assert!(macros.error.is_some());
+    assert_eq!(
+        fs::read_to_string(path.with_extension("json.bak")).unwrap(),
+        "[{ broken"
+    );
+
+    macros.last = vec![EditorCommand::InsertChar('x')];
+    macros.save_last("x".to_string(), Some('x')).unwrap();
+
+    let reloaded = Macros::load_from(&path);
+    assert_eq!(reloaded.error, None);
+    assert_eq!(reloaded.saved, macros.saved);
+}
@@ -0,0 +1,144 @@
+use notepad_minus_minus::models::tabs::{Split, Tabs};
+
+mod common;
+
+use common::TempFiles;
+
+#[test]
+fn split_panes_keep_their_own_caret() {
+    let files = TempFiles::new();
+    let path = files.add("carets", "one\ntwo\nthree\n");
+
+    let mut tabs = Tabs::new();
+    tabs.open_tab(path.clone());
+    tabs.split(Split::Vertical);
+
+    assert_eq!(tabs.panes.len(), 2);
+    let (left, right) = (tabs.panes[0].id, tabs.panes[1].id);
+    assert_eq!(tabs.focused, right);
+
+    tabs.get_current_file_mut().unwrap().char_idx = 8;
+    tabs.focus_pane(left);
+    assert_eq!(tabs.get_current_file_ref().unwrap().char_idx, 0);
+
+    tabs.focus_pane(right);
+    assert_eq!(tabs.get_current_file_ref().unwrap().char_idx, 8);
+    assert_eq!(tabs.get_pane_text(left).unwrap().view.char_idx, 0);
+}
+
+#[test]
+fn edits_show_in_every_pane() {
+    let files = TempFiles::new();
+    let path = files.add("edits", "text\n");
+
+    let mut tabs = Tabs::new();
+    tabs.open_tab(path.clone());
+    tabs.split(Split::Horizontal);
+
+    tabs.get_current_file_mut().unwrap().insert_char('a');
+    let left = tabs.panes[0].id;
+
+    assert_eq!(tabs.opened_tabs.len(), 1);
+    assert_eq!(
+        tabs.get_pane_text(left).unwrap().file.to_string(),
+        "atext\n"
+    );
+
+    // the history is shared, undoing in the other pane takes the edit back
+    tabs.focus_pane(left);
+    tabs.get_current_file_mut().unwrap().undo_event();
+    assert_eq!(tabs.get_current_file_ref().unwrap().to_string(), "text\n");
+}
+
+#[test]
+fn carets_are_clamped_after_edits_elsewhere() {
+    let files = TempFiles::new();
+    let path = files.add("clamp", "a long line\n");
+
+    let mut tabs = Tabs::new();
+    tabs.open_tab(path.clone());
+    tabs.get_current_file_mut().unwrap().char_idx = 10;
+
+    tabs.split(Split::Vertical);
+    let file = tabs.get_current_file_mut().unwrap();
+    file.char_idx = 0;
+    file.rope.remove(2..12);
+
+    let left = tabs.panes[0].id;
+    tabs.focus_pane(left);
+    assert_eq!(tabs.get_current_file_ref().unwrap().char_idx, 1);
+}
+
+#[test]
+fn edits_before_another_pane_move_its_caret_along() {
+    let files = TempFiles::new();
+    let path = files.add("shift", "one\ntwo\nthree\n");
+
+    let mut tabs = Tabs::new();
+    tabs.open_tab(path.clone());
+    let left = tabs.focused;
+
+    // the left pane sits on "three" with "two" selected
+    let file = tabs.get_current_file_mut().unwrap();
+    file.char_idx = 8;
+    file.selection = Some((4, 7));
+    file.scroll_offset = 2;
+
+    tabs.split(Split::Vertical);
+    let file = tabs.get_current_file_mut().unwrap();
+    file.char_idx = 0;
+    file.selection = None;
+    file.insert_string("zero\n".to_string());
+    file.char_idx = 5;
+    file.delete(false);
+
+    tabs.focus_pane(left);
+    let file = tabs.get_current_file_ref().unwrap();
+    assert_eq!(file.to_string(), "zero\nne\ntwo\nthree\n");
+    assert_eq!(file.get_selection().as_deref(), Some("two"));
+    assert_eq!(file.rope.slice(file.char_idx..).to_string(), "three\n");
+    assert_eq!(file.scroll_offset, 3);
+}
+
+#[test]
+fn tabs_move_between_panes() {
+    let files = TempFiles::new();
+    let first = files.add("move_first", "first\n");
+    let second = files.add("move_second", "second\n");
+
+    let mut tabs = Tabs::new();
+    tabs.open_tab(first.clone());
+    tabs.open_tab(second.clone());
+    tabs.split(Split::Vertical);
+    let (left, right) = (tabs.panes[0].id, tabs.panes[1].id);
+
+    tabs.move_tab(first.clone(), left, right);
+
+    assert_eq!(tabs.panes[0].tabs, vec![second.clone()]);
+    assert_eq!(tabs.panes[1].tabs, [second.clone(), first.clone()]);
+    assert_eq!(tabs.focused, right);
+    assert_eq!(tabs.current_file, Some(first.clone()));
+}
+
+#[test]
+fn closing_the_last_tab_of_a_pane_removes_it() {
+    let files = TempFiles::new();
+    let path = files.add("close", "text\n");
+
+    let mut tabs = Tabs::new();
+    tabs.open_tab(path.clone());
+    tabs.split(Split::Vertical);
+    let right = tabs.panes[1].id;
+
+    tabs.close_tab_in(right, &path);
+
+    // the other pane still shows the file
+    assert_eq!(tabs.panes.len(), 1);
+    assert_eq!(tabs.opened_tabs.len(), 1);
+    assert_eq!(tabs.current_file, Some(path.clone()));
+
+    tabs.close_tab(path.clone());
+    assert_eq!(tabs.panes.len(), 1);
+    assert!(tabs.opened_tabs.is_empty());
+    assert_eq!(tabs.current_file, None);
+}
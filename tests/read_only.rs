@@ -0,0 +1,184 @@
+use std::{fs, path::PathBuf};
+
+use notepad_minus_minus::models::{
+    large_file::Load,
+    macros::EditorCommand,
+    permissions::{is_writable, write_private},
+    tabs::{Tab, Tabs},
+    text::TextFile,
+};
+
+mod common;
+
+use common::TempFiles;
+
+#[test]
+fn writable_files_open_editable() {
+    let files = TempFiles::new();
+    let path = files.add("writable", b"text\n");
+
+    assert!(is_writable(&path));
+    assert!(!TextFile::new(path.clone()).read_only);
+}
+
+#[test]
+fn missing_files_count_as_writable() {
+    let files = TempFiles::new();
+    let path = files.path().join("missing.txt");
+
+    assert!(is_writable(&path));
+}
+
+#[test]
+fn files_without_write_permission_are_not_writable() {
+    let files = TempFiles::new();
+    let path = files.add("locked", b"text\n");
+
+    let mut permissions = fs::metadata(&path).unwrap().permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(&path, permissions).unwrap();
+
+    assert!(!is_writable(&path));
+}
+
+// opening a fifo to check it would wait for a reader
+#[cfg(unix)]
+#[test]
+fn fifos_are_checked_without_opening_them() {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let files = TempFiles::new();
+    let path = files.path().join("fifo");
+    let c_path = CString::new(path.as_os_str().as_bytes()).unwrap();
+    assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o644) }, 0);
+
+    assert!(is_writable(&path));
+}
+
+#[test]
+fn read_only_files_refuse_edits_but_not_moves() {
+    let mut text = TextFile::from_content(PathBuf::from("test.txt"), "one\ntwo\n");
+    text.read_only = true;
+
+    assert!(!EditorCommand::InsertChar('x').run(&mut text));
+    assert!(!EditorCommand::DeleteLines.run(&mut text));
+    assert!(EditorCommand::MoveDown { select: false }.run(&mut text));
+    assert!(EditorCommand::LineEnd {
+        document: false,
+        select: true
+    }
+    .run(&mut text));
+
+    assert_eq!(text.to_string(), "one\ntwo\n");
+    assert_eq!(text.get_selection().as_deref(), Some("two"));
+}
+
+#[test]
+fn read_only_tabs_are_not_saved() {
+    let files = TempFiles::new();
+    let path = files.add("save", b"original\n");
+
+    let mut tabs = Tabs::new();
+    tabs.open_read_only(path.clone());
+
+    let tab = tabs.get_current_tab_mut().unwrap();
+    assert!(tab.is_read_only());
+
+    tab.file.rope.insert(0, "changed ");
+    assert!(tab.save().is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "original\n");
+
+    tab.set_read_only(false);
+    tab.save().unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "changed original\n");
+}
+
+#[test]
+fn placeholders_stay_read_only_until_loaded() {
+    let mut tab = Tab::new(TextFile::from_content(PathBuf::from("big.log"), ""));
+    tab.file.read_only = true;
+    tab.load = Load::Confirm { size: 1 };
+
+    tab.set_read_only(false);
+    assert!(tab.is_read_only());
+
+    tab.load = Load::Loaded;
+    tab.set_read_only(false);
+    assert!(!tab.is_read_only());
+}
+
+#[test]
+fn binary_tabs_lock_the_bytes() {
+    let files = TempFiles::new();
+    let path = files.add("binary", b"\x00\x01\x02");
+
+    let mut tabs = Tabs::new();
+    tabs.open_tab(path.clone());
+
+    let tab = tabs.get_current_tab_mut().unwrap();
+    assert!(!tab.is_read_only());
+
+    tab.set_read_only(true);
+    assert!(tab.hex.as_ref().unwrap().read_only);
+    // the text file standing in for the bytes is never editable
+    tab.set_read_only(false);
+    assert!(tab.file.read_only);
+}
+
+#[test]
+fn replace_in_files_skips_tabs_that_must_not_change() {
+    let files = TempFiles::new();
+    let binary = files.add("binary", b"\x00\x01\x02");
+
+    let mut tab = Tab::new(TextFile::from_content(PathBuf::from("test.txt"), "text\n"));
+    assert!(tab.accepts_replace());
+
+    tab.set_read_only(true);
+    assert!(!tab.accepts_replace());
+
+    tab.set_read_only(false);
+    tab.load = Load::Loading { loaded: 0, size: 1 };
+    assert!(!tab.accepts_replace());
+
+    // the bytes are not locked, but the text standing in for them is no place for a replace
+    let mut tabs = Tabs::new();
+    tabs.open_tab(binary);
+    let tab = tabs.get_current_tab().unwrap();
+    assert!(!tab.is_read_only());
+    assert!(!tab.accepts_replace());
+}
+
+#[test]
+fn elevated_saves_clear_the_unsaved_state() {
+    let mut text = TextFile::from_content(PathBuf::from("test.txt"), "\n");
+    text.insert_char('a');
+    assert!(text.dirty_changes.is_some());
+
+    let mut tab = Tab::new(text);
+    assert_eq!(tab.content(), b"a\n");
+
+    tab.mark_saved();
+    assert_eq!(tab.file.dirty_changes, None);
+}
+
+#[cfg(unix)]
+#[test]
+fn private_files_are_only_readable_by_the_user() {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    let files = TempFiles::new();
+    let path = files.add("private", b"old\n");
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+    write_private(&path, b"secret\n").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "secret\n");
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // a link put in place of the file is not followed
+    let link = path.with_extension("link");
+    symlink(&path, &link).unwrap();
+    assert!(write_private(&link, b"other\n").is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "secret\n");
+}
@@ -0,0 +1,74 @@
+use notepad_minus_minus::models::search::find_char_ranges;
+
+mod common;
+
+use common::file;
+
+#[test]
+fn matches_do_not_overlap() {
+    assert_eq!(
+        find_char_ranges("aaaaa".chars(), "aa", true),
+        [(0, 2), (2, 4)]
+    );
+}
+
+#[test]
+fn chars_lowercasing_to_several_match_as_a_whole() {
+    // `İ` lowercases to `i` and a combining dot above
+    let haystack = "İx i\u{307}x ix";
+
+    assert_eq!(
+        find_char_ranges(haystack.chars(), "İ", false),
+        [(0, 1), (3, 5)]
+    );
+    assert_eq!(find_char_ranges(haystack.chars(), "ix", false), [(7, 9)]);
+    assert_eq!(find_char_ranges(haystack.chars(), "İ", true), [(0, 1)]);
+}
+
+#[test]
+fn cached_matches_follow_edits() {
+    for needle in ["ab", "aa", "a\nb"] {
+        let mut text = file("aaaa baab\nAaB ab\nb");
+        text.find_all(needle, false);
+
+        // a fixed pseudo random walk of inserts and removals all over the text
+        let mut seed = 7usize;
+        let mut next = |max: usize| {
+            seed = (seed * 1103515245 + 12345) % (1 << 31);
+            seed % max
+        };
+
+        for _ in 0..300 {
+            let len = text.rope.len_chars();
+            text.char_idx = next(len);
+
+            match next(3) {
+                0 => {
+                    let end = (text.char_idx + 1 + next(3)).min(len - 1);
+                    text.select_range(text.char_idx..end);
+                    text.delete(false);
+                }
+                _ => {
+                    let s = ["a", "b", "ab", "\n", "A", "aa"][next(6)];
+                    text.insert_string(s.to_string());
+                }
+            }
+
+            assert_eq!(
+                text.find_all(needle, false),
+                find_char_ranges(text.rope.chars(), needle, false),
+                "{needle:?} in {:?}",
+                text.to_string()
+            );
+        }
+    }
+}
+
+#[test]
+fn matches_in_a_range_include_the_ones_reaching_into_it() {
+    let text = file("ab ab ab ab\n");
+
+    assert_eq!(text.find_in("ab", true, 4..7), [(3, 5), (6, 8)]);
+    assert_eq!(text.find_in("ab", true, 2..3), []);
+    assert_eq!(text.first_match_in("ab", true, 5..12), Some((6, 8)));
+}
@@ -0,0 +1,217 @@
+use std::path::PathBuf;
+
+use notepad_minus_minus::models::text::TextFile;
+
+mod common;
+
+use common::file;
+
+// selects with shift and the arrows, the way the editor does it
+fn shift_select(text: &mut TextFile, from: usize, chars: usize) {
+    text.char_idx = from;
+    text.clear_selection();
+
+    for _ in 0..chars {
+        let old_idx = text.char_idx;
+        text.caret_move_right(false);
+        text.set_selection(true, old_idx);
+    }
+}
+
+#[test]
+fn selection_is_half_open() {
+    let mut text = file("hello world\n");
+    shift_select(&mut text, 0, 5);
+
+    assert_eq!(text.selection, Some((0, 5)));
+    assert_eq!(text.selection_range(), Some(0..5));
+    assert_eq!(text.get_selection().as_deref(), Some("hello"));
+}
+
+#[test]
+fn selection_backwards_covers_the_same_chars() {
+    let mut text = file("hello world\n");
+    text.select_to(11, 6);
+
+    assert_eq!(text.char_idx, 6);
+    assert_eq!(text.selection_range(), Some(6..11));
+    assert_eq!(text.get_selection().as_deref(), Some("world"));
+}
+
+#[test]
+fn empty_selection_is_none() {
+    let mut text = file("hello\n");
+
+    text.select_to(2, 2);
+    assert_eq!(text.selection, None);
+
+    // moving left at the start of the file selects nothing
+    text.char_idx = 0;
+    text.caret_move_left(false);
+    text.set_selection(true, 0);
+    assert_eq!(text.selection, None);
+}
+
+#[test]
+fn delete_removes_what_copy_copies() {
+    let mut text = file("hello world\n");
+    shift_select(&mut text, 6, 5);
+
+    let copied = text.get_selection().unwrap();
+    text.delete_selection();
+
+    assert_eq!(copied, "world");
+    assert_eq!(text.to_string(), "hello \n");
+    assert_eq!(text.char_idx, 6);
+    assert_eq!(text.selection, None);
+}
+
+#[test]
+fn typing_replaces_the_selection() {
+    let mut text = file("hello world\n");
+    shift_select(&mut text, 0, 5);
+    text.insert_char('J');
+
+    assert_eq!(text.to_string(), "J world\n");
+    assert_eq!(text.char_idx, 1);
+}
+
+#[test]
+fn copy_takes_the_selection() {
+    let mut text = file("one\ntwo\n");
+    shift_select(&mut text, 1, 4);
+
+    let entry = text.copy();
+
+    assert_eq!(entry.text, "ne\nt");
+    assert!(!entry.linewise);
+    assert_eq!(text.to_string(), "one\ntwo\n");
+}
+
+#[test]
+fn copy_without_selection_takes_the_line() {
+    let mut text = file("one\ntwo\nthree\n");
+    text.char_idx = 5;
+
+    let entry = text.copy();
+
+    assert_eq!(entry.text, "two\n");
+    assert!(entry.linewise);
+    assert_eq!(text.to_string(), "one\ntwo\nthree\n");
+}
+
+#[test]
+fn cut_takes_the_selection() {
+    let mut text = file("one\ntwo\n");
+    shift_select(&mut text, 1, 4);
+
+    let entry = text.cut();
+
+    assert_eq!(entry.text, "ne\nt");
+    assert!(!entry.linewise);
+    assert_eq!(text.to_string(), "owo\n");
+    assert_eq!(text.char_idx, 1);
+}
+
+#[test]
+fn cut_without_selection_takes_the_line() {
+    let mut text = file("one\ntwo\nthree\n");
+    text.char_idx = 5;
+
+    let entry = text.cut();
+
+    assert_eq!(entry.text, "two\n");
+    assert!(entry.linewise);
+    assert_eq!(text.to_string(), "one\nthree\n");
+}
+
+#[test]
+fn cut_is_a_single_undo_step() {
+    let mut text = file("one\ntwo\n");
+    shift_select(&mut text, 0, 3);
+
+    text.cut();
+    text.undo_event();
+
+    assert_eq!(text.to_string(), "one\ntwo\n");
+}
+
+#[test]
+fn cut_line_pastes_back_above_the_caret_line() {
+    let mut text = file("one\ntwo\nthree\n");
+    text.char_idx = 1;
+
+    let entry = text.cut();
+    text.char_idx = 6;
+    text.paste(entry.text, entry.linewise);
+
+    assert_eq!(text.to_string(), "two\none\nthree\n");
+    assert_eq!(text.char_idx, 10);
+}
+
+#[test]
+fn linewise_paste_over_a_selection_replaces_it() {
+    let mut text = file("one\ntwo\n");
+    shift_select(&mut text, 0, 3);
+    text.paste("six\n".to_string(), true);
+
+    assert_eq!(text.to_string(), "six\n\ntwo\n");
+}
+
+#[test]
+fn charwise_paste_goes_in_at_the_caret() {
+    let mut text = file("one\n");
+    text.char_idx = 1;
+    text.paste("xy".to_string(), false);
+
+    assert_eq!(text.to_string(), "oxyne\n");
+    assert_eq!(text.char_idx, 3);
+}
+
+#[test]
+fn found_text_is_selected_exactly() {
+    let mut text = file("a needle in a haystack\n");
+
+    assert_eq!(
+        text.find_and_select(0, "needle".to_string(), false, true),
+        Some(2)
+    );
+    assert_eq!(text.get_selection().as_deref(), Some("needle"));
+
+    text.delete_selection();
+    assert_eq!(text.to_string(), "a  in a haystack\n");
+}
+
+#[test]
+fn found_text_is_selected_exactly_backwards() {
+    let mut text = file("a needle in a haystack\n");
+
+    assert_eq!(
+        text.find_and_select(21, "a".to_string(), true, true),
+        Some(19)
+    );
+    assert_eq!(text.get_selection().as_deref(), Some("a"));
+}
+
+#[test]
+fn word_and_line_selections() {
+    let mut text = file("one two\nthree\n");
+
+    text.select_word_at(5);
+    assert_eq!(text.get_selection().as_deref(), Some("two"));
+    assert_eq!(text.char_idx, 7);
+
+    text.select_line_at(9);
+    assert_eq!(text.get_selection().as_deref(), Some("three\n"));
+}
+
+#[test]
+fn text_inserted_at_the_selection_end_stays_out_of_it() {
+    let mut text = TextFile::from_content(PathBuf::from("test.rs"), "foo(1);\n");
+    shift_select(&mut text, 4, 1);
+
+    text.toggle_block_comment();
+
+    assert_eq!(text.to_string(), "foo(/* 1 */);\n");
+    assert_eq!(text.get_selection().as_deref(), Some("1"));
+}
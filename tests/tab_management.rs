@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use notepad_minus_minus::models::tabs::{distinct_parents, Tabs};
+
+mod common;
+
+use common::TempFiles;
+
+fn temp_files(files: &TempFiles, count: usize) -> Vec<PathBuf> {
+    (0..count)
+        .map(|i| files.add(&format!("{i}.txt"), "text\n"))
+        .collect()
+}
+
+fn open_all(paths: &[PathBuf]) -> Tabs {
+    let mut tabs = Tabs::new();
+    for path in paths {
+        tabs.open_tab(path.clone());
+    }
+    tabs
+}
+
+fn pick(paths: &[PathBuf], order: &[usize]) -> Vec<PathBuf> {
+    order.iter().map(|i| paths[*i].clone()).collect()
+}
+
+#[test]
+fn dragged_tabs_take_the_place_they_are_dropped_on() {
+    let files = TempFiles::new();
+    let paths = temp_files(&files, 3);
+    let mut tabs = open_all(&paths);
+    let pane = tabs.focused;
+
+    tabs.start_drag(pane, paths[0].clone());
+    tabs.drop_tab(pane, Some(2));
+    assert_eq!(tabs.panes[0].tabs, pick(&paths, &[1, 2, 0]));
+
+    tabs.reorder_tab(pane, &paths[0], 0);
+    assert_eq!(tabs.panes[0].tabs, paths);
+}
+
+#[test]
+fn pinned_tabs_stay_left() {
+    let files = TempFiles::new();
+    let paths = temp_files(&files, 3);
+    let mut tabs = open_all(&paths);
+    let pane = tabs.focused;
+
+    tabs.toggle_pin(pane, &paths[2]);
+    assert_eq!(tabs.panes[0].tabs, pick(&paths, &[2, 0, 1]));
+    assert!(tabs.panes[0].is_pinned(&paths[2]));
+
+    // unpinned tabs can't be moved in front of pinned ones
+    tabs.reorder_tab(pane, &paths[1], 0);
+    assert_eq!(tabs.panes[0].tabs, pick(&paths, &[2, 1, 0]));
+
+    tabs.toggle_pin(pane, &paths[2]);
+    assert_eq!(tabs.panes[0].pinned, 0);
+    assert_eq!(tabs.panes[0].tabs[0], paths[2]);
+}
+
+#[test]
+fn close_others_keeps_pinned_tabs() {
+    let files = TempFiles::new();
+    let paths = temp_files(&files, 4);
+    let mut tabs = open_all(&paths);
+    let pane = tabs.focused;
+
+    tabs.toggle_pin(pane, &paths[0]);
+    tabs.close_others(pane, &paths[2]);
+
+    assert_eq!(tabs.panes[0].tabs, pick(&paths, &[0, 2]));
+    assert_eq!(tabs.opened_tabs.len(), 2);
+    assert_eq!(tabs.current_file.as_ref(), Some(&paths[2]));
+}
+
+#[test]
+fn close_to_the_right() {
+    let files = TempFiles::new();
+    let paths = temp_files(&files, 4);
+    let mut tabs = open_all(&paths);
+    let pane = tabs.focused;
+
+    tabs.close_to_the_right(pane, &paths[1]);
+
+    assert_eq!(tabs.panes[0].tabs, pick(&paths, &[0, 1]));
+    assert_eq!(tabs.current_file.as_ref(), Some(&paths[1]));
+}
+
+#[test]
+fn ctrl_tab_goes_back_through_recent_files() {
+    let files = TempFiles::new();
+    let paths = temp_files(&files, 3);
+    let mut tabs = open_all(&paths);
+
+    // held ctrl goes further back with every press
+    tabs.cycle_recent(false);
+    assert_eq!(tabs.current_file.as_ref(), Some(&paths[1]));
+    tabs.cycle_recent(false);
+    assert_eq!(tabs.current_file.as_ref(), Some(&paths[0]));
+    tabs.finish_cycle();
+
+    assert_eq!(tabs.panes[0].recent, pick(&paths, &[0, 2, 1]));
+
+    // a single press switches between the last two
+    tabs.cycle_recent(false);
+    tabs.finish_cycle();
+    assert_eq!(tabs.current_file.as_ref(), Some(&paths[2]));
+}
+
+#[test]
+fn same_names_show_their_parents() {
+    let paths = [
+        PathBuf::from("/project/src/views/mod.rs"),
+        PathBuf::from("/project/src/models/mod.rs"),
+        PathBuf::from("/other/src/models/mod.rs"),
+        PathBuf::from("/project/src/main.rs"),
+    ];
+
+    assert_eq!(
+        distinct_parents(&paths),
+        [
+            Some("views".to_string()),
+            Some("project/src/models".to_string()),
+            Some("other/src/models".to_string()),
+            None,
+        ]
+    );
+}
@@ -0,0 +1,101 @@
+use notepad_minus_minus::models::transform::Transform;
+
+#[test]
+fn identifiers_split_at_separators_and_case_changes() {
+    let cases = [
+        ("parseHTTPResponse", "parse_http_response"),
+        ("ParseHttpResponse", "parse_http_response"),
+        ("parse-http response", "parse_http_response"),
+        ("utf8Decoder", "utf8_decoder"),
+        ("version2Beta", "version2_beta"),
+        ("__already_snake__", "already_snake"),
+        ("ABC", "abc"),
+    ];
+
+    for (text, snake) in cases {
+        assert_eq!(Transform::Snake.apply(text).unwrap(), snake, "{text:?}");
+    }
+    assert_eq!(
+        Transform::Camel.apply("parse_HTTP_response").unwrap(),
+        "parseHttpResponse"
+    );
+    assert_eq!(
+        Transform::Kebab.apply("ParseHTTPResponse").unwrap(),
+        "parse-http-response"
+    );
+}
+
+#[test]
+fn identifier_cases_keep_the_whitespace_of_each_line() {
+    assert_eq!(
+        Transform::Snake.apply("  fooBar\n\tbazQux  \n").unwrap(),
+        "  foo_bar\n\tbaz_qux  \n"
+    );
+}
+
+#[test]
+fn url_decoding() {
+    assert_eq!(
+        Transform::UrlDecode.apply("a%20b+c%2Fd%C3%BC").unwrap(),
+        "a b c/dü"
+    );
+    assert_eq!(
+        Transform::UrlEncode.apply("a b/ü~").unwrap(),
+        "a%20b%2F%C3%BC~"
+    );
+
+    for invalid in ["%", "%2", "%zz", "%+f", "%-1"] {
+        assert!(Transform::UrlDecode.apply(invalid).is_err(), "{invalid:?}");
+    }
+    // a lone continuation byte is not text
+    assert!(Transform::UrlDecode.apply("%80").is_err());
+}
+
+#[test]
+fn hex_decoding() {
+    assert_eq!(
+        Transform::HexDecode.apply("68 65\n6C6c 6f").unwrap(),
+        "hello"
+    );
+    assert_eq!(Transform::HexEncode.apply("hi!").unwrap(), "686921");
+
+    for invalid in ["6", "+f", "-1", "0x41", "zz"] {
+        assert!(Transform::HexDecode.apply(invalid).is_err(), "{invalid:?}");
+    }
+}
+
+#[test]
+fn xml_unescaping() {
+    assert_eq!(
+        Transform::XmlUnescape
+            .apply("&lt;a href=&quot;x&quot;&gt;&amp;&apos;&#65;&#x42;&#X43;")
+            .unwrap(),
+        "<a href=\"x\">&'ABC"
+    );
+
+    // unknown or broken references are left alone
+    for kept in [
+        "&nbsp;", "&#;", "&#x;", "&#+65;", "&#x+41;", "&#xD800;", "a & b",
+    ] {
+        assert_eq!(Transform::XmlUnescape.apply(kept).unwrap(), kept);
+    }
+}
+
+#[test]
+fn json_unescaping() {
+    assert_eq!(
+        Transform::JsonUnescape.apply(r#"a\"b\\c\nü\t"#).unwrap(),
+        "a\"b\\c\nü\t"
+    );
+    assert_eq!(
+        Transform::JsonEscape.apply("a\"b\\c\nü\t").unwrap(),
+        r#"a\"b\\c\nü\t"#
+    );
+
+    for invalid in [r#"a"b"#, r"\x41", r"\u12"] {
+        assert!(
+            Transform::JsonUnescape.apply(invalid).is_err(),
+            "{invalid:?}"
+        );
+    }
+}
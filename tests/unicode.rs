@@ -0,0 +1,235 @@
+use notepad_minus_minus::models::{
+    historyevent::HistoryEvent,
+    indentation::Indentation,
+    unicode::{display_width, grapheme_boundaries},
+};
+
+mod common;
+
+use common::file;
+
+// e followed by a combining acute accent
+const E_ACUTE: &str = "e\u{301}";
+// a family emoji, four people joined by zero width joiners
+const FAMILY: &str = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200D}\u{1F466}";
+
+#[test]
+fn graphemes_are_found_in_chars() {
+    assert_eq!(grapheme_boundaries(&format!("a{E_ACUTE}b")), [0, 1, 3, 4]);
+    assert_eq!(grapheme_boundaries(FAMILY), [0, 7]);
+    assert_eq!(grapheme_boundaries(""), [0]);
+}
+
+#[test]
+fn caret_steps_over_combining_marks() {
+    let mut text = file(&format!("a{E_ACUTE}b\n"));
+    text.char_idx = 1;
+
+    text.caret_move_right(false);
+    assert_eq!(text.char_idx, 3);
+
+    text.caret_move_left(false);
+    assert_eq!(text.char_idx, 1);
+}
+
+#[test]
+fn caret_steps_over_emoji_sequences() {
+    let mut text = file(&format!("{FAMILY}!\n"));
+
+    text.caret_move_right(false);
+    assert_eq!(text.char_idx, 7);
+
+    text.caret_move_left(false);
+    assert_eq!(text.char_idx, 0);
+}
+
+#[test]
+fn backspace_removes_a_whole_grapheme() {
+    let mut text = file(&format!("x{FAMILY}\n"));
+    text.char_idx = 8;
+
+    text.backspace(false);
+
+    assert_eq!(text.to_string(), "x\n");
+    assert_eq!(text.char_idx, 1);
+
+    text.undo_event();
+    assert_eq!(text.to_string(), format!("x{FAMILY}\n"));
+}
+
+#[test]
+fn delete_removes_a_whole_grapheme() {
+    let mut text = file(&format!("{E_ACUTE}t\n"));
+
+    text.delete(false);
+
+    assert_eq!(text.to_string(), "t\n");
+    assert_eq!(text.char_idx, 0);
+}
+
+#[test]
+fn word_moves_use_unicode_words() {
+    let mut text = file("größe straße\n");
+
+    text.caret_move_right(true);
+    assert_eq!(text.char_idx, 5);
+
+    text.caret_move_right(true);
+    assert_eq!(text.char_idx, 12);
+
+    text.caret_move_left(true);
+    assert_eq!(text.char_idx, 6);
+
+    text.caret_move_left(true);
+    assert_eq!(text.char_idx, 0);
+}
+
+#[test]
+fn word_moves_pass_symbols_one_at_a_time() {
+    let mut text = file("f(x)\n");
+
+    text.caret_move_right(true);
+    assert_eq!(text.char_idx, 1);
+
+    text.caret_move_right(true);
+    assert_eq!(text.char_idx, 2);
+}
+
+#[test]
+fn word_moves_cross_lines() {
+    let mut text = file("ab\n  cd\n");
+    text.char_idx = 2;
+
+    text.caret_move_right(true);
+    assert_eq!(text.char_idx, 7);
+
+    text.char_idx = 3;
+    text.caret_move_left(true);
+    assert_eq!(text.char_idx, 2);
+}
+
+#[test]
+fn ctrl_backspace_removes_a_unicode_word() {
+    let mut text = file("über café\n");
+    text.char_idx = 9;
+
+    text.backspace(true);
+
+    assert_eq!(text.to_string(), "über \n");
+}
+
+#[test]
+fn double_click_selects_a_unicode_word() {
+    let mut text = file("naïve café\n");
+
+    text.select_word_at(2);
+    assert_eq!(text.get_selection().as_deref(), Some("naïve"));
+
+    text.select_word_at(8);
+    assert_eq!(text.get_selection().as_deref(), Some("café"));
+}
+
+#[test]
+fn strings_are_undone_by_chars_not_bytes() {
+    let mut text = file("\n");
+
+    text.apply_new_event(HistoryEvent::AddString("ωmega".to_string(), 0));
+    assert_eq!(text.char_idx, 5);
+
+    text.undo_event();
+    assert_eq!(text.to_string(), "\n");
+    assert_eq!(text.char_idx, 0);
+
+    text.redo_event();
+    assert_eq!(text.to_string(), "ωmega\n");
+}
+
+#[test]
+fn typing_after_non_ascii_text_merges_into_one_undo_step() {
+    let mut text = file("\n");
+
+    for c in "ñandú".chars() {
+        text.insert_char(c);
+    }
+    assert_eq!(text.event_history.len(), 1);
+
+    text.undo_event();
+    assert_eq!(text.to_string(), "\n");
+}
+
+#[test]
+fn search_after_non_ascii_text_selects_the_match() {
+    let mut text = file("ü ü needle\n");
+
+    assert_eq!(
+        text.find_and_select(0, "needle".to_string(), false, true),
+        Some(4)
+    );
+    assert_eq!(text.get_selection().as_deref(), Some("needle"));
+
+    assert_eq!(
+        text.find_and_select(9, "Ü".to_string(), true, false),
+        Some(2)
+    );
+    assert_eq!(text.get_selection().as_deref(), Some("ü"));
+}
+
+#[test]
+fn vertical_moves_do_not_land_inside_a_grapheme() {
+    let mut text = file(&format!("abc\n{E_ACUTE}x\n"));
+    text.char_idx = 1;
+
+    text.caret_move_down();
+
+    assert_eq!(text.char_idx, 4);
+}
+
+#[test]
+fn transpose_swaps_graphemes() {
+    let mut text = file(&format!("a{E_ACUTE}\n"));
+    text.char_idx = 3;
+
+    text.transpose_chars();
+
+    assert_eq!(text.to_string(), format!("{E_ACUTE}a\n"));
+}
+
+#[test]
+fn transpose_swaps_the_words_ctrl_arrow_stops_at() {
+    let mut text = file("don't stop, μέρα\n");
+    text.char_idx = 2;
+
+    text.transpose_words();
+    assert_eq!(text.to_string(), "stop don't, μέρα\n");
+    assert_eq!(text.char_idx, 10);
+
+    text.transpose_words();
+    assert_eq!(text.to_string(), "stop μέρα, don't\n");
+}
+
+#[test]
+fn display_columns_count_cells() {
+    assert_eq!(display_width("abc", 4), 3);
+    assert_eq!(display_width("日本", 4), 4);
+    assert_eq!(display_width(E_ACUTE, 4), 1);
+    assert_eq!(display_width("\tx", 4), 5);
+    assert_eq!(display_width("ab\t", 4), 4);
+
+    let mut text = file("日本語\n");
+    text.char_idx = 2;
+    assert_eq!(text.display_col(), 4);
+}
+
+#[test]
+fn tab_stops_count_cells() {
+    let mut text = file("日本\n\tx\n");
+    text.indentation = Indentation::Spaces(4);
+
+    text.char_idx = 2;
+    text.insert_tab();
+    assert_eq!(text.to_string(), "日本    \n\tx\n");
+
+    text.char_idx = 9;
+    text.insert_tab();
+    assert_eq!(text.to_string(), "日本    \n\tx   \n");
+}
@@ -0,0 +1,263 @@
+use std::{collections::HashMap, fs, sync::atomic::AtomicBool};
+
+use notepad_minus_minus::models::workspace_search::{
+    glob_match, replace_in_content, replace_preview, write_atomically, DiffLine, LineMatch,
+    WorkspaceQuery,
+};
+
+fn line_match(ln: usize, col: usize, text: &str) -> LineMatch {
+    LineMatch {
+        ln,
+        col,
+        text: text.to_string(),
+        preview: String::new(),
+        preview_col: col,
+    }
+}
+
+#[test]
+fn preview_shows_changed_lines_with_context() {
+    let content = "one\ntwo\nthree\nfour\nfive\nsix\n";
+    let matches = [line_match(0, 0, "one"), line_match(4, 0, "five")];
+
+    assert_eq!(
+        replace_preview(content, &matches, "1\n1"),
+        [
+            DiffLine::Removed(0, "one".to_string()),
+            DiffLine::Added(0, "1".to_string()),
+            DiffLine::Added(1, "1".to_string()),
+            DiffLine::Context(1, "two".to_string()),
+            DiffLine::Skipped,
+            DiffLine::Context(3, "four".to_string()),
+            DiffLine::Removed(4, "five".to_string()),
+            DiffLine::Added(5, "1".to_string()),
+            DiffLine::Added(6, "1".to_string()),
+            DiffLine::Context(5, "six".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn unsaved_buffers_are_searched_instead_of_the_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let saved = dir.path().join("saved.txt");
+    let edited = dir.path().join("edited.txt");
+    fs::write(&saved, "needle\n").unwrap();
+    fs::write(&edited, "nothing\n").unwrap();
+
+    let buffers = HashMap::from([(edited.clone(), "a\nthe needle\n".to_string())]);
+    let query = WorkspaceQuery::new("needle".to_string(), true, "", "");
+    let results = query.run(dir.path(), &buffers, &AtomicBool::new(false));
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].path, edited);
+    assert_eq!(
+        results[0].matches,
+        [LineMatch {
+            preview: "the needle".to_string(),
+            ..line_match(1, 4, "needle")
+        }]
+    );
+    assert_eq!(results[1].path, saved);
+}
+
+#[test]
+fn previews_are_centred_on_the_match() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("long.txt");
+    let line = format!("{}needle{}", "a".repeat(300), "b".repeat(300));
+    fs::write(&path, format!("{line}\n")).unwrap();
+
+    let query = WorkspaceQuery::new("needle".to_string(), true, "", "");
+    let results = query.run(dir.path(), &HashMap::new(), &AtomicBool::new(false));
+    let found = &results[0].matches[0];
+
+    assert_eq!(found.col, 300);
+    assert_eq!(found.preview.chars().count(), 120);
+    assert_eq!(found.preview_col, 57);
+    assert_eq!(&found.preview[57..63], "needle");
+}
+
+#[test]
+fn nested_directories_keep_their_parents_ignore_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("a").join("b");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(dir.path().join(".gitignore"), "*.log\n").unwrap();
+    fs::write(dir.path().join("a").join(".ignore"), "skip.txt\n").unwrap();
+    for name in ["keep.txt", "skip.txt", "out.log"] {
+        fs::write(nested.join(name), "needle\n").unwrap();
+    }
+
+    let query = WorkspaceQuery::new("needle".to_string(), true, "", "");
+    let results = query.run(dir.path(), &HashMap::new(), &AtomicBool::new(false));
+
+    assert_eq!(
+        results.iter().map(|f| f.path.clone()).collect::<Vec<_>>(),
+        [nested.join("keep.txt")]
+    );
+}
+
+#[test]
+fn globs_match_within_and_across_directories() {
+    let cases = [
+        ("*.rs", "main.rs", true),
+        ("*.rs", "src/main.rs", false),
+        ("src/*.rs", "src/main.rs", true),
+        ("src/*.rs", "src/models/text.rs", false),
+        ("src/**/*.rs", "src/main.rs", true),
+        ("src/**/*.rs", "src/models/text.rs", true),
+        ("**/target", "target", true),
+        ("**/target", "a/b/target", true),
+        ("**/target", "a/btarget", false),
+        ("src/**", "src/a/b", true),
+        ("?.txt", "a.txt", true),
+        ("?.txt", "ab.txt", false),
+        ("a?b", "a/b", false),
+        ("", "", true),
+        ("", "a", false),
+    ];
+
+    for (pattern, text, matched) in cases {
+        assert_eq!(glob_match(pattern, text), matched, "{pattern:?} {text:?}");
+    }
+}
+
+#[test]
+fn later_ignore_rules_win() {
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("nested");
+    fs::create_dir_all(nested.join("build")).unwrap();
+    fs::create_dir_all(dir.path().join("build")).unwrap();
+    fs::write(
+        dir.path().join(".gitignore"),
+        "*.log\n!keep.log\n/top.txt\nbuild/\n\\!bang.txt\n# !comment.log\n",
+    )
+    .unwrap();
+    // a nested ignore file overrides its parents, and `.ignore` overrides `.gitignore`
+    fs::write(nested.join(".gitignore"), "!out.log\n").unwrap();
+    fs::write(nested.join(".ignore"), "out.log\n").unwrap();
+
+    let files = [
+        "out.log",
+        "keep.log",
+        "top.txt",
+        "!bang.txt",
+        "comment.log",
+        "build/a.txt",
+        "nested/out.log",
+        "nested/keep.log",
+        "nested/top.txt",
+        "nested/build/a.txt",
+    ];
+    for file in files {
+        fs::write(dir.path().join(file), "needle\n").unwrap();
+    }
+    // a file called like an ignored directory is kept
+    fs::write(nested.join("build.txt"), "needle\n").unwrap();
+
+    let query = WorkspaceQuery::new("needle".to_string(), true, "", "");
+    let results = query.run(dir.path(), &HashMap::new(), &AtomicBool::new(false));
+
+    assert_eq!(
+        results.iter().map(|f| f.path.clone()).collect::<Vec<_>>(),
+        [
+            "keep.log",
+            "nested/build.txt",
+            "nested/keep.log",
+            "nested/top.txt"
+        ]
+        .map(|f| dir.path().join(f))
+    );
+}
+
+#[test]
+fn replaced_matches_keep_the_rest_of_the_line() {
+    let content = "ab ab\nüab ab\nxx\nab";
+    let matches = [
+        line_match(0, 0, "ab"),
+        line_match(0, 3, "ab"),
+        // columns count chars, not bytes
+        line_match(1, 1, "ab"),
+        line_match(1, 4, "ab"),
+        // stale, the line was changed since the search
+        line_match(2, 0, "ab"),
+        line_match(3, 0, "ab"),
+    ];
+
+    assert_eq!(
+        replace_in_content(content, &matches, "long"),
+        ("long long\nülong long\nxx\nlong".to_string(), 5)
+    );
+}
+
+#[test]
+fn overlapping_and_unordered_matches_are_replaced_once() {
+    let content = "aaaa\n";
+    let matches = [
+        line_match(0, 2, "aa"),
+        line_match(0, 1, "aa"),
+        line_match(0, 0, "aa"),
+        // past the end of the line
+        line_match(0, 3, "aa"),
+    ];
+
+    assert_eq!(
+        replace_in_content(content, &matches, "b"),
+        ("bb\n".to_string(), 2)
+    );
+}
+
+#[test]
+fn atomic_writes_leave_other_files_alone() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("target.txt");
+    // the name the temp file used to have
+    let neighbour = dir.path().join(".target.txt.tmp");
+    fs::write(&path, "old\n").unwrap();
+    fs::write(&neighbour, "mine\n").unwrap();
+
+    write_atomically(&path, "new\n").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "new\n");
+    assert_eq!(fs::read_to_string(&neighbour).unwrap(), "mine\n");
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn atomic_writes_keep_the_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("script.sh");
+    fs::write(&path, "old\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+    write_atomically(&path, b"new\n").unwrap();
+
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o755);
+}
+
+#[cfg(unix)]
+#[test]
+fn linked_directories_are_not_walked() {
+    use std::os::unix::fs::symlink;
+
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("nested");
+    fs::create_dir(&nested).unwrap();
+    fs::write(nested.join("found.txt"), "needle\n").unwrap();
+    // a loop back to the root, and a link to a file next to it
+    symlink(dir.path(), nested.join("loop")).unwrap();
+    symlink(nested.join("found.txt"), nested.join("linked.txt")).unwrap();
+
+    let query = WorkspaceQuery::new("needle".to_string(), true, "", "");
+    let results = query.run(dir.path(), &HashMap::new(), &AtomicBool::new(false));
+
+    assert_eq!(
+        results.iter().map(|f| f.path.clone()).collect::<Vec<_>>(),
+        [nested.join("found.txt"), nested.join("linked.txt")]
+    );
+}
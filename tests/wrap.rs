@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use notepad_minus_minus::models::{
+    text::TextFile,
+    wrap::{parse_wrap_column, WrapMode},
+};
+
+#[test]
+fn wrapped_lines_follow_edits() {
+    let mut text = TextFile::from_content(PathBuf::from("test.txt"), "short\nalso short\n");
+    text.wrap_width = Some(6);
+
+    assert_eq!(text.wrapped_line(0).rows(), 1);
+    assert_eq!(text.wrapped_line(1).rows(), 2);
+
+    text.insert_string("a much longer line\n".to_string());
+
+    assert_eq!(text.wrapped_line(0).rows(), 4);
+    assert_eq!(text.wrapped_line(1).rows(), 1);
+    assert_eq!(text.wrapped_line(2).rows(), 2);
+
+    // every pane keeps the rows of its own width
+    text.wrap_width = None;
+    assert_eq!(text.wrapped_line(0).rows(), 1);
+}
+
+#[test]
+fn wrap_modes_cycle_through_the_chosen_column() {
+    let modes = [WrapMode::Off, WrapMode::Viewport, WrapMode::Column(100)];
+
+    for (i, mode) in modes.iter().enumerate() {
+        assert_eq!(mode.next(100), modes[(i + 1) % modes.len()]);
+    }
+    assert_eq!(WrapMode::Column(100).width(40), Some(100));
+    assert_eq!(WrapMode::Viewport.width(40), Some(40));
+}
+
+#[test]
+fn wrap_columns_are_whole_numbers_in_range() {
+    assert_eq!(parse_wrap_column(" 120\n"), Some(120));
+    for invalid in ["", "abc", "-80", "8", "0", "100000", "80.5"] {
+        assert_eq!(parse_wrap_column(invalid), None, "{invalid:?}");
+    }
+}